@@ -12,7 +12,7 @@ use broker::{
     encode::pg_book::BookMapper,
     encode::xml::Xml,
     postgres::{Build as _, Builder as PostgresBuilder, models::Book},
-    query::combinators::{And, Or},
+    query::query,
     rest::{Build as _, Builder as RestBuilder},
 };
 use std::io::stdin;
@@ -92,10 +92,8 @@ async fn main() {
     }
     println!();
 
-    let query1 = And(
-        Book::author().eq("George Orwell"),
-        Or(Book::title().eq("1984"), Book::title().eq("Animal Farm")),
-    );
+    let query1 =
+        query!(Book: author == "George Orwell" && (title == "1984" || title == "Animal Farm"));
     println!("Defined query: {query1:#?} [1 residue]\nPress ENTER to run.");
 
     drop(stdin().read_line(&mut buf));
@@ -14,8 +14,9 @@ use broker::{
     connector::MemorySource,
     encode::json::Json as BrokerJson,
     encode::xml::Xml,
-    query::Queryable,
-    query::combinators::True,
+    query::combinators::{All, Any as AnyOf, True},
+    query::dynamic::DynQuery,
+    query::{FieldInfo, Query, Queryable, Schema as _, parse},
     rest::{Build as _, Builder as RestBuilder},
 };
 use serde::{Deserialize, Serialize};
@@ -37,7 +38,7 @@ struct AppState {
 /// Incoming JSON payload for `/query`.
 #[derive(Deserialize)]
 struct QueryCondition {
-    /// Field to search by: `author`, `title`, or `isbn`.
+    /// Field to search by: `author`, `title`, or `isbn`. See `/schema`.
     field: String,
     /// Value to match.
     value: String,
@@ -54,6 +55,13 @@ struct QueryRequest {
     operator: String,
     /// Sources to search in. Empty = search all.
     sources: Vec<String>,
+    /// Arbitrary query on books. If present, `conditions` and `operator` are ignored.
+    #[serde(default)]
+    query: Option<DynQuery>,
+    /// Arbitrary query on books in textual form, see [`broker::query::syntax`]. If present,
+    /// `query`, `conditions` and `operator` are ignored.
+    #[serde(default)]
+    filter: Option<String>,
 }
 
 /// Representation of a book.
@@ -165,6 +173,7 @@ async fn main() {
         .route("/books", post(add_book))
         .route("/sources", post(add_source_handler))
         .route("/sources", get(list_sources))
+        .route("/schema", get(schema))
         .layer(cors)
         .with_state(state);
 
@@ -190,22 +199,32 @@ async fn query_handler(
     State(state): State<AppState>,
     AxumJson(payload): AxumJson<QueryRequest>,
 ) -> Result<AxumJson<Vec<SearchResult<Book>>>, StatusCode> {
-    // Get all broker books
+    // Resolve the query before any I/O, so invalid requests are rejected early
+    let query = match (&payload.filter, &payload.query) {
+        (Some(filter), _) => parse::<Book>(filter).map_err(|_err| StatusCode::BAD_REQUEST)?,
+        (None, Some(query)) => query
+            .resolve::<Book>()
+            .map_err(|_err| StatusCode::BAD_REQUEST)?,
+        (None, None) => request_query(&payload),
+    };
+
+    // Get matching broker books, each source translates the query itself
     let mut results = {
         let mut broker = state.broker.lock().await;
         broker
-            .fetch_all_with_source(&True)
+            .fetch_all_with_source(&*query)
             .await
             .unwrap_or_default()
     };
 
-    // Get all SQLite books
+    // Get matching SQLite books. Translating queries to SQL requires the `postgres` feature of
+    // the broker, so the local database is filtered in memory.
     let db_books = sqlx::query_as::<_, Book>("SELECT title, author, isbn FROM books")
         .fetch_all(&state.db)
         .await
         .unwrap_or_default();
 
-    for book in db_books {
+    for book in db_books.into_iter().filter(|book| query.evaluate(book)) {
         results.push(SearchResult {
             item: book,
             source: "Local DB".to_owned(),
@@ -216,61 +235,45 @@ async fn query_handler(
     let mut seen: HashSet<Book> = HashSet::new();
     results.retain(|r| seen.insert(r.item.clone()));
 
-    // Apply filtering locally
+    // Source filtering
     let filtered: Vec<SearchResult<Book>> = results
         .into_iter()
-        .filter(|result| {
-            // Source filtering
-            if !payload.sources.is_empty() && !payload.sources.contains(&result.source) {
-                return false;
-            }
-
-            let book = &result.item;
-
-            match payload.operator.to_lowercase().as_str() {
-                "and" => payload
-                    .conditions
-                    .iter()
-                    .all(|cond| match cond.field.as_str() {
-                        "author" => book
-                            .author
-                            .to_lowercase()
-                            .contains(&cond.value.to_lowercase()),
-                        "title" => book
-                            .title
-                            .to_lowercase()
-                            .contains(&cond.value.to_lowercase()),
-                        "isbn" => book
-                            .isbn
-                            .to_lowercase()
-                            .contains(&cond.value.to_lowercase()),
-                        _ => false,
-                    }),
-                _ => payload
-                    .conditions
-                    .iter()
-                    .any(|cond| match cond.field.as_str() {
-                        "author" => book
-                            .author
-                            .to_lowercase()
-                            .starts_with(&cond.value.to_lowercase()),
-                        "title" => book
-                            .title
-                            .to_lowercase()
-                            .starts_with(&cond.value.to_lowercase()),
-                        "isbn" => book
-                            .isbn
-                            .to_lowercase()
-                            .starts_with(&cond.value.to_lowercase()),
-                        _ => false,
-                    }),
-            }
-        })
+        .filter(|result| payload.sources.is_empty() || payload.sources.contains(&result.source))
         .collect();
 
     Ok(AxumJson(filtered))
 }
 
+/// Constructs the query described by the conditions of a request.
+///
+/// With the `"and"` operator, all fields must contain their values. Otherwise, at least one field
+/// must start with its value. Matching ignores case, and conditions on unknown fields never match.
+fn request_query(payload: &QueryRequest) -> Box<dyn Query<Book> + Send + Sync + '_> {
+    let all = payload.operator.eq_ignore_ascii_case("and");
+    let conditions = payload
+        .conditions
+        .iter()
+        .map(|cond| -> Box<dyn Query<Book> + Send + Sync> {
+            let field = match cond.field.as_str() {
+                "author" => Book::author(),
+                "title" => Book::title(),
+                "isbn" => Book::isbn(),
+                _ => return Box::new(AnyOf::<True>(Vec::new())),
+            };
+            if all {
+                Box::new(field.contains(&cond.value).ignore_case())
+            } else {
+                Box::new(field.starts_with(&cond.value).ignore_case())
+            }
+        });
+
+    if all {
+        Box::new(All(conditions.collect()))
+    } else {
+        Box::new(AnyOf(conditions.collect()))
+    }
+}
+
 /// Handles POST `/books` by inserting a new book into `SQLite DB`.
 async fn add_book(
     State(state): State<AppState>,
@@ -329,3 +332,11 @@ async fn list_sources(State(state): State<AppState>) -> AxumJson<Vec<SourceInfo>
 
     AxumJson(sources)
 }
+
+/// Returns the queryable fields of books.
+///
+/// This endpoint is used by the frontend to populate
+/// the field selection dropdowns.
+async fn schema() -> AxumJson<Vec<FieldInfo>> {
+    AxumJson(Book::fields())
+}
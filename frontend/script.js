@@ -76,6 +76,31 @@ async function search() {
         `Found ${data.length} books`;
 }
 
+// Queryable fields, as listed by the broker.
+let schemaFields = ["author", "title", "isbn"];
+
+function fieldOptions() {
+    return schemaFields
+        .map(name => `<option value="${name}">${name}</option>`)
+        .join("");
+}
+
+async function loadSchema() {
+    const res = await fetch("http://localhost:3000/schema");
+    const fields = await res.json();
+
+    // Conditions are matched as strings.
+    schemaFields = fields.filter(field => field.string).map(field => field.name);
+
+    document.querySelectorAll("#conditions .field").forEach(select => {
+        const selected = select.value;
+        select.innerHTML = fieldOptions();
+        if (schemaFields.includes(selected)) {
+            select.value = selected;
+        }
+    });
+}
+
 function addCondition() {
     const container = document.getElementById("conditions");
 
@@ -87,11 +112,7 @@ function addCondition() {
     div.innerHTML = `
         <span class="condition-operator">${operator}</span>
 
-        <select class="field">
-            <option value="author">Author</option>
-            <option value="title">Title</option>
-            <option value="isbn">ISBN</option>
-        </select>
+        <select class="field">${fieldOptions()}</select>
 
         <input type="text" class="value" placeholder="Enter value" />
 
@@ -211,4 +232,5 @@ async function loadSources() {
 
 window.addEventListener("DOMContentLoaded", () => {
     loadSources();
+    loadSchema();
 });
\ No newline at end of file
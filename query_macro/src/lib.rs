@@ -4,16 +4,44 @@
 //! type-safe [`Field`](broker::query::Field) accessors for each named field
 //! in a struct. These accessors are used to construct queries in a fluent,
 //! compile-time checked way.
+//!
+//! It also provides the `query!` macro, which builds queries from infix
+//! expressions.
 
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{Data, DeriveInput, parse_macro_input};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{ToTokens, quote, quote_spanned};
+use syn::{
+    BinOp, Data, DeriveInput, Error, Expr, ExprBinary, ExprLit, ExprMethodCall, ExprPath,
+    ExprUnary, Fields, Ident, Lit, LitStr, Result, Token, Type, UnOp,
+    ext::IdentExt as _,
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    spanned::Spanned,
+};
 
 /// Derive macro enabling query construction on a struct.
 ///
 /// For each named field in the struct, this macro generates an inherent
 /// method returning a [`Field`](broker::query::Field) describing how to
-/// access that field.
+/// access that field. It also implements
+/// [`dynamic::Queryable`](broker::query::dynamic::Queryable), allowing
+/// fields to be referred to by name in a
+/// [`DynQuery`](broker::query::dynamic::DynQuery), and
+/// [`Schema`](broker::query::Schema), listing the fields at runtime.
+///
+/// # Attributes
+///
+/// Fields accept the following options through `#[queryable(...)]`:
+/// - `rename = "..."`: the name used for translation and dynamic queries,
+///   instead of the name of the field.
+/// - `skip`: no accessor is generated, and the field can not be queried.
+/// - `nested`: the field is itself `Queryable`, and its fields can be
+///   queried using dotted names such as `publisher.name`, which resolve to
+///   [`Field::then`](broker::query::Field::then) chains.
+///
+/// Generic structs are supported, although their accessors require the
+/// struct to be `'static`.
 ///
 /// # Example
 ///
@@ -21,57 +49,529 @@ use syn::{Data, DeriveInput, parse_macro_input};
 /// #[derive(Queryable)]
 /// struct User {
 ///     id: u32,
+///     #[queryable(rename = "display_name")]
 ///     name: String,
+///     #[queryable(skip)]
+///     password: String,
+///     #[queryable(nested)]
+///     address: Address,
 /// }
 ///
 /// // Generated:
 /// // impl User {
 /// //     pub fn id() -> Field<User, u32> { ... }
 /// //     pub fn name() -> Field<User, String> { ... }
+/// //     pub fn address() -> Field<User, Address> { ... }
 /// // }
+/// //
+/// // impl dynamic::Queryable for User { ... }
+/// // impl Schema for User { ... }
 /// ```
 ///
-/// # Panics
+/// # Errors
 ///
-/// This macro panics at compile time if:
-/// - The input type is not a struct
-/// - The struct does not have named fields
-///
-/// These constraints are required to generate valid field accessors.
-#[proc_macro_derive(Queryable)]
+/// Compilation fails if the input type is not a struct with named fields,
+/// or if an attribute is malformed.
+#[proc_macro_derive(Queryable, attributes(queryable))]
 #[inline]
 pub fn derive_queryable(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(|error| error.to_compile_error().into())
+}
+
+/// Build a query from an infix expression on the fields of a `Queryable`
+/// type, expanding into the equivalent
+/// [combinators](broker::query::combinators).
+///
+/// The input is the type, followed by a colon and the query:
+/// - Comparisons `==`, `!=`, `<`, `>`, `<=` and `>=`, with a field on the
+///   left-hand side and any expression on the right-hand side. Values are
+///   borrowed, as with [`Field::eq`](broker::query::Field::eq).
+/// - Method calls on fields, such as `title.starts_with("E").ignore_case()`
+///   or `year.between(&1800, &1900)`, which are forwarded to the
+///   [`Field`](broker::query::Field) methods.
+/// - `!`, `&&`, `||` and `^`, with the usual precedence, as well as
+///   parentheses.
+/// - `true` and `false`.
+///
+/// # Example
+///
+/// ```ignore
+/// let query = query!(
+///     Book: author == "George Orwell" && (title == "1984" || title == "Animal Farm")
+/// );
+///
+/// // Expands to:
+/// let query = And(
+///     Book::author().eq("George Orwell"),
+///     Or(Book::title().eq("1984"), Book::title().eq("Animal Farm")),
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Compilation fails if the query contains other expressions. Unknown
+/// fields are reported at their name, and comparisons to values of the
+/// wrong type at the comparison.
+#[proc_macro]
+#[inline]
+pub fn query(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Input);
+    input
+        .expand()
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
+}
+
+/// The options of a field, given by its `#[queryable(...)]` attributes.
+struct Options<'a> {
+    /// The Rust identifier.
+    ident: &'a Ident,
+    /// The name used for translation.
+    name: String,
+    /// Whether the field is itself `Queryable`.
+    nested: bool,
+}
+
+/// Parse the options of `field`, returning [`None`] if it is skipped.
+///
+/// # Errors
+///
+/// Fails if the field is unnamed or an attribute is malformed.
+fn options(field: &syn::Field) -> Result<Option<Options<'_>>> {
+    let Some(ident) = &field.ident else {
+        return Err(Error::new_spanned(
+            field,
+            "Queryable can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut name = None;
+    let mut skip = false;
+    let mut nested = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("queryable"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                name = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("skip") {
+                skip = true;
+            } else if meta.path.is_ident("nested") {
+                nested = true;
+            } else {
+                return Err(meta.error("expected `rename`, `skip` or `nested`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    if skip && (name.is_some() || nested) {
+        return Err(Error::new_spanned(
+            ident,
+            "`skip` can not be combined with other options",
+        ));
+    }
+
+    Ok((!skip).then(|| Options {
+        ident,
+        name: name.unwrap_or_else(|| ident.unraw().to_string()),
+        nested,
+    }))
+}
+
+/// Generate the body of a resolution method, where `access` builds the expression accessing the
+/// field with the given identifier, and `unknown` the full name of an unknown field.
+fn resolve_body<A: ToTokens>(
+    queried: &[(&syn::Field, Options<'_>)],
+    access: impl Fn(&Ident) -> A,
+    unknown: &impl ToTokens,
+) -> impl ToTokens {
+    let nested: Vec<_> = queried
+        .iter()
+        .filter(|(_, options)| options.nested)
+        .collect();
+    let nested_types = nested.iter().map(|(field, _)| &field.ty);
+    let nested_prefixes = nested
+        .iter()
+        .map(|(_, options)| format!("{}.", options.name));
+    let nested_access = nested.iter().map(|(_, options)| access(options.ident));
+    let names = queried.iter().map(|(_, options)| &options.name);
+    let access = queried.iter().map(|(_, options)| access(options.ident));
+
+    quote! {
+        use ::broker::query::dynamic::{
+            FieldResolver, ResolveSupported as _, ResolveUnsupported as _,
+        };
+
+        #(
+            if let ::std::option::Option::Some(rest) = field.strip_prefix(#nested_prefixes) {
+                return <#nested_types as ::broker::query::dynamic::Queryable>::resolve_nested(
+                    #nested_access,
+                    rest,
+                    query,
+                );
+            }
+        )*
+
+        match field {
+            #(#names => (&FieldResolver(#access, query)).resolve(),)*
+            _ => ::std::result::Result::Err(
+                ::broker::query::dynamic::ResolveError::UnknownField(#unknown),
+            ),
+        }
+    }
+}
+
+/// Generate the body of [`Schema::fields`].
+///
+/// [`Schema::fields`]: broker::query::Schema::fields
+fn schema_body(queried: &[(&syn::Field, Options<'_>)]) -> impl ToTokens {
+    let infos = queried.iter().map(|(field, Options { name, nested, .. })| {
+        let ty = &field.ty;
+        let info = quote! {
+            {
+                let mut info = ::broker::query::FieldInfo::new::<#ty>(#name);
+                info.comparable = (&Probe::<#ty>::new()).comparable();
+                info.string = (&Probe::<#ty>::new()).string();
+                info.optional = (&Probe::<#ty>::new()).optional();
+                info.dynamic = (&Probe::<#ty>::new()).dynamic();
+                info
+            }
+        };
+
+        if *nested {
+            quote! {
+                fields.push(#info);
+                fields.extend(
+                    <#ty as ::broker::query::Schema>::fields()
+                        .into_iter()
+                        .map(|field| field.nested(#name)),
+                );
+            }
+        } else {
+            quote!(fields.push(#info);)
+        }
+    });
+
+    quote! {
+        use ::broker::query::schema::{
+            Comparable as _, Dynamic as _, Optional as _, Probe, Textual as _, Unsupported as _,
+        };
+
+        let mut fields = ::std::vec::Vec::new();
+        #(#infos)*
+        fields
+    }
+}
+
+/// Generate the accessors and the [`dynamic::Queryable`] and [`Schema`] implementations.
+///
+/// # Errors
+///
+/// Fails if the input type is not a struct with named fields, or if an attribute is malformed.
+///
+/// [`dynamic::Queryable`]: broker::query::dynamic::Queryable
+/// [`Schema`]: broker::query::Schema
+fn expand(input: &DeriveInput) -> Result<TokenStream> {
     let struct_name = &input.ident;
 
-    let Data::Struct(data) = input.data else {
-        panic!("Queryable can only be derived for struct types");
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "Queryable can only be derived for struct types",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            &data.fields,
+            "Queryable can only be derived for structs with named fields",
+        ));
     };
 
-    let field_methods = data.fields.iter().map(|field| {
-        let ident = field
-            .ident
-            .as_ref()
-            .expect("Queryable can only be derived for structs with named fields");
-        let name = ident.to_string();
+    let mut queried = Vec::new();
+    for field in &fields.named {
+        if let Some(options) = options(field)? {
+            queried.push((field, options));
+        }
+    }
 
+    let field_methods = queried.iter().map(|(field, Options { ident, name, .. })| {
         let ty = &field.ty;
 
         quote! {
             /// Returns a queryable handle to this field.
-            pub fn #ident() -> ::broker::query::Field<#struct_name, #ty> {
+            pub fn #ident() -> ::broker::query::Field<Self, #ty> {
                 ::broker::query::Field::new(
                     ::std::sync::Arc::<str>::from(#name),
-                    |s: &#struct_name| -> &#ty { &s.#ident }
+                    |s: &Self| -> &#ty { &s.#ident }
                 )
             }
         }
     });
+    let unknown = quote!(field.to_owned());
+    let resolve_field = resolve_body(&queried, |ident| quote!(Self::#ident()), &unknown);
+    let unknown_nested = quote!(::std::format!("{}.{}", parent.name(), field));
+    let resolve_nested = resolve_body(
+        &queried,
+        |ident| quote!(parent.then(&Self::#ident())),
+        &unknown_nested,
+    );
 
-    quote! {
-        impl #struct_name {
+    // Accessors require a `'static` getter, which captures any generic parameters.
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(Self: 'static));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut queryable_generics = generics.clone();
+    for (field, _) in queried.iter().filter(|(_, options)| options.nested) {
+        let ty = &field.ty;
+        queryable_generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#ty: ::broker::query::dynamic::Queryable + ::broker::query::Schema));
+    }
+    let (_, _, queryable_where_clause) = queryable_generics.split_for_impl();
+
+    let schema = schema_body(&queried);
+
+    Ok(quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
             #(#field_methods)*
         }
+
+        #[automatically_derived]
+        impl #impl_generics ::broker::query::dynamic::Queryable for #struct_name #ty_generics
+        #queryable_where_clause
+        {
+            fn resolve_field(
+                field: &str,
+                query: &::broker::query::dynamic::DynQuery,
+            ) -> ::std::result::Result<
+                ::std::boxed::Box<
+                    dyn ::broker::query::Query<Self> + ::std::marker::Send + ::std::marker::Sync,
+                >,
+                ::broker::query::dynamic::ResolveError,
+            > {
+                #resolve_field
+            }
+
+            fn resolve_nested<P: ::broker::query::dynamic::Queryable>(
+                parent: ::broker::query::Field<P, Self>,
+                field: &str,
+                query: &::broker::query::dynamic::DynQuery,
+            ) -> ::std::result::Result<
+                ::std::boxed::Box<
+                    dyn ::broker::query::Query<P> + ::std::marker::Send + ::std::marker::Sync,
+                >,
+                ::broker::query::dynamic::ResolveError,
+            > {
+                #resolve_nested
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics ::broker::query::Schema for #struct_name #ty_generics
+        #queryable_where_clause
+        {
+            fn fields() -> ::std::vec::Vec<::broker::query::FieldInfo> {
+                #schema
+            }
+        }
+    }
+    .into())
+}
+
+/// The input of the [`query!`] macro: the queried type and the query itself.
+struct Input {
+    /// The type implementing `Queryable`.
+    ty: Type,
+    /// The query.
+    expr: Expr,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let ty = input.parse()?;
+        let _: Token![:] = input.parse()?;
+        let expr = input.parse()?;
+        Ok(Self { ty, expr })
+    }
+}
+
+impl Input {
+    /// Expand into the equivalent combinators.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the query contains unsupported expressions.
+    fn expand(&self) -> Result<TokenStream2> {
+        let Self { ty, expr } = self;
+        infix(ty, expr)
+    }
+}
+
+/// Expand an infix query on `ty`.
+///
+/// # Errors
+///
+/// Fails if the query contains unsupported expressions.
+fn infix(ty: &Type, expr: &Expr) -> Result<TokenStream2> {
+    let combinators = quote!(::broker::query::combinators);
+
+    if let Expr::Paren(paren) = expr {
+        return infix(ty, &paren.expr);
+    }
+    if let Expr::Group(group) = expr {
+        return infix(ty, &group.expr);
     }
-    .into()
+    if let Expr::Lit(ExprLit {
+        lit: Lit::Bool(lit),
+        ..
+    }) = expr
+    {
+        return Ok(if lit.value {
+            quote_spanned!(lit.span=> #combinators::True)
+        } else {
+            quote_spanned!(lit.span=> #combinators::Not(#combinators::True))
+        });
+    }
+    if let Expr::Unary(ExprUnary {
+        op: UnOp::Not(_),
+        expr: operand,
+        ..
+    }) = expr
+    {
+        let operand = infix(ty, operand)?;
+        return Ok(quote!(#combinators::Not(#operand)));
+    }
+    if let Expr::Binary(binary) = expr {
+        let Some(combinator) = logical(&binary.op) else {
+            return comparison(ty, binary);
+        };
+        let left = infix(ty, &binary.left)?;
+        let right = infix(ty, &binary.right)?;
+        return Ok(quote!(#combinators::#combinator(#left, #right)));
+    }
+    if let Expr::MethodCall(call) = expr {
+        let predicate = method_call(ty, call)?;
+        return Ok(checked(expr, ty, &predicate));
+    }
+
+    Err(Error::new_spanned(
+        expr,
+        "expected a comparison, a method call on a field, `!`, `&&`, `||`, `^`, `true` or `false`",
+    ))
+}
+
+/// The combinator corresponding to a logical operator.
+fn logical(op: &BinOp) -> Option<TokenStream2> {
+    if let BinOp::And(_) = op {
+        Some(quote!(And))
+    } else if let BinOp::Or(_) = op {
+        Some(quote!(Or))
+    } else if let BinOp::BitXor(_) = op {
+        Some(quote!(Xor))
+    } else {
+        None
+    }
+}
+
+/// The [`Field`](broker::query::Field) method corresponding to a comparison operator.
+const fn comparison_method(op: &BinOp) -> Option<&'static str> {
+    Some(if let BinOp::Eq(_) = op {
+        "eq"
+    } else if let BinOp::Ne(_) = op {
+        "ne"
+    } else if let BinOp::Lt(_) = op {
+        "lt"
+    } else if let BinOp::Gt(_) = op {
+        "gt"
+    } else if let BinOp::Le(_) = op {
+        "le"
+    } else if let BinOp::Ge(_) = op {
+        "ge"
+    } else {
+        return None;
+    })
+}
+
+/// Expand a comparison between a field and a value.
+///
+/// # Errors
+///
+/// Fails if the operator is not a comparison, or the left-hand side is not a field.
+fn comparison(ty: &Type, binary: &ExprBinary) -> Result<TokenStream2> {
+    let ExprBinary {
+        left, op, right, ..
+    } = binary;
+    let Some(method) = comparison_method(op) else {
+        return Err(Error::new_spanned(
+            op,
+            "expected `&&`, `||`, `^` or a comparison",
+        ));
+    };
+    let method = Ident::new(method, op.span());
+    let field = field(ty, left)?;
+    // String literals are compared as `str`, avoiding a double reference.
+    let value = if let Expr::Lit(ExprLit {
+        lit: Lit::Str(lit), ..
+    }) = &**right
+    {
+        lit.to_token_stream()
+    } else {
+        quote_spanned!(right.span()=> &(#right))
+    };
+
+    Ok(checked(binary, ty, &quote!(#field.#method(#value))))
+}
+
+/// Expand a method call on a field, such as `title.starts_with("E")`, or on another method call,
+/// such as `.ignore_case()`.
+///
+/// # Errors
+///
+/// Fails if the innermost receiver is not a field.
+fn method_call(ty: &Type, call: &ExprMethodCall) -> Result<TokenStream2> {
+    let ExprMethodCall {
+        receiver,
+        method,
+        turbofish,
+        args,
+        ..
+    } = call;
+    let receiver = if let Expr::MethodCall(inner) = &**receiver {
+        method_call(ty, inner)?
+    } else {
+        field(ty, receiver)?
+    };
+    Ok(quote!(#receiver.#method #turbofish (#args)))
+}
+
+/// Expand a field name into its accessor, such that an unknown field is reported at its name.
+///
+/// # Errors
+///
+/// Fails if the expression is not a single identifier.
+fn field(ty: &Type, expr: &Expr) -> Result<TokenStream2> {
+    if let Expr::Path(ExprPath {
+        qself: None, path, ..
+    }) = expr
+        && let Some(ident) = path.get_ident()
+    {
+        Ok(quote_spanned!(ident.span()=> <#ty>::#ident()))
+    } else {
+        Err(Error::new_spanned(expr, "expected a field name"))
+    }
+}
+
+/// Wrap a predicate such that type errors, such as comparing a field to a value of the wrong type,
+/// are reported at the expression the predicate was expanded from.
+fn checked(expr: &impl Spanned, ty: &Type, predicate: &TokenStream2) -> TokenStream2 {
+    quote_spanned!(expr.span()=> ::broker::query::check::<#ty, _>(#predicate))
 }
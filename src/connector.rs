@@ -5,7 +5,7 @@ use crate::{
     query::Query,
 };
 use async_trait::async_trait;
-use futures::stream::{BoxStream, StreamExt as _, iter as from_iter};
+use futures::stream::{BoxStream, StreamExt as _, iter as from_iter, once};
 use std::any::Any;
 use std::slice::from_ref;
 
@@ -44,6 +44,24 @@ where
         Ok(from_iter(vec.into_iter().map(Ok)).boxed())
     }
 
+    /// Fetch all data matching the query as a stream, taking ownership of both the source and the
+    /// query. Unlike with [`fetch`](Self::fetch), the stream borrows neither, so it can be
+    /// `'static` and, for example, be moved into a spawned task. Queries borrowing their values
+    /// can be converted using [`IntoOwned`](crate::query::IntoOwned).
+    ///
+    /// The default implementation calls [`fetch_all`](Self::fetch_all) when the stream is first
+    /// polled, and yields either entirely [`Ok`] values, or a single [`Err`] value. Crucially,
+    /// this means that it is *not lazy*.
+    #[inline]
+    fn fetch_owned<'a, Q>(self, query: Q) -> BoxStream<'a, Result<T, FetchError>>
+    where
+        Self: Sized + Send + 'a,
+        Q: Query<T> + Send + 'a,
+        T: 'a,
+    {
+        fetch_owned_eager(self, query)
+    }
+
     /// Fetch all data matching the query.
     async fn fetch_all(&mut self, query: &(dyn Query<T> + Sync)) -> Result<Vec<T>, FetchError>;
 
@@ -101,6 +119,27 @@ where
     }
 }
 
+/// Backing implementation for [`Source::fetch_owned`], calling [`fetch_all`](Source::fetch_all)
+/// when the stream is first polled.
+pub(crate) fn fetch_owned_eager<'a, T, S, Q>(
+    mut source: S,
+    query: Q,
+) -> BoxStream<'a, Result<T, FetchError>>
+where
+    T: Send + 'a,
+    S: Source<T> + Send + 'a,
+    Q: Query<T> + Send + 'a,
+{
+    once(async move { source.fetch_all(&query).await })
+        .flat_map(|result| {
+            from_iter(match result {
+                Ok(entries) => entries.into_iter().map(Ok).collect(),
+                Err(err) => vec![Err(err)],
+            })
+        })
+        .boxed()
+}
+
 /// A type that can accept data.
 ///
 /// This trait provides several ways of sending data regarding how much data is sent at a time.
@@ -157,8 +196,15 @@ where
     }
 
     #[inline]
-    async fn fetch_all(&mut self, _query: &(dyn Query<T> + Sync)) -> Result<Vec<T>, FetchError> {
-        Ok(self.items.clone())
+    async fn fetch_all(&mut self, query: &(dyn Query<T> + Sync)) -> Result<Vec<T>, FetchError> {
+        let mut items = self
+            .items
+            .iter()
+            .filter(|item| query.evaluate(item))
+            .cloned()
+            .collect();
+        query.shape().apply(&mut items, false);
+        Ok(items)
     }
 }
 
@@ -1,26 +1,51 @@
 //! Decoder/Encoder for the postgres connector that uses diesel orm
 use crate::postgres::models::Book;
 use crate::postgres::schema::books;
-use crate::postgres::{PgDecode, PgEncode};
+use crate::postgres::{PgDecode, PgEncode, bind_params};
+use crate::query::SqlStatement;
 use diesel::prelude::*;
+use diesel::result::Error as DslError;
+
+/// The columns of the `books` table. Statements referencing any other column are rejected.
+const COLUMNS: [&str; 4] = [
+    books::title::NAME,
+    books::author::NAME,
+    books::format::NAME,
+    books::isbn::NAME,
+];
 
 /// The Postgres decoder/encoder for the Book model.
 #[derive(Debug, Clone, Default)]
 pub struct BookMapper;
 
+impl BookMapper {
+    /// Builds the text of a query selecting books matching the statement, including its clauses.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the statement references columns not in the `books` table.
+    fn select(statement: &SqlStatement) -> QueryResult<String> {
+        statement
+            .check_columns(&COLUMNS)
+            .map_err(|err| DslError::QueryBuilderError(Box::new(err)))?;
+        Ok(format!(
+            "SELECT * FROM books WHERE {}{}",
+            statement.condition(),
+            statement.clauses()
+        ))
+    }
+}
+
 impl PgDecode<Book> for BookMapper {
     /// Takes a DB connection and the SQL and decodes it into a list.
     #[inline]
-    fn decode_all(&self, conn: &mut PgConnection, sql_text: &str) -> QueryResult<Vec<Book>> {
-        let pg_safe_condition = sql_text.replace('\"', "'");
-
-        let final_query = if pg_safe_condition.trim().is_empty() {
-            "SELECT * FROM books".to_owned()
-        } else {
-            format!("SELECT * FROM books WHERE {pg_safe_condition}")
-        };
-
-        diesel::sql_query(final_query).load::<Book>(conn)
+    fn decode_all(
+        &self,
+        conn: &mut PgConnection,
+        statement: &SqlStatement,
+    ) -> QueryResult<Vec<Book>> {
+        let query = diesel::sql_query(Self::select(statement)?);
+        bind_params(query, &statement.params).load::<Book>(conn)
     }
 
     /// Fetches a single book, safely returning None if it doesn't exist.
@@ -28,17 +53,14 @@ impl PgDecode<Book> for BookMapper {
     fn decode_optional(
         &self,
         conn: &mut PgConnection,
-        sql_text: &str,
+        statement: &SqlStatement,
     ) -> QueryResult<Option<Book>> {
-        let pg_safe_condition = sql_text.replace('\"', "'");
-
-        let final_query = if pg_safe_condition.trim().is_empty() {
-            "SELECT * FROM books LIMIT 1".to_owned()
-        } else {
-            format!("SELECT * FROM books WHERE {pg_safe_condition} LIMIT 1")
+        let statement = SqlStatement {
+            limit: Some(statement.limit.map_or(1, |limit| limit.min(1))),
+            ..statement.clone()
         };
-
-        diesel::sql_query(final_query)
+        let query = diesel::sql_query(Self::select(&statement)?);
+        bind_params(query, &statement.params)
             .get_result::<Book>(conn)
             .optional()
     }
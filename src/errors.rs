@@ -50,6 +50,8 @@ impl From<ReqwestError> for ConnectionError {
     /// in its error message. That being said, this function handles some cases and maps them to
     /// known [`ConnectionError`]s.
     ///
+    /// The URL is removed from the error, since it may contain secrets such as API keys.
+    ///
     /// # Panics
     ///
     /// Panics if for the given error, [`reqwest::Error::is_builder`] is true as those should be
@@ -64,6 +66,7 @@ impl From<ReqwestError> for ConnectionError {
         // `Response::error_for_status` (`_ref`) shouldn't be used as HTTP errors are included as
         // their own variant.
         assert!(!value.is_status());
+        let value = value.without_url();
 
         // No guarantee that several of these cases don't match; order by decreasing specificity.
         if let Some(status) = value.status() {
@@ -1,5 +1,6 @@
 #![feature(type_changing_struct_update)]
 #![feature(never_type)]
+#![feature(where_clause_attrs)]
 
 //! The data broker.
 
@@ -16,10 +17,12 @@ use async_trait::async_trait;
 use futures::{
     StreamExt as _,
     future::try_join_all,
-    stream::{BoxStream, FuturesUnordered, select_all},
+    stream::{BoxStream, FuturesUnordered, iter as from_iter, select_all, unfold},
 };
 use serde::Serialize;
 use std::any::Any;
+use std::mem::take;
+use std::vec::IntoIter;
 use std::{collections::HashSet, hash::Hash};
 use tokio as _;
 
@@ -39,10 +42,13 @@ pub mod connector;
 
 pub mod query;
 pub use query::Query;
+use query::{Shape, shape::Reshaped};
 
 pub mod encode;
 pub use encode::{Codec, Decode, Encode};
 
+pub mod retry;
+
 #[cfg(feature = "rest")]
 pub mod rest;
 
@@ -117,6 +123,135 @@ where
     }
 }
 
+impl<T> Broker<T>
+where
+    T: Send + 'static,
+{
+    /// Fetch the entries needed from each source to produce the results of `query` shaped by
+    /// `shape` once merged, that is, the first `offset + limit` entries in order.
+    ///
+    /// # Errors
+    ///
+    /// Delegates errors returned by [`Source::fetch_all`] for any source.
+    async fn fetch_ordered(
+        &mut self,
+        query: &(dyn Query<T> + Sync),
+        shape: &Shape<T>,
+    ) -> Result<Vec<Vec<T>>, FetchError> {
+        let query = Reshaped::new(query, shape.for_merge());
+        let futures = self
+            .sources
+            .iter_mut()
+            .map(|source| source.1.fetch_all(&query))
+            .collect::<Vec<_>>();
+        try_join_all(futures).await
+    }
+}
+
+/// Merge streams ordered by `shape` into a single ordered stream without duplicates. Ties are
+/// resolved in favor of earlier streams, and errors are forwarded as soon as they are encountered.
+///
+/// Equal entries compare as equal, so entries are held back until all entries comparing equal to
+/// them have been merged, and only the first of any duplicates is returned.
+fn merge_sorted<'a, T>(
+    streams: Vec<BoxStream<'a, Result<T, FetchError>>>,
+    shape: Shape<T>,
+) -> BoxStream<'a, Result<T, FetchError>>
+where
+    T: Eq + Hash + Send + 'a,
+{
+    // Held back entries are returned after the merged stream ends, so it is polled again.
+    let merged = merge(streams, shape.clone()).fuse();
+    unfold(
+        (merged, shape, Vec::new(), Vec::new().into_iter()),
+        |(mut merged, shape, mut ties, mut ready): (_, _, Vec<T>, IntoIter<T>)| async move {
+            loop {
+                if let Some(entry) = ready.next() {
+                    return Some((Ok(entry), (merged, shape, ties, ready)));
+                }
+                match merged.next().await {
+                    Some(Ok(entry)) => {
+                        if ties
+                            .first()
+                            .is_some_and(|tie| shape.compare(tie, &entry).is_ne())
+                        {
+                            deduplicate(&mut ties);
+                            ready = take(&mut ties).into_iter();
+                        }
+                        ties.push(entry);
+                    },
+                    Some(Err(err)) => return Some((Err(err), (merged, shape, ties, ready))),
+                    None if ties.is_empty() => return None,
+                    None => {
+                        deduplicate(&mut ties);
+                        ready = take(&mut ties).into_iter();
+                    },
+                }
+            }
+        },
+    )
+    .boxed()
+}
+
+/// Remove duplicates from `entries`, keeping the first of each and preserving the order.
+fn deduplicate<T>(entries: &mut Vec<T>)
+where
+    T: Eq + Hash,
+{
+    let mut seen = HashSet::with_capacity(entries.len());
+    let keep = entries
+        .iter()
+        .map(|entry| seen.insert(entry))
+        .collect::<Vec<_>>();
+    drop(seen);
+    let mut keep = keep.into_iter();
+    entries.retain(|_| keep.next().unwrap_or(false));
+}
+
+/// Merge streams ordered by `shape` into a single ordered stream, keeping duplicates. See
+/// [`merge_sorted`].
+fn merge<'a, T>(
+    streams: Vec<BoxStream<'a, Result<T, FetchError>>>,
+    shape: Shape<T>,
+) -> BoxStream<'a, Result<T, FetchError>>
+where
+    T: Send + 'a,
+{
+    let heads = streams.into_iter().map(|stream| (stream, None)).collect();
+    unfold(
+        (heads, shape),
+        |(mut heads, shape): (Vec<(_, Option<T>)>, _)| async move {
+            for index in 0..heads.len() {
+                let Some((stream, head @ None)) = heads.get_mut(index) else {
+                    continue;
+                };
+                match stream.next().await {
+                    Some(Ok(entry)) => *head = Some(entry),
+                    Some(Err(err)) => return Some((Err(err), (heads, shape))),
+                    None => {},
+                }
+            }
+            // Streams without a head are exhausted.
+            heads.retain(|(_, head)| head.is_some());
+
+            let (index, _) = heads
+                .iter()
+                .enumerate()
+                .filter_map(|(index, (_, head))| Some((index, head.as_ref()?)))
+                .reduce(|min, next| {
+                    if shape.compare(next.1, min.1).is_lt() {
+                        next
+                    } else {
+                        min
+                    }
+                })?;
+            let entry = heads.get_mut(index)?.1.take()?;
+            Some((Ok(entry), (heads, shape)))
+        },
+    )
+    .boxed()
+}
+
 impl<T> Default for Broker<T>
 where
     T: Send,
@@ -145,20 +280,66 @@ where
     where
         T: 's,
     {
-        // TODO: Don't require awaiting all streams immediately. Might require own `Stream`
-        // implementation.
-        let futures = self
-            .sources
-            .iter_mut()
-            .map(|source| source.1.fetch(query))
-            .collect::<Vec<_>>();
-        try_join_all(futures)
-            .await
-            .map(|streams| select_all(streams).boxed())
+        let shape = query.shape();
+        if shape.is_unshaped() {
+            // TODO: Don't require awaiting all streams immediately. Might require own `Stream`
+            // implementation.
+            let futures = self
+                .sources
+                .iter_mut()
+                .map(|source| source.1.fetch(query))
+                .collect::<Vec<_>>();
+            return try_join_all(futures)
+                .await
+                .map(|streams| select_all(streams).boxed());
+        }
+
+        let limit = shape.limit.unwrap_or(usize::MAX);
+        if shape.offset == 0 {
+            // Each source returns its first `limit` entries in order, so the streams can be
+            // fetched as requested and merged. Duplicates are skipped before counting towards the
+            // limit, as in `fetch_all`.
+            let futures = self
+                .sources
+                .iter_mut()
+                .map(|source| source.1.fetch(query))
+                .collect::<Vec<_>>();
+            let streams = try_join_all(futures).await?;
+            Ok(merge_sorted(streams, shape).take(limit).boxed())
+        } else {
+            // The offset can't be applied by each source individually. Instead, each source
+            // returns its first `offset + limit` entries, and the offset is applied after merging.
+            let entries = self.fetch_ordered(query, &shape).await?;
+            let streams = entries
+                .into_iter()
+                .map(|entries| from_iter(entries.into_iter().map(Ok)).boxed())
+                .collect();
+            Ok(merge_sorted(streams, shape.clone())
+                .skip(shape.offset)
+                .take(limit)
+                .boxed())
+        }
     }
 
     #[inline]
     async fn fetch_all(&mut self, query: &(dyn Query<T> + Sync)) -> Result<Vec<T>, FetchError> {
+        let shape = query.shape();
+        if !shape.is_unshaped() {
+            let mut entries = self
+                .fetch_ordered(query, &shape)
+                .await?
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+
+            // Deduplicate while preserving the order, such that the (stable) sort below merges
+            // the results of the sources.
+            deduplicate(&mut entries);
+
+            shape.apply(&mut entries, false);
+            return Ok(entries);
+        }
+
         let min_capacity = self
             .sources
             .iter()
@@ -318,3 +499,180 @@ where
         Ok(out)
     }
 }
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    reason = "Panics simply indicate failed tests."
+)]
+#[allow(clippy::unwrap_used, reason = "Panics simply indicate failed tests.")]
+mod tests {
+    use super::*;
+    use crate::query::{
+        IntoOwned as _, Queryable,
+        combinators::{Limit, Offset, OrderBy},
+    };
+    use futures::TryStreamExt as _;
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Queryable)]
+    struct TestBook {
+        title: String,
+        year: u16,
+    }
+
+    async fn broker() -> Broker<TestBook> {
+        let mut broker = Broker::new();
+        for books in [
+            [("Emma", 1815), ("Persuasion", 1817)],
+            [("Mansfield Park", 1814), ("Northanger Abbey", 1817)],
+        ] {
+            let mut source = MemorySource::new();
+            for (title, year) in books {
+                let book = TestBook {
+                    title: title.to_owned(),
+                    year,
+                };
+                source.send_one(&book).await.unwrap();
+            }
+            broker.add_source("memory", Box::new(source));
+        }
+        broker
+    }
+
+    fn titles(books: &[TestBook]) -> Vec<&str> {
+        books.iter().map(|book| book.title.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn merge_ordered() {
+        let mut broker = broker().await;
+        let query = Limit(
+            OrderBy(
+                TestBook::year().gt(&1800),
+                vec![TestBook::year().desc(), TestBook::title().asc()],
+            ),
+            3,
+        );
+
+        let streamed = broker
+            .fetch(&query)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let all = broker.fetch_all(&query).await.unwrap();
+
+        assert_eq!(
+            titles(&streamed),
+            ["Northanger Abbey", "Persuasion", "Emma"]
+        );
+        assert_eq!(streamed, all);
+    }
+
+    #[tokio::test]
+    async fn merge_duplicates() {
+        let mut broker = broker().await;
+        let mut duplicates = MemorySource::new();
+        duplicates
+            .send_one(&TestBook {
+                title: "Emma".to_owned(),
+                year: 1815,
+            })
+            .await
+            .unwrap();
+        broker.add_source("duplicates", Box::new(duplicates));
+        let query = Limit(
+            OrderBy(TestBook::year().gt(&1800), vec![TestBook::year().asc()]),
+            3,
+        );
+
+        let streamed = broker
+            .fetch(&query)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let all = broker.fetch_all(&query).await.unwrap();
+
+        assert_eq!(titles(&streamed), ["Mansfield Park", "Emma", "Persuasion"]);
+        assert_eq!(streamed, all);
+    }
+
+    #[tokio::test]
+    async fn merge_drained() {
+        let mut broker = broker().await;
+        let unlimited = OrderBy(TestBook::year().gt(&1800), vec![TestBook::year().desc()]);
+        let underfilled = Limit(
+            OrderBy(TestBook::year().gt(&1800), vec![TestBook::year().asc()]),
+            10,
+        );
+
+        let drained = broker
+            .fetch(&unlimited)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(
+            titles(&drained),
+            ["Persuasion", "Northanger Abbey", "Emma", "Mansfield Park"]
+        );
+
+        let streamed = broker
+            .fetch(&underfilled)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let all = broker.fetch_all(&underfilled).await.unwrap();
+        assert_eq!(
+            titles(&streamed),
+            ["Mansfield Park", "Emma", "Persuasion", "Northanger Abbey"]
+        );
+        assert_eq!(streamed, all);
+    }
+
+    #[tokio::test]
+    async fn merge_offset() {
+        let mut broker = broker().await;
+        let query = Offset(
+            Limit(
+                OrderBy(TestBook::year().gt(&1800), vec![TestBook::year().asc()]),
+                3,
+            ),
+            1,
+        );
+
+        let streamed = broker
+            .fetch(&query)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let all = broker.fetch_all(&query).await.unwrap();
+
+        assert_eq!(titles(&streamed), ["Emma", "Persuasion"]);
+        assert_eq!(streamed, all);
+    }
+
+    #[tokio::test]
+    async fn fetch_owned() {
+        let broker = broker().await;
+        let title = String::from("Emma");
+        let query = TestBook::title().ne(&title).into_owned();
+        drop(title);
+
+        let stream = broker.fetch_owned(OrderBy(query, vec![TestBook::year().asc()]));
+        let fetched = stream.try_collect::<Vec<_>>().await.unwrap();
+
+        assert_eq!(
+            titles(&fetched),
+            ["Mansfield Park", "Persuasion", "Northanger Abbey"]
+        );
+    }
+}
@@ -2,19 +2,24 @@
 
 use async_trait::async_trait;
 use diesel::PgConnection;
+use diesel::pg::Pg;
+use diesel::query_builder::{BoxedSqlQuery, SqlQuery};
 use diesel::r2d2::{ConnectionManager, Pool};
-use diesel::result::Error as DslError;
+use diesel::result::{DatabaseErrorKind, Error as DslError};
+use diesel::sql_types::{BigInt, Bool, Double, Nullable, Text};
 use futures::stream::{self, BoxStream, StreamExt as _};
 use std::any::Any;
 use std::io::Error;
-use std::io::ErrorKind::ConnectionRefused;
+use std::io::ErrorKind::{ConnectionAborted, ConnectionRefused};
 use std::marker::PhantomData;
 use std::slice::from_ref;
+use std::sync::Arc;
 use tokio::task::spawn_blocking;
 
 use crate::connector::{Sink, Source};
 use crate::errors::{ConnectionError, EncodeError, FetchError, FetchOneError, SendError};
-use crate::query::Query;
+use crate::query::{Query, Single, SqlParam, SqlStatement};
+use crate::retry::{Failure, Policy};
 use diesel::QueryResult;
 
 pub mod builder;
@@ -28,19 +33,31 @@ pub use models::*;
 // Database traits instead of byte-oriented Codec that can map directly to database rows.
 
 /// Postgres decode
+///
+/// Implementors are responsible for checking the [columns](SqlStatement::columns) of the
+/// statement against their table, and for binding its [parameters](SqlStatement::params).
 pub trait PgDecode<T> {
-    /// Decodes data from a raw SQL string.
+    /// Fetches all records matching a parameterized SQL condition.
     ///
     /// # Errors
     ///
-    /// Returns a `diesel::result::Error` if the database query fails or if
-    /// the raw rows cannot be safely mapped to the target struct.
-    fn decode_all(&self, conn: &mut PgConnection, sql_text: &str) -> Result<Vec<T>, DslError>;
+    /// Returns a `diesel::result::Error` if the statement references unknown columns, if the
+    /// database query fails or if the raw rows cannot be safely mapped to the target struct.
+    fn decode_all(
+        &self,
+        conn: &mut PgConnection,
+        statement: &SqlStatement,
+    ) -> Result<Vec<T>, DslError>;
     /// Fetches a single optional record from the database.
     ///
     /// # Errors
-    /// Returns a `diesel::result::Error` if the database query fails.
-    fn decode_optional(&self, conn: &mut PgConnection, sql_text: &str) -> QueryResult<Option<T>>;
+    /// Returns a `diesel::result::Error` if the statement references unknown columns or if the
+    /// database query fails.
+    fn decode_optional(
+        &self,
+        conn: &mut PgConnection,
+        statement: &SqlStatement,
+    ) -> QueryResult<Option<T>>;
 }
 
 /// Postgres encode
@@ -64,14 +81,147 @@ pub trait PgEncode<T> {
     fn encode_optional(&self, conn: &mut PgConnection, entry: Option<&T>) -> QueryResult<usize>;
 }
 
+/// Binds the [parameters](SqlStatement::params) of a statement to a raw SQL query, in placeholder
+/// order and using the SQL type corresponding to each [`SqlParam`].
+#[inline]
+pub fn bind_params<'f>(query: SqlQuery, params: &[SqlParam]) -> BoxedSqlQuery<'f, Pg, SqlQuery> {
+    params
+        .iter()
+        .cloned()
+        .fold(query.into_boxed(), |query, param| match param {
+            SqlParam::Text(value) | SqlParam::Cast { value, .. } => query.bind::<Text, _>(value),
+            SqlParam::BigInt(value) => query.bind::<BigInt, _>(value),
+            SqlParam::Double(value) => query.bind::<Double, _>(value),
+            SqlParam::Bool(value) => query.bind::<Bool, _>(value),
+            SqlParam::Null => query.bind::<Nullable<Text>, _>(None::<String>),
+        })
+}
+
 /// Database connection pool
 pub type PgPool = Pool<ConnectionManager<PgConnection>>;
 
+/// Helper to use for [`Source`] implementations, fetching the rows matching `statement`.
+///
+/// Acquiring a connection is retried as specified by `retry`, as is the statement if the
+/// connection to the database is lost.
+///
+/// # Errors
+///
+/// Fails if no connection can be acquired or if the statement fails, on the last attempt.
+///
+/// # Panics
+///
+/// Panics if the background thread running the statement panics.
+async fn fetch_impl<T, D>(
+    pool: &PgPool,
+    decoder: &D,
+    retry: &Policy,
+    statement: SqlStatement,
+) -> Result<Vec<T>, FetchError>
+where
+    T: Send + 'static,
+    D: PgDecode<T> + Clone + Send + Sync + 'static,
+{
+    let policy = *retry;
+    retry
+        .run(|| {
+            let pool = pool.clone();
+            let decoder = decoder.clone();
+            let statement = statement.clone();
+            async move {
+                spawn_blocking(move || {
+                    let mut conn = pool.get().map_err(|e| {
+                        let retryable = policy.retries(&ConnectionError::TimedOut, true);
+                        Failure::new(FetchError::InvalidQuery(Box::new(e)), retryable)
+                    })?;
+
+                    decoder.decode_all(&mut conn, &statement).map_err(|e| {
+                        let retryable =
+                            lost_connection(&e).is_some_and(|err| policy.retries(&err, true));
+                        Failure::new(FetchError::InvalidQuery(Box::new(e)), retryable)
+                    })
+                })
+                .await
+                .expect("Tokio background thread panicked")
+            }
+        })
+        .await
+}
+
+/// Helper to use for [`Sink`] implementations, inserting `entries`.
+///
+/// Acquiring a connection is retried as specified by `retry`. Since inserting is not idempotent,
+/// the statement itself is only retried if the policy [allows it](Policy::non_idempotent).
+///
+/// # Errors
+///
+/// Fails if no connection can be acquired or if the statement fails, on the last attempt.
+///
+/// # Panics
+///
+/// Panics if the background thread running the statement panics.
+async fn send_impl<T, E>(
+    pool: &PgPool,
+    encoder: &E,
+    retry: &Policy,
+    entries: &[T],
+) -> Result<(), SendError>
+where
+    T: Clone + Send + Sync + 'static,
+    E: PgEncode<T> + Clone + Send + Sync + 'static,
+{
+    let policy = *retry;
+    let entries: Arc<[T]> = entries.into();
+    retry
+        .run(|| {
+            let pool = pool.clone();
+            let encoder = encoder.clone();
+            let entries_to_insert = Arc::clone(&entries);
+            async move {
+                spawn_blocking(move || {
+                    let mut conn = pool.get().map_err(|e| {
+                        let io_err = Error::new(ConnectionRefused, e.to_string());
+                        let err = ConnectionError::Io(io_err);
+                        // Nothing has been sent yet, so this is safe to retry.
+                        let retryable = policy.retries(&err, true);
+                        Failure::new(SendError::Connection(err), retryable)
+                    })?;
+
+                    let _ = encoder
+                        .encode_all(&mut conn, &entries_to_insert)
+                        .map_err(|e| {
+                            let retryable =
+                                lost_connection(&e).is_some_and(|err| policy.retries(&err, false));
+                            Failure::new(SendError::Encode(EncodeError(Box::new(e))), retryable)
+                        })?;
+
+                    Ok(())
+                })
+                .await
+                .expect("Tokio background thread panicked")
+            }
+        })
+        .await
+}
+
+/// The connection error causing `err`, if the connection to the database was lost.
+fn lost_connection(err: &DslError) -> Option<ConnectionError> {
+    let DslError::DatabaseError(DatabaseErrorKind::ClosedConnection, info) = err else {
+        return None;
+    };
+    Some(ConnectionError::Io(Error::new(
+        ConnectionAborted,
+        info.message().to_owned(),
+    )))
+}
+
 #[derive(Debug, Clone)]
 /// Struct for readonly connector
 pub struct ReadOnly<T, D> {
     /// Database pool
     pub(crate) pool: PgPool,
+    /// How failed statements are retried
+    pub(crate) retry: Policy,
     /// Decoder
     pub(crate) decoder: D,
     /// Phantomdata field
@@ -83,6 +233,8 @@ pub struct ReadOnly<T, D> {
 pub struct WriteOnly<T, E> {
     /// Database pool
     pub(crate) pool: PgPool,
+    /// How failed statements are retried
+    pub(crate) retry: Policy,
     /// Encoder
     pub(crate) encoder: E,
     /// Phantomdata field
@@ -94,6 +246,8 @@ pub struct WriteOnly<T, E> {
 pub struct ReadWrite<T, E, D> {
     /// Database pool
     pub(crate) pool: PgPool,
+    /// How failed statements are retried
+    pub(crate) retry: Policy,
     /// Encoder
     pub(crate) encoder: E,
     /// Decoder
@@ -115,27 +269,21 @@ where
 
     #[inline]
     async fn fetch_all(&mut self, query: &(dyn Query<T> + Sync)) -> Result<Vec<T>, FetchError> {
-        let single = query.to_sql_single();
-        let sql_text = single.query.query_text;
-        let residue = single.residue;
-
-        let pool = self.pool.clone();
-        let decoder = self.decoder.clone();
-
-        let mut decoded_items = spawn_blocking(move || {
-            let mut conn = pool
-                .get()
-                .map_err(|e| FetchError::InvalidQuery(Box::new(e)))?;
-
-            decoder
-                .decode_all(&mut conn, &sql_text)
-                .map_err(|e| FetchError::InvalidQuery(Box::new(e)))
-        })
-        .await
-        .expect("Tokio background thread panicked")?;
+        let shape = query.shape();
+        let Single {
+            query: mut statement,
+            residue,
+        } = query.to_sql_single();
+        // The limit and offset can only be applied by the database if it does all filtering.
+        let windowed = residue.is_empty();
+        statement.shape(&shape, windowed);
+
+        let mut decoded_items =
+            fetch_impl(&self.pool, &self.decoder, &self.retry, statement).await?;
 
         // Evaluate any fallback residue
         decoded_items.retain(|item| residue.iter().all(|r| r.evaluate(item)));
+        shape.apply(&mut decoded_items, windowed);
 
         Ok(decoded_items)
     }
@@ -169,30 +317,7 @@ where
 {
     #[inline]
     async fn send_all(&mut self, entries: &[T]) -> Result<(), SendError> {
-        let pool = self.pool.clone();
-        let encoder = self.encoder.clone();
-        let entries_to_insert = entries.to_vec();
-
-        spawn_blocking(move || -> Result<(), SendError> {
-            let mut conn = pool.get().map_err(|e| {
-                let io_err = Error::new(ConnectionRefused, e.to_string());
-                SendError::Connection(ConnectionError::Io(io_err))
-            })?;
-
-            let _ = encoder
-                .encode_all(&mut conn, &entries_to_insert)
-                .map_err(|e| {
-                    let boxed_err = Box::new(e);
-
-                    SendError::Encode(EncodeError(boxed_err))
-                })?;
-
-            Ok(())
-        })
-        .await
-        .expect("Tokio background thread panicked")?;
-
-        Ok(())
+        send_impl(&self.pool, &self.encoder, &self.retry, entries).await
     }
     #[inline]
     async fn send_one(&mut self, entry: &T) -> Result<(), SendError> {
@@ -215,26 +340,20 @@ where
 
     #[inline]
     async fn fetch_all(&mut self, query: &(dyn Query<T> + Sync)) -> Result<Vec<T>, FetchError> {
-        let single = query.to_sql_single();
-        let sql_text = single.query.query_text;
-        let residue = single.residue;
-
-        let pool = self.pool.clone();
-        let decoder = self.decoder.clone();
-
-        let mut decoded_items = spawn_blocking(move || {
-            let mut conn = pool
-                .get()
-                .map_err(|e| FetchError::InvalidQuery(Box::new(e)))?;
-
-            decoder
-                .decode_all(&mut conn, &sql_text)
-                .map_err(|e| FetchError::InvalidQuery(Box::new(e)))
-        })
-        .await
-        .expect("Tokio background thread panicked")?;
+        let shape = query.shape();
+        let Single {
+            query: mut statement,
+            residue,
+        } = query.to_sql_single();
+        // The limit and offset can only be applied by the database if it does all filtering.
+        let windowed = residue.is_empty();
+        statement.shape(&shape, windowed);
+
+        let mut decoded_items =
+            fetch_impl(&self.pool, &self.decoder, &self.retry, statement).await?;
 
         decoded_items.retain(|item| residue.iter().all(|r| r.evaluate(item)));
+        shape.apply(&mut decoded_items, windowed);
 
         Ok(decoded_items)
     }
@@ -265,31 +384,7 @@ where
 {
     #[inline]
     async fn send_all(&mut self, entries: &[T]) -> Result<(), SendError> {
-        let pool = self.pool.clone();
-        let encoder = self.encoder.clone();
-        let entries_to_insert = entries.to_vec();
-
-        spawn_blocking(move || -> Result<(), SendError> {
-            let mut conn = pool.get().map_err(|e| {
-                let io_err = Error::new(ConnectionRefused, e.to_string());
-
-                SendError::Connection(ConnectionError::Io(io_err))
-            })?;
-
-            let _ = encoder
-                .encode_all(&mut conn, &entries_to_insert)
-                .map_err(|e| {
-                    let boxed_err = Box::new(e);
-
-                    SendError::Encode(EncodeError(boxed_err))
-                })?;
-
-            Ok(())
-        })
-        .await
-        .expect("Tokio background thread panicked")?;
-
-        Ok(())
+        send_impl(&self.pool, &self.encoder, &self.retry, entries).await
     }
     #[inline]
     async fn send_one(&mut self, entry: &T) -> Result<(), SendError> {
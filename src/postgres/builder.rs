@@ -1,6 +1,7 @@
 //! Builder for `PostgreSQL` connectors.
 
 use crate::postgres::{ReadOnly, ReadWrite, WriteOnly};
+use crate::retry::Policy;
 use diesel::PgConnection;
 use diesel::r2d2::{ConnectionManager, Pool, PoolError};
 use std::marker::PhantomData;
@@ -21,6 +22,8 @@ pub struct Builder<
     encoder: Option<E>,
     /// Field for decoder
     decoder: Option<D>,
+    /// How failed statements are retried
+    retry: Policy,
     /// Phantomdata field
     _phantom: PhantomData<T>,
 }
@@ -34,6 +37,7 @@ impl<T> Builder<T> {
             url: None,
             encoder: None,
             decoder: None,
+            retry: Policy::new(),
             _phantom: PhantomData,
         }
     }
@@ -46,6 +50,19 @@ impl<T> Default for Builder<T> {
     }
 }
 
+impl<T, E, D, const URL: bool, const ENCODER: bool, const DECODER: bool>
+    Builder<T, E, D, URL, ENCODER, DECODER>
+{
+    /// Sets how statements failing with transient errors are retried. Defaults to
+    /// [`Policy::new`], making a single attempt. Acquiring a connection is always safe to retry,
+    /// but inserts are only retried if the policy [allows it](Policy::non_idempotent).
+    #[must_use]
+    #[inline]
+    pub fn retry(self, retry: Policy) -> Self {
+        Self { retry, ..self }
+    }
+}
+
 // State transitions (Setting the fields)
 impl<T, E, D, const ENCODER: bool, const DECODER: bool> Builder<T, E, D, false, ENCODER, DECODER> {
     /// Adds a connection string to use for the database pool.
@@ -55,6 +72,7 @@ impl<T, E, D, const ENCODER: bool, const DECODER: bool> Builder<T, E, D, false,
             url: Some(url.into()),
             encoder: self.encoder,
             decoder: self.decoder,
+            retry: self.retry,
             _phantom: PhantomData,
         }
     }
@@ -68,6 +86,7 @@ impl<T, D, const URL: bool, const DECODER: bool> Builder<T, (), D, URL, false, D
             url: self.url,
             encoder: Some(encoder),
             decoder: self.decoder,
+            retry: self.retry,
             _phantom: PhantomData,
         }
     }
@@ -81,6 +100,7 @@ impl<T, E, const URL: bool, const ENCODER: bool> Builder<T, E, (), URL, ENCODER,
             url: self.url,
             encoder: self.encoder,
             decoder: Some(decoder),
+            retry: self.retry,
             _phantom: PhantomData,
         }
     }
@@ -94,6 +114,7 @@ impl<T, const URL: bool> Builder<T, (), (), URL, false, false> {
             url: self.url,
             encoder: Some(codec.clone()), // Plugs into encoder
             decoder: Some(codec),         // Plugs into decoder
+            retry: self.retry,
             _phantom: PhantomData,
         }
     }
@@ -125,6 +146,7 @@ impl<T, D> Build for Builder<T, (), D, true, false, true> {
             decoder: self
                 .decoder
                 .expect("Type-state guarantees decoder is present"),
+            retry: self.retry,
             _phantom: PhantomData,
         })
     }
@@ -146,6 +168,7 @@ impl<T, E> Build for Builder<T, E, (), true, true, false> {
             encoder: self
                 .encoder
                 .expect("Type-state guarantees encoder is present"),
+            retry: self.retry,
             _phantom: PhantomData,
         })
     }
@@ -171,6 +194,7 @@ impl<T, E, D> Build for Builder<T, E, D, true, true, true> {
             decoder: self
                 .decoder
                 .expect("Type-state guarantees decoder is present"),
+            retry: self.retry,
             _phantom: PhantomData,
         })
     }
@@ -1,5 +1,6 @@
 //! Models file for book related types and for the diesel orm.
 use super::schema::books;
+use crate::query::{SqlParam, ToSqlParam};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter, Result};
 
@@ -74,6 +75,16 @@ impl Display for BookFormatType {
     }
 }
 
+impl ToSqlParam for BookFormatType {
+    /// Binds the variant using its serialized spelling, which matches the database, cast to
+    /// `book_format_type`.
+    #[inline]
+    #[expect(clippy::unreachable, reason = "Unit variants always serialize.")]
+    fn to_sql_param(&self) -> SqlParam {
+        SqlParam::cast_serialized(self, "book_format_type").unwrap_or_else(|_| unreachable!())
+    }
+}
+
 impl Display for Book {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
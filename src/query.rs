@@ -4,23 +4,89 @@
 //! combining, and translating queries in a type-safe manner.
 
 use nameof::{name_of, name_of_type};
-pub use query_macro::Queryable;
+pub use query_macro::{Queryable, query};
+#[cfg(feature = "postgres")]
+use serde::Serialize;
+#[cfg(feature = "postgres")]
+use serde_json::{Value, to_value};
+use std::borrow::Cow;
 use std::fmt;
 use std::sync::Arc;
+#[cfg(feature = "postgres")]
+use thiserror::Error;
 
 /// Query primitives and combinators.
 pub mod combinators;
-use combinators::{Eq, Gt, Lt, Ne};
+use combinators::{
+    AnyElement, Between, Contains, EndsWith, Eq, EqIgnoreCase, FieldEq, FieldGe, FieldGt, FieldLe,
+    FieldLt, FieldNe, Ge, Gt, In, IsNone, IsSome, Le, Lt, Ne, StartsWith,
+};
+
+pub mod shape;
+pub use shape::{Shape, SortKey};
+
+pub mod optimize;
+pub use optimize::{Node, optimize};
+
+pub mod dynamic;
+
+pub mod schema;
+pub use schema::{FieldInfo, Schema};
+
+pub mod syntax;
+pub use syntax::parse;
+
+pub mod view;
+pub use view::{Operand, Operator, Primitive};
+
+#[cfg(feature = "rest")]
+pub mod http;
+#[cfg(feature = "rest")]
+pub use http::HttpConvention;
 
 /// A query that can be evaluated to check if some data matches a predicate.
-pub trait Query<T> {
+///
+/// Queries are required to implement [`Debug`](fmt::Debug) such that type-erased queries, such as
+/// `Box<dyn Query<T> + Sync>`, can still be printed. They are also required to be [`Sync`], since
+/// connectors and [`optimize`](optimize::optimize) share references to their parts.
+pub trait Query<T>: fmt::Debug + Sync {
     /// Try to match `data` to the predicate specified by this query.
     fn evaluate(&self, data: &T) -> bool;
 
-    /// Translate into a single [`HttpQuery`]. See [`Single`] documentation for caveats, and
-    /// primitive- or combinator-specific documentation for details.
+    /// The ordering, limit and offset requested for the results of this query. These are
+    /// specified using [`OrderBy`](combinators::OrderBy), [`Limit`](combinators::Limit) and
+    /// [`Offset`](combinators::Offset), and only respected for the outermost query.
+    ///
+    /// The default implementation returns [`Shape::new`], leaving results as they are.
+    #[inline]
+    fn shape(&self) -> Shape<T> {
+        Shape::new()
+    }
+
+    /// The logical structure of this query, used by [`optimize`](optimize::optimize) to rewrite
+    /// it. Logical combinators return their operands, and comparisons return a [`Node::Leaf`]
+    /// carrying their negation.
+    ///
+    /// The default implementation returns [`None`], meaning the query is an opaque predicate.
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        None
+    }
+
+    /// A description of this query as a comparison between a field and values, allowing it to be
+    /// translated outside of this crate. See the [`view`] module for details.
+    ///
+    /// The default implementation returns [`None`], meaning the query is not a comparison.
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        None
+    }
+
+    /// Translate into a single [`HttpQuery`], following the parameter conventions of the source.
+    /// See [`Single`] documentation for caveats, and primitive- or combinator-specific
+    /// documentation for details.
     #[cfg(feature = "rest")]
-    fn to_http_single(&self) -> Single<'_, HttpQuery<'_>, T>;
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T>;
 
     /// Translate into multiple [`HttpQuery`] instances. This avoids some of the caveats of
     /// [`Single`], but has the obvious downside of requiring multiple requests to be made.
@@ -35,7 +101,7 @@ pub trait Query<T> {
     /// [`None`] is returned if no translation specific enough to select only desired elements is
     /// possible.
     #[cfg(feature = "rest")]
-    fn to_http_multi(&self) -> Option<Vec<HttpQuery<'_>>>;
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>>;
 
     /// Translates the query combinator into a single parameterized SQL query.
     ///
@@ -69,6 +135,31 @@ pub trait Query<T> {
     fn to_sql_multi(&self) -> Option<Vec<SqlStatement>>;
 }
 
+/// Require `query` to be a query on `T`. Used by [`query!`] to report type errors where they
+/// occur.
+#[doc(hidden)]
+#[inline]
+pub const fn check<T, Q: Query<T>>(query: Q) -> Q {
+    query
+}
+
+/// A query that can be converted into one owning all of its values.
+///
+/// Comparisons such as [`Eq`] borrow their values, which ties the query to the lifetime of its
+/// inputs. The converted query clones any borrowed values, such that it can outlive them; for
+/// example, it can be moved into a spawned task or passed to [`Source::fetch_owned`]. If the
+/// fields and values are `'static`, so is the converted query.
+///
+/// [`Source::fetch_owned`]: crate::connector::Source::fetch_owned
+pub trait IntoOwned {
+    /// The equivalent query owning its values.
+    type Owned;
+
+    /// Convert into an equivalent query owning its values.
+    #[must_use]
+    fn into_owned(self) -> Self::Owned;
+}
+
 /// A best-effort translation of the input query to a single output query.
 ///
 /// In cases where total translation is not possible, [`query`](Self::query) attempts to fetch a
@@ -100,7 +191,6 @@ pub struct Single<'a, Q, T> {
 ///
 /// Fields constructors are generated from [`#[derive(Queryable)]`].
 // TODO: Can these fields be simplified?
-#[derive(Clone)]
 pub struct Field<T: ?Sized, U: ?Sized> {
     /// Field name.
     name: Arc<str>,
@@ -117,6 +207,23 @@ impl<T: ?Sized, U: ?Sized> Field<T, U> {
             getter: Arc::new(getter),
         }
     }
+
+    /// The name of the field, used for translation.
+    #[must_use]
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> Clone for Field<T, U> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            name: Arc::clone(&self.name),
+            getter: Arc::clone(&self.getter),
+        }
+    }
 }
 
 impl<T: ?Sized, U: ?Sized> fmt::Debug for Field<T, U> {
@@ -151,26 +258,235 @@ impl<T: ?Sized + 'static, U: ?Sized + 'static> Field<T, U> {
 impl<T, U: ?Sized> Field<T, U> {
     /// Specifies that the field should be equal to `value`.
     #[inline]
-    pub const fn eq<V: ?Sized>(self, value: &V) -> Eq<'_, Self, V> {
-        Eq { field: self, value }
+    pub const fn eq<V: ToOwned + ?Sized>(self, value: &V) -> Eq<'_, Self, V> {
+        Eq {
+            field: self,
+            value: Cow::Borrowed(value),
+        }
     }
 
     /// Specifies that the field should not be equal to `value`.
     #[inline]
-    pub const fn ne<V: ?Sized>(self, value: &V) -> Ne<'_, Self, V> {
-        Ne { field: self, value }
+    pub const fn ne<V: ToOwned + ?Sized>(self, value: &V) -> Ne<'_, Self, V> {
+        Ne {
+            field: self,
+            value: Cow::Borrowed(value),
+        }
     }
 
     /// Specifies that the field should be greater than `value`.
     #[inline]
-    pub const fn gt<V: ?Sized>(self, value: &V) -> Gt<'_, Self, V> {
-        Gt { field: self, value }
+    pub const fn gt<V: ToOwned + ?Sized>(self, value: &V) -> Gt<'_, Self, V> {
+        Gt {
+            field: self,
+            value: Cow::Borrowed(value),
+        }
     }
 
     /// Specifies that the field should be lesser than `value`.
     #[inline]
-    pub const fn lt<V: ?Sized>(self, value: &V) -> Lt<'_, Self, V> {
-        Lt { field: self, value }
+    pub const fn lt<V: ToOwned + ?Sized>(self, value: &V) -> Lt<'_, Self, V> {
+        Lt {
+            field: self,
+            value: Cow::Borrowed(value),
+        }
+    }
+
+    /// Specifies that the field should be greater than or equal to `value`.
+    #[inline]
+    pub const fn ge<V: ToOwned + ?Sized>(self, value: &V) -> Ge<'_, Self, V> {
+        Ge {
+            field: self,
+            value: Cow::Borrowed(value),
+        }
+    }
+
+    /// Specifies that the field should be lesser than or equal to `value`.
+    #[inline]
+    pub const fn le<V: ToOwned + ?Sized>(self, value: &V) -> Le<'_, Self, V> {
+        Le {
+            field: self,
+            value: Cow::Borrowed(value),
+        }
+    }
+
+    /// Specifies that the field should be equal to any of `values`.
+    #[inline]
+    pub const fn is_in<V: Clone>(self, values: &[V]) -> In<'_, Self, V> {
+        In {
+            field: self,
+            values: Cow::Borrowed(values),
+        }
+    }
+
+    /// Specifies that the field should be between `low` and `high`, inclusive.
+    #[inline]
+    pub const fn between<'a, V: ToOwned + ?Sized>(
+        self,
+        low: &'a V,
+        high: &'a V,
+    ) -> Between<'a, Self, V> {
+        Between {
+            field: self,
+            low: Cow::Borrowed(low),
+            high: Cow::Borrowed(high),
+        }
+    }
+
+    /// Specifies that the field should be equal to the field `other` of the same element.
+    #[must_use]
+    #[inline]
+    pub const fn eq_field<W: ?Sized>(self, other: Field<T, W>) -> FieldEq<Self, Field<T, W>> {
+        FieldEq {
+            left: self,
+            right: other,
+        }
+    }
+
+    /// Specifies that the field should be not equal to the field `other` of the same element.
+    #[must_use]
+    #[inline]
+    pub const fn ne_field<W: ?Sized>(self, other: Field<T, W>) -> FieldNe<Self, Field<T, W>> {
+        FieldNe {
+            left: self,
+            right: other,
+        }
+    }
+
+    /// Specifies that the field should be greater than the field `other` of the same element.
+    #[must_use]
+    #[inline]
+    pub const fn gt_field<W: ?Sized>(self, other: Field<T, W>) -> FieldGt<Self, Field<T, W>> {
+        FieldGt {
+            left: self,
+            right: other,
+        }
+    }
+
+    /// Specifies that the field should be lesser than the field `other` of the same element.
+    #[must_use]
+    #[inline]
+    pub const fn lt_field<W: ?Sized>(self, other: Field<T, W>) -> FieldLt<Self, Field<T, W>> {
+        FieldLt {
+            left: self,
+            right: other,
+        }
+    }
+
+    /// Specifies that the field should be greater than or equal to the field `other` of the same
+    /// element.
+    #[must_use]
+    #[inline]
+    pub const fn ge_field<W: ?Sized>(self, other: Field<T, W>) -> FieldGe<Self, Field<T, W>> {
+        FieldGe {
+            left: self,
+            right: other,
+        }
+    }
+
+    /// Specifies that the field should be lesser than or equal to the field `other` of the same
+    /// element.
+    #[must_use]
+    #[inline]
+    pub const fn le_field<W: ?Sized>(self, other: Field<T, W>) -> FieldLe<Self, Field<T, W>> {
+        FieldLe {
+            left: self,
+            right: other,
+        }
+    }
+}
+
+impl<T, U: AsRef<str> + ?Sized> Field<T, U> {
+    /// Specifies that the field should start with `value`.
+    #[must_use]
+    #[inline]
+    pub const fn starts_with(self, value: &str) -> StartsWith<'_, Self> {
+        StartsWith {
+            field: self,
+            value: Cow::Borrowed(value),
+            ignore_case: false,
+        }
+    }
+
+    /// Specifies that the field should contain `value`.
+    #[must_use]
+    #[inline]
+    pub const fn contains(self, value: &str) -> Contains<'_, Self> {
+        Contains {
+            field: self,
+            value: Cow::Borrowed(value),
+            ignore_case: false,
+        }
+    }
+
+    /// Specifies that the field should end with `value`.
+    #[must_use]
+    #[inline]
+    pub const fn ends_with(self, value: &str) -> EndsWith<'_, Self> {
+        EndsWith {
+            field: self,
+            value: Cow::Borrowed(value),
+            ignore_case: false,
+        }
+    }
+
+    /// Specifies that the field should be equal to `value`, ignoring case.
+    #[must_use]
+    #[inline]
+    pub const fn eq_ignore_case(self, value: &str) -> EqIgnoreCase<'_, Self> {
+        EqIgnoreCase {
+            field: self,
+            value: Cow::Borrowed(value),
+        }
+    }
+}
+
+impl<T, U> Field<T, Option<U>> {
+    /// Specifies that the field should be [`None`].
+    #[must_use]
+    #[inline]
+    pub const fn is_none(self) -> IsNone<Self> {
+        IsNone { field: self }
+    }
+
+    /// Specifies that the field should be [`Some`].
+    #[must_use]
+    #[inline]
+    pub const fn is_some(self) -> IsSome<Self> {
+        IsSome { field: self }
+    }
+}
+
+impl<T, U: 'static> Field<T, Vec<U>> {
+    /// Specifies that any element of the field should match the query built by `predicate`.
+    ///
+    /// `predicate` is given a field referring to an element itself, which shares the name of this
+    /// field. Fields of the elements can be reached using [`then`](Self::then).
+    #[inline]
+    pub fn any_element<Q>(self, predicate: impl FnOnce(Field<U, U>) -> Q) -> AnyElement<Self, Q> {
+        let element = Field::new(Arc::clone(&self.name), |element| element);
+        AnyElement {
+            field: self,
+            predicate: predicate(element),
+        }
+    }
+}
+
+impl<T: 'static, U: Ord + ?Sized + 'static> Field<T, U> {
+    /// Order results by the field, in ascending order.
+    #[must_use]
+    #[inline]
+    pub fn asc(self) -> SortKey<T> {
+        let Self { name, getter } = self;
+        SortKey::new(name, move |a, b| getter(a).cmp(getter(b)), false)
+    }
+
+    /// Order results by the field, in descending order.
+    #[must_use]
+    #[inline]
+    pub fn desc(self) -> SortKey<T> {
+        let Self { name, getter } = self;
+        SortKey::new(name, move |a, b| getter(a).cmp(getter(b)), true)
     }
 }
 
@@ -181,22 +497,44 @@ impl<T, U: ?Sized> Field<T, U> {
 /// Note that while many endpoints ignore duplicate keys and key order, this is not actually part
 /// of any particular specification, so this implementation conservatively does not check for
 /// duplicate keys (regardless of their values) and maintains input key order.
+///
+/// Keys are usually borrowed from field names, but are owned when derived from them by an
+/// [`HttpConvention`].
 //
 // This is `Vec<_>` instead of `Box<[_]>` both because implementation becomes easier, but also
 // because callers may choose to add extra parameters beyond what is provided by the query API.
 // TODO: Feature flag to disable preserving order, if this would be more performant?
 #[cfg(feature = "rest")]
 #[expect(clippy::module_name_repetitions, reason = "Established terminology.")]
-pub type HttpQuery<'a> = Vec<(&'a str, Box<str>)>;
+pub type HttpQuery<'a> = Vec<(Cow<'a, str>, Box<str>)>;
 
+/// A parameterized SQL condition, intended to be placed in a `WHERE` clause.
+///
+/// Values are never interpolated into [`query_text`](Self::query_text). Instead, it refers to
+/// [`params`](Self::params) through the placeholders `$1, $2, ...`, which should be bound by the
+/// connector when executing the statement. Column names are quoted, and the names of all referenced
+/// columns are collected in [`columns`](Self::columns) such that the connector can check them
+/// against the table before executing anything.
+///
+/// An empty [`query_text`](Self::query_text) means that every row matches.
+///
+/// The statement may also carry the `ORDER BY`, `LIMIT` and `OFFSET` clauses used to shape the
+/// results, which should be placed after the condition; see [`clauses`](Self::clauses).
 #[cfg(feature = "postgres")]
 #[derive(Debug, Clone, Default)]
-/// Struct for sql queries
 pub struct SqlStatement {
     /// Raw SQL logic string
     pub query_text: String,
-    /// Parameters for the query
-    pub params: Vec<String>,
+    /// Parameters for the query, in placeholder order.
+    pub params: Vec<SqlParam>,
+    /// The (unquoted) names of all columns referenced by the query.
+    pub columns: Vec<String>,
+    /// The terms of the `ORDER BY` clause, such as `"year" DESC`.
+    pub order_by: Vec<String>,
+    /// The `LIMIT` clause.
+    pub limit: Option<usize>,
+    /// The `OFFSET` clause.
+    pub offset: usize,
 }
 
 #[cfg(feature = "postgres")]
@@ -207,4 +545,275 @@ impl SqlStatement {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Quote `name` as an SQL identifier, and record it as a referenced column.
+    #[inline]
+    pub fn column(&mut self, name: &str) -> String {
+        self.columns.push(name.to_owned());
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+
+    /// Add a parameter, returning the placeholder referring to it.
+    #[inline]
+    pub fn param(&mut self, param: SqlParam) -> String {
+        self.params.push(param);
+        self.params[self.params.len() - 1].placeholder(self.params.len())
+    }
+
+    /// The condition represented by this statement, which is `TRUE` if
+    /// [`query_text`](Self::query_text) is empty.
+    #[must_use]
+    #[inline]
+    pub fn condition(&self) -> &str {
+        if self.query_text.trim().is_empty() {
+            "TRUE"
+        } else {
+            &self.query_text
+        }
+    }
+
+    /// Join two statements using a binary operator, renumbering the placeholders of `rhs` to
+    /// follow those of `lhs`.
+    #[must_use]
+    #[inline]
+    pub fn combine(mut lhs: Self, operator: &str, rhs: Self) -> Self {
+        let offset = lhs.params.len();
+        lhs.query_text = format!(
+            "({}) {operator} ({})",
+            lhs.condition(),
+            shift_placeholders(rhs.condition(), offset)
+        );
+        lhs.params.extend(rhs.params);
+        lhs.columns.extend(rhs.columns);
+        lhs
+    }
+
+    /// Add clauses ordering the results as specified by `shape`. Unless the results are filtered
+    /// further after being fetched, `window` should be set to also add its limit and offset.
+    #[inline]
+    pub fn shape<T>(&mut self, shape: &Shape<T>, window: bool) {
+        for key in &shape.order {
+            let column = self.column(key.name());
+            let direction = if key.is_descending() { "DESC" } else { "ASC" };
+            self.order_by.push(format!("{column} {direction}"));
+        }
+        if window {
+            self.limit = shape.limit;
+            self.offset = shape.offset;
+        }
+    }
+
+    /// The clauses to place after the condition, each preceded by a space.
+    #[must_use]
+    #[inline]
+    pub fn clauses(&self) -> String {
+        let mut clauses = Vec::new();
+        if !self.order_by.is_empty() {
+            clauses.push(format!(" ORDER BY {}", self.order_by.join(", ")));
+        }
+        if let Some(limit) = self.limit {
+            clauses.push(format!(" LIMIT {limit}"));
+        }
+        if self.offset > 0 {
+            clauses.push(format!(" OFFSET {}", self.offset));
+        }
+        clauses.concat()
+    }
+
+    /// Check that every referenced column is one of `known`.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first referenced column not found in `known`.
+    #[inline]
+    pub fn check_columns(&self, known: &[&str]) -> Result<(), UnknownColumn> {
+        self.columns
+            .iter()
+            .find(|column| !known.contains(&column.as_str()))
+            .map_or(Ok(()), |column| Err(UnknownColumn(column.clone())))
+    }
 }
+
+/// Add `offset` to the number of every placeholder (`$1`, `$2`, ...) in `text`, skipping quoted
+/// identifiers and literals.
+#[cfg(feature = "postgres")]
+fn shift_placeholders(text: &str, offset: usize) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut quote = None;
+
+    while let Some(c) = chars.next() {
+        out.push(c);
+        match quote {
+            // A doubled (escaped) quote simply closes and reopens.
+            Some(q) if c == q => quote = None,
+            None if matches!(c, '"' | '\'') => quote = Some(c),
+            None if c == '$' => {
+                let mut digits = String::new();
+                while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                    digits.push(d);
+                }
+                if let Ok(n) = digits.parse::<usize>() {
+                    out.push_str(&(n + offset).to_string());
+                } else {
+                    out.push_str(&digits);
+                }
+            },
+            Some(_) | None => {},
+        }
+    }
+
+    out
+}
+
+/// A parameter bound to a [`SqlStatement`].
+#[cfg(feature = "postgres")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlParam {
+    /// Bound as `TEXT`.
+    Text(String),
+    /// Bound as `BIGINT`.
+    BigInt(i64),
+    /// Bound as `DOUBLE PRECISION`.
+    Double(f64),
+    /// Bound as `BOOLEAN`.
+    Bool(bool),
+    /// Bound as `NULL`. Since nothing compares equal to `NULL` in SQL, comparisons with it remain
+    /// as residue.
+    Null,
+    /// Bound as `TEXT` and cast to a named type in the query text. This is needed for
+    /// user-defined types such as enums, which have no implicit conversion from `TEXT`.
+    Cast {
+        /// The textual representation of the value, as accepted by the type's input function.
+        value: String,
+        /// The name of the SQL type to cast to.
+        sql_type: &'static str,
+    },
+}
+
+#[cfg(feature = "postgres")]
+impl SqlParam {
+    /// The placeholder referring to this parameter at 1-based position `index`.
+    #[must_use]
+    #[inline]
+    pub fn placeholder(&self, index: usize) -> String {
+        match self {
+            Self::Cast { sql_type, .. } => format!("${index}::{sql_type}"),
+            Self::Text(_) | Self::BigInt(_) | Self::Double(_) | Self::Bool(_) | Self::Null => {
+                format!("${index}")
+            },
+        }
+    }
+
+    /// Convert `value` using its [`Serialize`] implementation, such that it is spelled the way it
+    /// is deserialized. Strings and unit enum variants are bound as `TEXT`, integers as `BIGINT`
+    /// if they fit and other numbers as `DOUBLE PRECISION`, booleans as `BOOLEAN`, null, such as
+    /// [`None`], as [`Null`](Self::Null), and anything else as JSON `TEXT`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `value` fails to serialize.
+    #[inline]
+    pub fn serialized<V: Serialize + ?Sized>(value: &V) -> Result<Self, serde_json::Error> {
+        Ok(match to_value(value)? {
+            Value::String(string) => Self::Text(string),
+            Value::Bool(boolean) => Self::Bool(boolean),
+            Value::Null => Self::Null,
+            Value::Number(number) => number.as_i64().map_or_else(
+                || {
+                    number
+                        .as_f64()
+                        .map_or_else(|| Self::Text(number.to_string()), Self::Double)
+                },
+                Self::BigInt,
+            ),
+            other @ (Value::Array(_) | Value::Object(_)) => Self::Text(other.to_string()),
+        })
+    }
+
+    /// Convert `value` like [`serialized`](Self::serialized), but cast to `sql_type`. Null is not
+    /// cast.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `value` fails to serialize.
+    #[inline]
+    pub fn cast_serialized<V: Serialize + ?Sized>(
+        value: &V,
+        sql_type: &'static str,
+    ) -> Result<Self, serde_json::Error> {
+        let value = match Self::serialized(value)? {
+            Self::Text(text) | Self::Cast { value: text, .. } => text,
+            Self::BigInt(number) => number.to_string(),
+            Self::Double(number) => number.to_string(),
+            Self::Bool(boolean) => boolean.to_string(),
+            Self::Null => return Ok(Self::Null),
+        };
+        Ok(Self::Cast { value, sql_type })
+    }
+}
+
+/// A value that can be bound as a parameter of a [`SqlStatement`].
+#[cfg(feature = "postgres")]
+pub trait ToSqlParam {
+    /// Convert the value into a parameter.
+    fn to_sql_param(&self) -> SqlParam;
+}
+
+#[cfg(feature = "postgres")]
+impl<V: ToSqlParam + ?Sized> ToSqlParam for &V {
+    #[inline]
+    fn to_sql_param(&self) -> SqlParam {
+        (**self).to_sql_param()
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl ToSqlParam for str {
+    #[inline]
+    fn to_sql_param(&self) -> SqlParam {
+        SqlParam::Text(self.to_owned())
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl ToSqlParam for String {
+    #[inline]
+    fn to_sql_param(&self) -> SqlParam {
+        SqlParam::Text(self.clone())
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl ToSqlParam for bool {
+    #[inline]
+    fn to_sql_param(&self) -> SqlParam {
+        SqlParam::Bool(*self)
+    }
+}
+
+/// Implement [`ToSqlParam`] for types losslessly convertible to some variant of [`SqlParam`].
+#[cfg(feature = "postgres")]
+macro_rules! impl_to_sql_param {
+    ($variant:ident($target:ty): $($ty:ty),+) => {
+        $(
+            impl ToSqlParam for $ty {
+                #[inline]
+                fn to_sql_param(&self) -> SqlParam {
+                    SqlParam::$variant(<$target>::from(*self))
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "postgres")]
+impl_to_sql_param!(BigInt(i64): i8, i16, i32, i64, u8, u16, u32);
+#[cfg(feature = "postgres")]
+impl_to_sql_param!(Double(f64): f32, f64);
+
+/// Error raised when a [`SqlStatement`] references a column not present in the table.
+#[cfg(feature = "postgres")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Error)]
+#[error("The column `{0}` does not exist in the table.")]
+pub struct UnknownColumn(pub String);
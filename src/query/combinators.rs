@@ -2,17 +2,30 @@
 //!
 //! This module defines the core query abstract syntax tree (AST) and the semantics for evaluating
 //! queries against in-memory data. Queries are composable, immutable, and evaluated recursively.
+//!
+//! Besides constructing combinators directly, comparisons and logical combinators can be combined
+//! using the `&`, `|`, `^` and `!` operators, as in
+//! `Book::author().eq(a) & (Book::title().eq(b) | Book::title().eq(c))`.
 
-#[cfg(feature = "postgres")]
-use super::SqlStatement;
-use super::{Field, Query};
+#[cfg(any(feature = "rest", feature = "postgres"))]
+use super::Single;
+use super::{Field, IntoOwned, Node, Operand, Operator, Primitive, Query, Shape, SortKey};
 #[cfg(feature = "rest")]
-use super::{HttpQuery, Single};
+use super::{
+    HttpConvention, HttpQuery,
+    http::{ListParams, ParamName, RangeParams},
+};
+#[cfg(feature = "postgres")]
+use super::{SqlParam, SqlStatement, ToSqlParam};
 use either::Either;
 use nameof::{name_of, name_of_type};
+use serde::Serialize;
+use std::any::TypeId;
+use std::borrow::Cow;
 #[cfg(feature = "rest")]
 use std::collections::HashSet;
 use std::fmt::{Debug, Display, Error as FmtError, Formatter};
+use std::ops::{self, BitAnd, BitOr, BitXor};
 
 /// Matches everything.
 ///
@@ -24,44 +37,247 @@ pub struct True;
 
 /// Checks if the field specified by `field` is equal to `value`.
 #[derive(Clone)]
-pub struct Eq<'a, F, V: ?Sized> {
+pub struct Eq<'a, F, V: ToOwned + ?Sized> {
     /// The field to check equality on.
     pub(super) field: F,
     /// The value to compare the field to.
-    pub value: &'a V,
+    pub value: Cow<'a, V>,
 }
 
 /// Checks if the field specified by `field` is not equal to `value`.
 ///
 /// This is a pure query node and does not perform any evaluation by itself.
 #[derive(Clone)]
-pub struct Ne<'a, F, V: ?Sized> {
+pub struct Ne<'a, F, V: ToOwned + ?Sized> {
     /// The field to check inequality on.
     pub(super) field: F,
     /// The value to compare the field to.
-    pub value: &'a V,
+    pub value: Cow<'a, V>,
 }
 
 /// Checks if the field specified by `field` is greater than `value`.
 ///
 /// This is a pure query node and does not perform any evaluation by itself.
 #[derive(Clone)]
-pub struct Gt<'a, F, V: ?Sized> {
+pub struct Gt<'a, F, V: ToOwned + ?Sized> {
     /// The field to perform comparison on.
     pub(super) field: F,
     /// The value to compare the field to.
-    pub value: &'a V,
+    pub value: Cow<'a, V>,
 }
 
 /// Checks if the field specified by `field` is lesser than `value`.
 ///
 /// This is a pure query node and does not perform any evaluation by itself.
 #[derive(Clone)]
-pub struct Lt<'a, F, V: ?Sized> {
+pub struct Lt<'a, F, V: ToOwned + ?Sized> {
+    /// The field to perform comparison on.
+    pub(super) field: F,
+    /// The value to compare the field to.
+    pub value: Cow<'a, V>,
+}
+
+/// Checks if the field specified by `field` is greater than or equal to `value`.
+///
+/// This is a pure query node and does not perform any evaluation by itself.
+#[derive(Clone)]
+pub struct Ge<'a, F, V: ToOwned + ?Sized> {
+    /// The field to perform comparison on.
+    pub(super) field: F,
+    /// The value to compare the field to.
+    pub value: Cow<'a, V>,
+}
+
+/// Checks if the field specified by `field` is lesser than or equal to `value`.
+///
+/// This is a pure query node and does not perform any evaluation by itself.
+#[derive(Clone)]
+pub struct Le<'a, F, V: ToOwned + ?Sized> {
+    /// The field to perform comparison on.
+    pub(super) field: F,
+    /// The value to compare the field to.
+    pub value: Cow<'a, V>,
+}
+
+/// Checks if the field specified by `field` is between `low` and `high`, inclusive.
+///
+/// This is a pure query node and does not perform any evaluation by itself.
+#[derive(Clone)]
+pub struct Between<'a, F, V: ToOwned + ?Sized> {
     /// The field to perform comparison on.
     pub(super) field: F,
+    /// The lower bound.
+    pub low: Cow<'a, V>,
+    /// The upper bound.
+    pub high: Cow<'a, V>,
+}
+
+/// Checks if the field specified by `field` is equal to any of `values`.
+///
+/// This is a pure query node and does not perform any evaluation by itself.
+#[derive(Clone)]
+pub struct In<'a, F, V: Clone> {
+    /// The field to check membership on.
+    pub(super) field: F,
+    /// The values to compare the field to.
+    pub values: Cow<'a, [V]>,
+}
+
+/// Checks if the string field specified by `field` starts with `value`.
+///
+/// This is a pure query node and does not perform any evaluation by itself.
+#[derive(Clone)]
+pub struct StartsWith<'a, F> {
+    /// The field to perform matching on.
+    pub(super) field: F,
+    /// The pattern to match.
+    pub value: Cow<'a, str>,
+    /// Whether to ignore case when matching.
+    pub ignore_case: bool,
+}
+
+/// Checks if the string field specified by `field` contains `value`.
+///
+/// This is a pure query node and does not perform any evaluation by itself.
+#[derive(Clone)]
+pub struct Contains<'a, F> {
+    /// The field to perform matching on.
+    pub(super) field: F,
+    /// The pattern to match.
+    pub value: Cow<'a, str>,
+    /// Whether to ignore case when matching.
+    pub ignore_case: bool,
+}
+
+/// Checks if the string field specified by `field` ends with `value`.
+///
+/// This is a pure query node and does not perform any evaluation by itself.
+#[derive(Clone)]
+pub struct EndsWith<'a, F> {
+    /// The field to perform matching on.
+    pub(super) field: F,
+    /// The pattern to match.
+    pub value: Cow<'a, str>,
+    /// Whether to ignore case when matching.
+    pub ignore_case: bool,
+}
+
+/// Checks if the string field specified by `field` is equal to `value`, ignoring case.
+///
+/// This is a pure query node and does not perform any evaluation by itself.
+#[derive(Clone)]
+pub struct EqIgnoreCase<'a, F> {
+    /// The field to check equality on.
+    pub(super) field: F,
     /// The value to compare the field to.
-    pub value: &'a V,
+    pub value: Cow<'a, str>,
+}
+
+/// Checks if the field specified by `left` is equal to the field specified by `right`, both
+/// belonging to the same element.
+///
+/// This is a pure query node and does not perform any evaluation by itself. Since it compares to
+/// a field rather than a value, it has no [primitive](Query::primitive) form.
+#[derive(Clone)]
+pub struct FieldEq<L, R> {
+    /// The field on the left-hand side.
+    pub(super) left: L,
+    /// The field on the right-hand side.
+    pub(super) right: R,
+}
+
+/// Checks if the field specified by `left` is not equal to the field specified by `right`, both
+/// belonging to the same element.
+///
+/// This is a pure query node and does not perform any evaluation by itself. Since it compares to
+/// a field rather than a value, it has no [primitive](Query::primitive) form.
+#[derive(Clone)]
+pub struct FieldNe<L, R> {
+    /// The field on the left-hand side.
+    pub(super) left: L,
+    /// The field on the right-hand side.
+    pub(super) right: R,
+}
+
+/// Checks if the field specified by `left` is greater than the field specified by `right`, both
+/// belonging to the same element.
+///
+/// This is a pure query node and does not perform any evaluation by itself. Since it compares to
+/// a field rather than a value, it has no [primitive](Query::primitive) form.
+#[derive(Clone)]
+pub struct FieldGt<L, R> {
+    /// The field on the left-hand side.
+    pub(super) left: L,
+    /// The field on the right-hand side.
+    pub(super) right: R,
+}
+
+/// Checks if the field specified by `left` is lesser than the field specified by `right`, both
+/// belonging to the same element.
+///
+/// This is a pure query node and does not perform any evaluation by itself. Since it compares to
+/// a field rather than a value, it has no [primitive](Query::primitive) form.
+#[derive(Clone)]
+pub struct FieldLt<L, R> {
+    /// The field on the left-hand side.
+    pub(super) left: L,
+    /// The field on the right-hand side.
+    pub(super) right: R,
+}
+
+/// Checks if the field specified by `left` is greater than or equal to the field specified by
+/// `right`, both belonging to the same element.
+///
+/// This is a pure query node and does not perform any evaluation by itself. Since it compares to
+/// a field rather than a value, it has no [primitive](Query::primitive) form.
+#[derive(Clone)]
+pub struct FieldGe<L, R> {
+    /// The field on the left-hand side.
+    pub(super) left: L,
+    /// The field on the right-hand side.
+    pub(super) right: R,
+}
+
+/// Checks if the field specified by `left` is lesser than or equal to the field specified by
+/// `right`, both belonging to the same element.
+///
+/// This is a pure query node and does not perform any evaluation by itself. Since it compares to
+/// a field rather than a value, it has no [primitive](Query::primitive) form.
+#[derive(Clone)]
+pub struct FieldLe<L, R> {
+    /// The field on the left-hand side.
+    pub(super) left: L,
+    /// The field on the right-hand side.
+    pub(super) right: R,
+}
+
+/// Checks if the optional field specified by `field` is [`None`].
+///
+/// This is a pure query node and does not perform any evaluation by itself.
+#[derive(Clone)]
+pub struct IsNone<F> {
+    /// The field to check.
+    pub(super) field: F,
+}
+
+/// Checks if the optional field specified by `field` is [`Some`].
+///
+/// This is a pure query node and does not perform any evaluation by itself.
+#[derive(Clone)]
+pub struct IsSome<F> {
+    /// The field to check.
+    pub(super) field: F,
+}
+
+/// Checks if any element of the collection field specified by `field` matches `predicate`.
+///
+/// This is a pure query node and does not perform any evaluation by itself.
+#[derive(Clone)]
+pub struct AnyElement<F, Q> {
+    /// The field containing the elements.
+    pub(super) field: F,
+    /// The query to match each element against.
+    pub predicate: Q,
 }
 
 /// Performs AND on the two subqueries.
@@ -80,16 +296,323 @@ pub struct Xor<L, R>(pub L, pub R);
 #[derive(Clone)]
 pub struct Not<Q>(pub Q);
 
+/// Performs AND on any number of subqueries. Matches everything if there are no subqueries.
+///
+/// The subqueries all have the same type, which may be a boxed query such as
+/// `Box<dyn Query<T> + Sync>` if the number or kinds of conditions are only known at runtime.
+#[derive(Clone)]
+pub struct All<Q>(pub Vec<Q>);
+
+/// Performs OR on any number of subqueries. Matches nothing if there are no subqueries.
+///
+/// See [`All`] regarding the type of the subqueries.
+#[derive(Clone)]
+pub struct Any<Q>(pub Vec<Q>);
+
+/// Checks that exactly one of any number of subqueries matches. Matches nothing if there are no
+/// subqueries.
+///
+/// Note that this differs from chained [`Xor`]s, which check that an odd number of subqueries
+/// match. See [`All`] regarding the type of the subqueries.
+#[derive(Clone)]
+pub struct One<Q>(pub Vec<Q>);
+
+impl<F> StartsWith<'_, F> {
+    /// Ignore case when matching.
+    #[must_use]
+    #[inline]
+    pub fn ignore_case(self) -> Self {
+        Self {
+            ignore_case: true,
+            ..self
+        }
+    }
+}
+
+impl<F> Contains<'_, F> {
+    /// Ignore case when matching.
+    #[must_use]
+    #[inline]
+    pub fn ignore_case(self) -> Self {
+        Self {
+            ignore_case: true,
+            ..self
+        }
+    }
+}
+
+impl<F> EndsWith<'_, F> {
+    /// Ignore case when matching.
+    #[must_use]
+    #[inline]
+    pub fn ignore_case(self) -> Self {
+        Self {
+            ignore_case: true,
+            ..self
+        }
+    }
+}
+
+/// Orders the results of a query by the given keys, the first being most significant.
+///
+/// Ordering is always applied before any [`Limit`] or [`Offset`], regardless of how they are
+/// nested. If nested within another `OrderBy`, the keys of the outer one are more significant.
+/// Like the other modifiers, this is only respected for the outermost query; see
+/// [`Query::shape`].
+#[derive(Clone)]
+pub struct OrderBy<Q, T>(pub Q, pub Vec<SortKey<T>>);
+
+/// Keeps at most a number of results of a query.
+///
+/// See [`OrderBy`] regarding nesting.
+#[derive(Clone)]
+pub struct Limit<Q>(pub Q, pub usize);
+
+/// Skips a number of results of a query.
+///
+/// See [`OrderBy`] regarding nesting.
+#[derive(Clone)]
+pub struct Offset<Q>(pub Q, pub usize);
+
 // TODO: Possible future combinators:
-// - Remaining comparators: `Ge`, `Le`.
 // - Remaining logic gates: `Nand`, `Nor`, `Xor`, `Xnor`.
 // However, since queries are expressed through types, the compiler should be able to optimize
 // them well as is. As such, these above combinators would be more of a convenience feature rather
 // than new functionality.
-// - Variadic logic gates: `All`, `Any`, `One`.
-// - Interconnected field equality (e.g. `.foo == .bar`).
-// - Type-specific queries (e.g. `StartsWith` for strings).
-// - `Limit`.
+
+/// Whether `U` is known to be totally ordered, such that an ordering comparison fails exactly when
+/// its opposite succeeds.
+///
+/// This is not the case for floating-point numbers, since NaN is unordered, nor in general for
+/// types implementing [`PartialOrd`], so only primitive integers, booleans, characters and strings
+/// are recognized.
+fn totally_ordered<U: ?Sized + 'static>() -> bool {
+    [
+        TypeId::of::<i8>(),
+        TypeId::of::<i16>(),
+        TypeId::of::<i32>(),
+        TypeId::of::<i64>(),
+        TypeId::of::<i128>(),
+        TypeId::of::<isize>(),
+        TypeId::of::<u8>(),
+        TypeId::of::<u16>(),
+        TypeId::of::<u32>(),
+        TypeId::of::<u64>(),
+        TypeId::of::<u128>(),
+        TypeId::of::<usize>(),
+        TypeId::of::<bool>(),
+        TypeId::of::<char>(),
+        TypeId::of::<str>(),
+        TypeId::of::<String>(),
+    ]
+    .contains(&TypeId::of::<U>())
+}
+
+/// Forget the negation of the ordering comparison `leaf` on values of `U` unless `U` is
+/// [totally ordered](totally_ordered). Otherwise, the negation stays a [`Not`].
+fn ordering_leaf<T, U: ?Sized + 'static>(leaf: Node<'_, T>) -> Node<'_, T> {
+    match leaf {
+        Node::Leaf { predicate, .. } if !totally_ordered::<U>() => Node::Leaf {
+            predicate,
+            negation: None,
+        },
+        node @ (Node::True
+        | Node::False
+        | Node::Leaf { .. }
+        | Node::Not(_)
+        | Node::And(_)
+        | Node::Or(_)) => node,
+    }
+}
+
+/// Backing implementation for the `to_sql_single` methods of comparisons between a field and a
+/// value. See [`sql_params_to_single_impl`].
+#[cfg(feature = "postgres")]
+fn sql_comparison_impl<'a, T, V>(
+    whole: &'a (dyn Query<T> + Sync),
+    name: &str,
+    operator: &str,
+    value: &V,
+) -> Single<'a, SqlStatement, T>
+where
+    V: ToSqlParam + ?Sized,
+{
+    let mut query = SqlStatement::new();
+    let column = query.column(name);
+    let param = query.param(value.to_sql_param());
+    query.query_text = format!("{column} {operator} {param}");
+    sql_params_to_single_impl(whole, query)
+}
+
+/// Backing implementation for the `to_sql_single` methods of primitives with parameters.
+///
+/// Since nothing compares equal to `NULL` in SQL, if any parameter is [`SqlParam::Null`], the query
+/// is empty and **`whole` remains as residue**.
+#[cfg(feature = "postgres")]
+fn sql_params_to_single_impl<T>(
+    whole: &(dyn Query<T> + Sync),
+    query: SqlStatement,
+) -> Single<'_, SqlStatement, T> {
+    if query.params.contains(&SqlParam::Null) {
+        Single {
+            query: SqlStatement::new(),
+            residue: vec![whole],
+        }
+    } else {
+        Single {
+            query,
+            residue: Vec::new(),
+        }
+    }
+}
+
+/// Backing implementation for the `to_sql_single` methods of comparisons between two fields.
+/// `condition` receives the quoted columns.
+#[cfg(feature = "postgres")]
+fn sql_field_comparison_impl(
+    left: &str,
+    right: &str,
+    condition: impl FnOnce(&str, &str) -> String,
+) -> SqlStatement {
+    let mut query = SqlStatement::new();
+    let left = query.column(left);
+    let right = query.column(right);
+    query.query_text = condition(&left, &right);
+    query
+}
+
+/// Construct the parameter used for a comparison, if the convention specifies one, the source
+/// supports the field and the value can be written.
+#[cfg(feature = "rest")]
+fn convention_param<'a, V>(
+    convention: &HttpConvention,
+    param: Option<&ParamName>,
+    name: &'a str,
+    value: &V,
+) -> Option<(Cow<'a, str>, Box<str>)>
+where
+    V: Display + Serialize + ?Sized,
+{
+    let HttpConvention { fields, values, .. } = convention;
+    let param = fields.param(name, param?)?;
+    Some((param, fields.value(name, values.write(value)?)))
+}
+
+/// Backing implementation for the `to_http_single` methods of primitives translated into a fixed
+/// number of parameters. If any parameter is missing, the primitive remains as residue.
+#[cfg(feature = "rest")]
+fn params_to_single_impl<'a, T, const N: usize>(
+    query: &'a (dyn Query<T> + Sync),
+    params: [Option<(Cow<'a, str>, Box<str>)>; N],
+) -> Single<'a, HttpQuery<'a>, T> {
+    let residue = if params.iter().all(Option::is_some) {
+        Vec::new()
+    } else {
+        vec![query]
+    };
+
+    Single {
+        query: params.into_iter().flatten().collect(),
+        residue,
+    }
+}
+
+/// Backing implementation for the `to_http_multi` methods of primitives translated into a fixed
+/// number of parameters. Translation is impossible if any parameter is missing.
+#[cfg(feature = "rest")]
+fn params_to_multi_impl<const N: usize>(
+    params: [Option<(Cow<'_, str>, Box<str>)>; N],
+) -> Option<Vec<HttpQuery<'_>>> {
+    params
+        .into_iter()
+        .collect::<Option<HttpQuery<'_>>>()
+        .map(|query| vec![query])
+}
+
+/// Escape the special characters of `LIKE` patterns, such that `value` only matches itself.
+#[cfg(feature = "postgres")]
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Backing implementation for the `to_sql_single` methods of string predicates. `pattern` should
+/// already be escaped.
+#[cfg(feature = "postgres")]
+fn like_sql_impl(name: &str, pattern: String, ignore_case: bool) -> SqlStatement {
+    let mut query = SqlStatement::new();
+    let column = query.column(name);
+    let param = query.param(SqlParam::Text(pattern));
+    let operator = if ignore_case { "ILIKE" } else { "LIKE" };
+    query.query_text = format!("{column} {operator} {param}");
+    query
+}
+
+/// Evaluate a string predicate, given as a function of the field value and the pattern.
+fn pattern_evaluate(
+    haystack: &str,
+    needle: &str,
+    ignore_case: bool,
+    predicate: impl FnOnce(&str, &str) -> bool,
+) -> bool {
+    if ignore_case {
+        predicate(&haystack.to_lowercase(), &needle.to_lowercase())
+    } else {
+        predicate(haystack, needle)
+    }
+}
+
+/// Backing implementation for the `to_http_single` methods of pattern predicates, where `param` is
+/// the parameter the convention specifies for the predicate.
+#[cfg(feature = "rest")]
+fn pattern_to_single_impl<'a, T>(
+    query: &'a (dyn Query<T> + Sync),
+    param: Option<&ParamName>,
+    convention: &HttpConvention,
+    name: &'a str,
+    value: &str,
+    ignore_case: bool,
+) -> Single<'a, HttpQuery<'a>, T> {
+    let HttpConvention { string, fields, .. } = convention;
+    let param = param.and_then(|param| fields.param(name, param));
+    match param {
+        Some(param) if ignore_case == string.ignore_case => Single {
+            query: vec![(param, fields.value(name, value.to_owned()))],
+            residue: Vec::new(),
+        },
+        // A case-insensitive source selects a superset of the desired elements.
+        Some(param) if string.ignore_case => Single {
+            query: vec![(param, fields.value(name, value.to_owned()))],
+            residue: vec![query],
+        },
+        _ => Single {
+            query: HttpQuery::new(),
+            residue: vec![query],
+        },
+    }
+}
+
+/// Backing implementation for the `to_http_multi` methods of pattern predicates. Translation is
+/// only possible if the source matches with the same case sensitivity as the predicate.
+#[cfg(feature = "rest")]
+fn pattern_to_multi_impl<'a>(
+    param: Option<&ParamName>,
+    convention: &HttpConvention,
+    name: &'a str,
+    value: &str,
+    ignore_case: bool,
+) -> Option<Vec<HttpQuery<'a>>> {
+    let HttpConvention { string, fields, .. } = convention;
+    let param = param.filter(|_| ignore_case == string.ignore_case)?;
+    let param = fields.param(name, param)?;
+    Some(vec![vec![(param, fields.value(name, value.to_owned()))]])
+}
 
 impl<T> Query<T> for True {
     /// Returns `true`.
@@ -98,14 +621,16 @@ impl<T> Query<T> for True {
         true
     }
 
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        Some(Node::True)
+    }
+
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
         Single {
-            query: SqlStatement {
-                query_text: String::new(),
-                params: Vec::new(),
-            },
+            query: SqlStatement::new(),
             residue: Vec::new(),
         }
     }
@@ -119,7 +644,7 @@ impl<T> Query<T> for True {
     /// Returns a query with no parameters and no residue.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_single(&self) -> Single<'_, HttpQuery<'_>, T> {
+    fn to_http_single(&self, _: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
         Single {
             query: HttpQuery::new(),
             residue: Vec::new(),
@@ -129,7 +654,7 @@ impl<T> Query<T> for True {
     /// Returns a single query with no parameters.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_multi(&self) -> Option<Vec<HttpQuery<'_>>> {
+    fn to_http_multi(&self, _: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
         Some(vec![HttpQuery::new()])
     }
 }
@@ -140,7 +665,10 @@ where
     // TODO: This bound is not required for `evauluate`, but there will be many situations like
     // this one where translation methods require more bounds. Is adding them to the entire trait
     // implementation acceptable? Should the bound at least be feature gated?
-    V: Display + ?Sized,
+    V: Debug + Display + Serialize + Sync + ToOwned + ?Sized,
+    V::Owned: Send + Sync,
+    #[cfg(feature = "postgres")]
+    V: ToSqlParam,
 {
     #[inline]
     fn evaluate(&self, data: &T) -> bool {
@@ -148,54 +676,85 @@ where
             field: Field { getter, .. },
             value,
         } = self;
-        getter(data) == *value
+        getter(data) == &**value
+    }
+
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        let Self { field, value } = self;
+        let negation = Ne {
+            field: field.clone(),
+            value: Cow::Borrowed(&**value),
+        };
+        Some(Node::leaf(self, negation))
+    }
+
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        let Self { field, value } = self;
+        Some(Primitive::new(
+            &field.name,
+            Operator::Eq,
+            vec![Box::new(&**value)],
+        ))
     }
 
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
-        Single {
-            query: SqlStatement {
-                query_text: format!("{} = '{}'", self.field.name, self.value),
-                params: vec![self.value.to_string()],
-            },
-            residue: Vec::new(),
-        }
+        let Self { field, value } = self;
+        sql_comparison_impl(self, &field.name, "=", &**value)
     }
 
+    /// Translation is impossible if the value is null.
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
-        Some(vec![self.to_sql_single().query])
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
     }
 
-    /// Returns a query with one parameter, that being the field name and `value.to_string()`, and
-    /// no residue.
+    /// Returns a query with one parameter, that being the field name and the value, as written and
+    /// mapped by the convention, and no residue. If the source does not support the field, or the
+    /// value can not be written, **this entire (sub)query remains as residue**.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_single(&self) -> Single<'_, HttpQuery<'_>, T> {
-        let Self { field: _, value } = self;
-        Single {
-            query: vec![(&self.field.name, value.to_string().into())],
-            residue: Vec::new(),
-        }
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self { field, value } = self;
+        params_to_single_impl(
+            self,
+            [convention_param(
+                convention,
+                Some(&ParamName::default()),
+                &field.name,
+                &**value,
+            )],
+        )
     }
 
-    /// Returns a single query with one parameter, that being the field name and
-    /// `value.to_string()`.
+    /// Returns a single query with one parameter, that being the field name and the value, as
+    /// written and mapped by the convention. Translation is impossible if the source does not
+    /// support the field, or the value can not be written.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_multi(&self) -> Option<Vec<HttpQuery<'_>>> {
-        let Self { field: _, value } = self;
-        let query = vec![(&*self.field.name, value.to_string().into())];
-        Some(vec![query])
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self { field, value } = self;
+        params_to_multi_impl([convention_param(
+            convention,
+            Some(&ParamName::default()),
+            &field.name,
+            &**value,
+        )])
     }
 }
 
 impl<T, U, V> Query<T> for Ne<'_, Field<T, U>, V>
 where
     U: PartialEq<V> + ?Sized,
-    V: Sync + ?Sized + ToString,
+    V: Debug + Display + Serialize + Sync + ToOwned + ?Sized,
+    V::Owned: Send + Sync,
+    #[cfg(feature = "postgres")]
+    V: ToSqlParam,
 {
     #[inline]
     fn evaluate(&self, data: &T) -> bool {
@@ -203,32 +762,49 @@ where
             field: Field { getter, .. },
             value,
         } = self;
-        getter(data) != *value
+        getter(data) != &**value
+    }
+
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        let Self { field, value } = self;
+        let negation = Eq {
+            field: field.clone(),
+            value: Cow::Borrowed(&**value),
+        };
+        Some(Node::leaf(self, negation))
+    }
+
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        let Self { field, value } = self;
+        Some(Primitive::new(
+            &field.name,
+            Operator::Ne,
+            vec![Box::new(&**value)],
+        ))
     }
 
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
-        Single {
-            query: SqlStatement {
-                query_text: format!("{} != '{}'", self.field.name, self.value.to_string()),
-                params: vec![self.value.to_string()],
-            },
-            residue: Vec::new(),
-        }
+        let Self { field, value } = self;
+        sql_comparison_impl(self, &field.name, "!=", &**value)
     }
 
+    /// Translation is impossible if the value is null.
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
-        Some(vec![self.to_sql_single().query])
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
     }
 
     /// Returns a single query with no parameters, meaning **this entire (sub)query remains as
     /// residue**.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_single(&self) -> Single<'_, HttpQuery<'_>, T> {
+    fn to_http_single(&self, _: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
         Single {
             query: HttpQuery::new(),
             residue: vec![self],
@@ -238,15 +814,18 @@ where
     /// Translation is impossible.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_multi(&self) -> Option<Vec<HttpQuery<'_>>> {
+    fn to_http_multi(&self, _: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
         None
     }
 }
 
 impl<T, U, V> Query<T> for Gt<'_, Field<T, U>, V>
 where
-    U: PartialOrd<V> + ?Sized,
-    V: Sync + ?Sized + ToString,
+    U: PartialOrd<V> + ?Sized + 'static,
+    V: Debug + Display + Serialize + Sync + ToOwned + ?Sized,
+    V::Owned: Send + Sync,
+    #[cfg(feature = "postgres")]
+    V: ToSqlParam,
 {
     #[inline]
     fn evaluate(&self, data: &T) -> bool {
@@ -254,51 +833,84 @@ where
             field: Field { getter, .. },
             value,
         } = self;
-        getter(data) > *value
+        getter(data) > &**value
+    }
+
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        let Self { field, value } = self;
+        let negation = Le {
+            field: field.clone(),
+            value: Cow::Borrowed(&**value),
+        };
+        Some(ordering_leaf::<T, U>(Node::leaf(self, negation)))
+    }
+
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        let Self { field, value } = self;
+        Some(Primitive::new(
+            &field.name,
+            Operator::Gt,
+            vec![Box::new(&**value)],
+        ))
     }
 
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
-        Single {
-            query: SqlStatement {
-                query_text: format!("{} > '{}'", self.field.name, self.value.to_string()),
-                params: vec![self.value.to_string()],
-            },
-            // Empty residue. Postgres handles the logic natively
-            residue: Vec::new(),
-        }
+        let Self { field, value } = self;
+        sql_comparison_impl(self, &field.name, ">", &**value)
     }
 
+    /// Translation is impossible if the value is null.
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
-        Some(vec![self.to_sql_single().query])
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
     }
 
-    /// Returns a single query with no paramqters, meaning **this entire (sub)query remains as
+    /// Returns a query with one parameter named according to [`RangeParams::gt`] and no residue.
+    /// If the convention specifies no such parameter, **this entire (sub)query remains as
     /// residue**.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_single(&self) -> Single<'_, HttpQuery<'_>, T> {
-        Single {
-            query: HttpQuery::new(),
-            residue: vec![self],
-        }
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self { field, value } = self;
+        params_to_single_impl(
+            self,
+            [convention_param(
+                convention,
+                convention.range.gt.as_ref(),
+                &field.name,
+                &**value,
+            )],
+        )
     }
 
-    /// Translation is impossible.
+    /// Returns a single query with one parameter named according to [`RangeParams::gt`].
+    /// Translation is impossible if the convention specifies no such parameter.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_multi(&self) -> Option<Vec<HttpQuery<'_>>> {
-        None
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self { field, value } = self;
+        params_to_multi_impl([convention_param(
+            convention,
+            convention.range.gt.as_ref(),
+            &field.name,
+            &**value,
+        )])
     }
 }
 
 impl<T, U, V> Query<T> for Lt<'_, Field<T, U>, V>
 where
-    U: PartialOrd<V> + ?Sized,
-    V: Sync + ?Sized + ToString,
+    U: PartialOrd<V> + ?Sized + 'static,
+    V: Debug + Display + Serialize + Sync + ToOwned + ?Sized,
+    V::Owned: Send + Sync,
+    #[cfg(feature = "postgres")]
+    V: ToSqlParam,
 {
     #[inline]
     fn evaluate(&self, data: &T) -> bool {
@@ -306,285 +918,536 @@ where
             field: Field { getter, .. },
             value,
         } = self;
-        getter(data) < *value
+        getter(data) < &**value
+    }
+
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        let Self { field, value } = self;
+        let negation = Ge {
+            field: field.clone(),
+            value: Cow::Borrowed(&**value),
+        };
+        Some(ordering_leaf::<T, U>(Node::leaf(self, negation)))
+    }
+
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        let Self { field, value } = self;
+        Some(Primitive::new(
+            &field.name,
+            Operator::Lt,
+            vec![Box::new(&**value)],
+        ))
     }
 
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
-        Single {
-            query: SqlStatement {
-                query_text: format!("{} < '{}'", self.field.name, self.value.to_string()),
-                params: vec![self.value.to_string()],
-            },
-            residue: Vec::new(),
-        }
+        let Self { field, value } = self;
+        sql_comparison_impl(self, &field.name, "<", &**value)
     }
 
+    /// Translation is impossible if the value is null.
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
-        Some(vec![self.to_sql_single().query])
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
     }
 
-    /// Returns a single query with no parameters, meaning **this entire (sub)query remains as
+    /// Returns a query with one parameter named according to [`RangeParams::lt`] and no residue.
+    /// If the convention specifies no such parameter, **this entire (sub)query remains as
     /// residue**.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_single(&self) -> Single<'_, HttpQuery<'_>, T> {
-        Single {
-            query: HttpQuery::new(),
-            residue: vec![self],
-        }
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self { field, value } = self;
+        params_to_single_impl(
+            self,
+            [convention_param(
+                convention,
+                convention.range.lt.as_ref(),
+                &field.name,
+                &**value,
+            )],
+        )
     }
 
-    /// Translation is impossible.
+    /// Returns a single query with one parameter named according to [`RangeParams::lt`].
+    /// Translation is impossible if the convention specifies no such parameter.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_multi(&self) -> Option<Vec<HttpQuery<'_>>> {
-        None
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self { field, value } = self;
+        params_to_multi_impl([convention_param(
+            convention,
+            convention.range.lt.as_ref(),
+            &field.name,
+            &**value,
+        )])
     }
 }
 
-impl<T, L, R> Query<T> for And<L, R>
+impl<T, U, V> Query<T> for Ge<'_, Field<T, U>, V>
 where
-    L: Query<T>,
-    R: Query<T>,
+    U: PartialOrd<V> + ?Sized + 'static,
+    V: Debug + Display + Serialize + Sync + ToOwned + ?Sized,
+    V::Owned: Send + Sync,
+    #[cfg(feature = "postgres")]
+    V: ToSqlParam,
 {
     #[inline]
     fn evaluate(&self, data: &T) -> bool {
-        let Self(lhs, rhs) = self;
-        lhs.evaluate(data) && rhs.evaluate(data)
+        let Self {
+            field: Field { getter, .. },
+            value,
+        } = self;
+        getter(data) >= &**value
     }
 
-    #[cfg(feature = "postgres")]
     #[inline]
-    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
-        let Self(lhs, rhs) = self;
-        let mut l_single = lhs.to_sql_single();
-        let mut r_single = rhs.to_sql_single();
+    fn node(&self) -> Option<Node<'_, T>> {
+        let Self { field, value } = self;
+        let negation = Lt {
+            field: field.clone(),
+            value: Cow::Borrowed(&**value),
+        };
+        Some(ordering_leaf::<T, U>(Node::leaf(self, negation)))
+    }
 
-        l_single.query.params.append(&mut r_single.query.params);
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        let Self { field, value } = self;
+        Some(Primitive::new(
+            &field.name,
+            Operator::Ge,
+            vec![Box::new(&**value)],
+        ))
+    }
 
-        Single {
-            query: SqlStatement {
-                query_text: format!(
-                    "({}) AND ({})",
-                    l_single.query.query_text, r_single.query.query_text
-                ),
-                params: l_single.query.params,
-            },
-            residue: Vec::new(),
-        }
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        let Self { field, value } = self;
+        sql_comparison_impl(self, &field.name, ">=", &**value)
     }
 
+    /// Translation is impossible if the value is null.
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
-        Some(vec![self.to_sql_single().query])
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
     }
 
-    /// Combines both the parameter lists and the residues of both subqueries.
+    /// Returns a query with one parameter named according to [`RangeParams::ge`] and no residue.
+    /// If the convention specifies no such parameter, **this entire (sub)query remains as
+    /// residue**.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_single(&self) -> Single<'_, HttpQuery<'_>, T> {
-        let Self(lhs, rhs) = self;
-        let Single {
-            mut query,
-            mut residue,
-        } = lhs.to_http_single();
-        let mut rhs = rhs.to_http_single();
-
-        query.append(&mut rhs.query);
-        residue.append(&mut rhs.residue);
-
-        Single { query, residue }
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self { field, value } = self;
+        params_to_single_impl(
+            self,
+            [convention_param(
+                convention,
+                convention.range.ge.as_ref(),
+                &field.name,
+                &**value,
+            )],
+        )
     }
 
-    /// Creates the cartesian product of all parts from both subqueries. Although this is
-    /// theoretically a "perfect" translation, **the number of output queries grows very quickly
-    /// for complex queries**.
+    /// Returns a single query with one parameter named according to [`RangeParams::ge`].
+    /// Translation is impossible if the convention specifies no such parameter.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_multi(&self) -> Option<Vec<HttpQuery<'_>>> {
-        let Self(lhs, rhs) = self;
-        let lhs = lhs.to_http_multi()?;
-        let rhs = rhs.to_http_multi()?;
-        let mut result = Vec::new();
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self { field, value } = self;
+        params_to_multi_impl([convention_param(
+            convention,
+            convention.range.ge.as_ref(),
+            &field.name,
+            &**value,
+        )])
+    }
+}
 
-        for l in &lhs {
-            for r in &rhs {
-                let mut l = l.clone();
-                l.extend_from_slice(r);
-                result.push(l);
-            }
-        }
+impl<T, U, V> Query<T> for Le<'_, Field<T, U>, V>
+where
+    U: PartialOrd<V> + ?Sized + 'static,
+    V: Debug + Display + Serialize + Sync + ToOwned + ?Sized,
+    V::Owned: Send + Sync,
+    #[cfg(feature = "postgres")]
+    V: ToSqlParam,
+{
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        let Self {
+            field: Field { getter, .. },
+            value,
+        } = self;
+        getter(data) <= &**value
+    }
 
-        Some(result)
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        let Self { field, value } = self;
+        let negation = Gt {
+            field: field.clone(),
+            value: Cow::Borrowed(&**value),
+        };
+        Some(ordering_leaf::<T, U>(Node::leaf(self, negation)))
     }
-}
 
-/// Backing implementation for [`Or::to_http_single`] and [`Xor::to_http_single`].
-#[cfg(feature = "rest")]
-fn or_to_single_impl<'a, T>(
-    lhs: &'a impl Query<T>,
-    rhs: &'a impl Query<T>,
-) -> Single<'a, HttpQuery<'a>, T> {
-    let Single {
-        mut query,
-        mut residue,
-    } = lhs.to_http_single();
-    let mut rhs = rhs.to_http_single();
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        let Self { field, value } = self;
+        Some(Primitive::new(
+            &field.name,
+            Operator::Le,
+            vec![Box::new(&**value)],
+        ))
+    }
 
-    // TODO: Is this needlessly complex?
-    let mut rhs_query = HashSet::with_capacity(rhs.query.len());
-    rhs_query.extend(rhs.query);
-    query.retain(|z| rhs_query.contains(z));
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        let Self { field, value } = self;
+        sql_comparison_impl(self, &field.name, "<=", &**value)
+    }
 
-    residue.append(&mut rhs.residue);
+    /// Translation is impossible if the value is null.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
+    }
 
-    Single { query, residue }
+    /// Returns a query with one parameter named according to [`RangeParams::le`] and no residue.
+    /// If the convention specifies no such parameter, **this entire (sub)query remains as
+    /// residue**.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self { field, value } = self;
+        params_to_single_impl(
+            self,
+            [convention_param(
+                convention,
+                convention.range.le.as_ref(),
+                &field.name,
+                &**value,
+            )],
+        )
+    }
+
+    /// Returns a single query with one parameter named according to [`RangeParams::le`].
+    /// Translation is impossible if the convention specifies no such parameter.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self { field, value } = self;
+        params_to_multi_impl([convention_param(
+            convention,
+            convention.range.le.as_ref(),
+            &field.name,
+            &**value,
+        )])
+    }
 }
 
-impl<T, L, R> Query<T> for Or<L, R>
+impl<T, U, V> Query<T> for Between<'_, Field<T, U>, V>
 where
-    L: Query<T>,
-    R: Query<T>,
+    U: PartialOrd<V> + ?Sized + 'static,
+    V: Debug + Display + Serialize + Sync + ToOwned + ?Sized,
+    V::Owned: Send + Sync,
+    #[cfg(feature = "postgres")]
+    V: ToSqlParam,
 {
     #[inline]
     fn evaluate(&self, data: &T) -> bool {
-        let Self(lhs, rhs) = self;
-        lhs.evaluate(data) || rhs.evaluate(data)
+        let Self {
+            field: Field { getter, .. },
+            low,
+            high,
+        } = self;
+        let value = getter(data);
+        value >= &**low && value <= &**high
     }
 
-    #[cfg(feature = "postgres")]
+    /// Expands into `(field >= low) & (field <= high)`.
     #[inline]
-    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
-        let Self(lhs, rhs) = self;
-        let mut l_single = lhs.to_sql_single();
-        let mut r_single = rhs.to_sql_single();
+    fn node(&self) -> Option<Node<'_, T>> {
+        let Self { field, low, high } = self;
+        Some(Node::And(vec![
+            ordering_leaf::<T, U>(Node::shared(
+                Ge {
+                    field: field.clone(),
+                    value: Cow::Borrowed(&**low),
+                },
+                Lt {
+                    field: field.clone(),
+                    value: Cow::Borrowed(&**low),
+                },
+            )),
+            ordering_leaf::<T, U>(Node::shared(
+                Le {
+                    field: field.clone(),
+                    value: Cow::Borrowed(&**high),
+                },
+                Gt {
+                    field: field.clone(),
+                    value: Cow::Borrowed(&**high),
+                },
+            )),
+        ]))
+    }
 
-        l_single.query.params.append(&mut r_single.query.params);
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        let Self { field, low, high } = self;
+        Some(Primitive::new(
+            &field.name,
+            Operator::Between,
+            vec![Box::new(&**low), Box::new(&**high)],
+        ))
+    }
 
-        Single {
-            query: SqlStatement {
-                query_text: format!(
-                    "({}) OR ({})",
-                    l_single.query.query_text, r_single.query.query_text
-                ),
-                params: l_single.query.params,
-            },
-            residue: Vec::new(),
-        }
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        let Self { field, low, high } = self;
+        let mut query = SqlStatement::new();
+        let column = query.column(&field.name);
+        let low = query.param(low.to_sql_param());
+        let high = query.param(high.to_sql_param());
+        query.query_text = format!("{column} BETWEEN {low} AND {high}");
+        sql_params_to_single_impl(self, query)
     }
 
+    /// Translation is impossible if either bound is null.
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
-        Some(vec![self.to_sql_single().query])
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
     }
 
-    /// Retains only the parameters specified in both subqueries, and combines the residues.
+    /// Returns a query with two parameters named according to [`RangeParams::ge`] and
+    /// [`RangeParams::le`], and no residue. If the convention specifies only one of them, it is
+    /// still used but **this entire (sub)query also remains as residue**.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_single(&self) -> Single<'_, HttpQuery<'_>, T> {
-        let Self(lhs, rhs) = self;
-        or_to_single_impl(lhs, rhs)
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self { field, low, high } = self;
+        let RangeParams { ge, le, .. } = &convention.range;
+        params_to_single_impl(
+            self,
+            [
+                convention_param(convention, ge.as_ref(), &field.name, &**low),
+                convention_param(convention, le.as_ref(), &field.name, &**high),
+            ],
+        )
     }
 
-    /// Combines the partial queries.
+    /// Returns a single query with two parameters named according to [`RangeParams::ge`] and
+    /// [`RangeParams::le`]. Translation is impossible if the convention does not specify both.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_multi(&self) -> Option<Vec<HttpQuery<'_>>> {
-        let Self(lhs, rhs) = self;
-        let mut lhs = lhs.to_http_multi()?;
-        let mut rhs = rhs.to_http_multi()?;
-
-        lhs.append(&mut rhs);
-        Some(lhs)
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self { field, low, high } = self;
+        let RangeParams { ge, le, .. } = &convention.range;
+        params_to_multi_impl([
+            convention_param(convention, ge.as_ref(), &field.name, &**low),
+            convention_param(convention, le.as_ref(), &field.name, &**high),
+        ])
     }
 }
 
-impl<T, L, R> Query<T> for Xor<L, R>
+impl<T, U, V> Query<T> for In<'_, Field<T, U>, V>
 where
-    L: Query<T> + Sync,
-    R: Query<T> + Sync,
+    U: PartialEq<V> + ?Sized,
+    V: Debug + Display + Serialize + Send + Sync + Clone,
+    #[cfg(feature = "postgres")]
+    V: ToSqlParam,
 {
     #[inline]
     fn evaluate(&self, data: &T) -> bool {
-        let Self(lhs, rhs) = self;
-        lhs.evaluate(data) ^ rhs.evaluate(data)
+        let Self { field, values } = self;
+        let value = (field.getter)(data);
+        values.iter().any(|v| value == v)
     }
 
-    #[cfg(feature = "postgres")]
+    /// A leaf negated by requiring the field to differ from each value.
     #[inline]
-    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
-        let Self(lhs, rhs) = self;
-        let mut l_single = lhs.to_sql_single();
-        let mut r_single = rhs.to_sql_single();
+    fn node(&self) -> Option<Node<'_, T>> {
+        let Self { field, values } = self;
+        let negation = values.iter().map(|value| Ne {
+            field: field.clone(),
+            value: Cow::Borrowed(value),
+        });
+        Some(Node::leaf(self, All(negation.collect())))
+    }
 
-        l_single.query.params.append(&mut r_single.query.params);
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        let Self { field, values } = self;
+        let operands = values
+            .iter()
+            .map(|value| -> Box<dyn Operand> { Box::new(value) });
+        Some(Primitive::new(
+            &field.name,
+            Operator::In,
+            operands.collect(),
+        ))
+    }
 
-        Single {
-            query: SqlStatement {
-                query_text: format!(
-                    "({}) != ({})",
-                    l_single.query.query_text, r_single.query.query_text
-                ),
-                params: l_single.query.params,
-            },
-            residue: Vec::new(),
+    /// Translates into `IN (...)`, or `FALSE` if there are no values.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        let Self { field, values } = self;
+        if values.is_empty() {
+            return Single {
+                query: sql_false(),
+                residue: Vec::new(),
+            };
         }
+
+        let mut query = SqlStatement::new();
+        let column = query.column(&field.name);
+        let params = values
+            .iter()
+            .map(|value| query.param(value.to_sql_param()))
+            .collect::<Vec<_>>();
+        query.query_text = format!("{column} IN ({})", params.join(", "));
+        sql_params_to_single_impl(self, query)
     }
 
+    /// Translation is impossible if any value is null.
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
-        Some(vec![self.to_sql_single().query])
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
     }
 
-    /// Retains only the parameters specified in both subqueries, and combines the residues. The
-    /// XOR itself also remains as residue.
+    /// Translated into repeated or joined parameters, as specified by the convention. If the
+    /// convention supports neither, the source does not support the field, any value can not be
+    /// written, or there are no values, this query remains as residue.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_single(&self) -> Single<'_, HttpQuery<'_>, T> {
-        let Self(lhs, rhs) = self;
-        // For this purpose, XOR is just an OR that might exclude some more results after the
-        // local filtering step.
-        let mut res = or_to_single_impl(lhs, rhs);
-        res.residue.push(self);
-        res
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self { field, values } = self;
+        let HttpConvention { fields, .. } = convention;
+        let written = values
+            .iter()
+            .map(|value| {
+                let written = convention.values.write(value)?;
+                Some(fields.value(&field.name, written))
+            })
+            .collect::<Option<Vec<_>>>();
+        let query = match (
+            &convention.list,
+            written.filter(|written| !written.is_empty()),
+        ) {
+            (ListParams::Repeated(param), Some(written)) => {
+                fields.param(&field.name, param).map(|name| {
+                    written
+                        .into_iter()
+                        .map(|value| (name.clone(), value))
+                        .collect()
+                })
+            },
+            (ListParams::Joined { name, separator }, Some(written)) => fields
+                .param(&field.name, name)
+                .filter(|_| !written.iter().any(|value| value.contains(&**separator)))
+                .map(|name| vec![(name, written.join(separator).into())]),
+            (ListParams::Unsupported, _) | (_, None) => None,
+        };
+
+        query.map_or_else(
+            || Single {
+                query: HttpQuery::new(),
+                residue: vec![self],
+            },
+            |query| Single {
+                query,
+                residue: Vec::new(),
+            },
+        )
     }
 
-    /// Translation is impossible.
+    /// Returns one query per value, each with one parameter like [`Eq::to_http_multi`]. If there
+    /// are no values, no queries need to be made. Translation is impossible if the source does
+    /// not support the field, or any value can not be written.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_multi(&self) -> Option<Vec<HttpQuery<'_>>> {
-        None
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self { field, values } = self;
+        values
+            .iter()
+            .map(|value| {
+                convention_param(convention, Some(&ParamName::default()), &field.name, value)
+                    .map(|param| vec![param])
+            })
+            .collect()
     }
 }
 
-impl<T, Q> Query<T> for Not<Q>
+impl<T, U> Query<T> for StartsWith<'_, Field<T, U>>
 where
-    Q: Query<T> + Sync,
+    U: AsRef<str> + ?Sized,
 {
     #[inline]
     fn evaluate(&self, data: &T) -> bool {
-        let Self(query) = self;
-        !query.evaluate(data)
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        pattern_evaluate(
+            (field.getter)(data).as_ref(),
+            value,
+            *ignore_case,
+            |haystack, needle| haystack.starts_with(needle),
+        )
+    }
+
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        Some(Primitive::new(
+            &field.name,
+            Operator::StartsWith {
+                ignore_case: *ignore_case,
+            },
+            vec![Box::new(&**value)],
+        ))
     }
 
+    /// Translates into `LIKE 'value%'`, or `ILIKE` if ignoring case.
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
-        let Self(inner_query) = self;
-        let single = inner_query.to_sql_single();
-
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
         Single {
-            query: SqlStatement {
-                query_text: format!("NOT ({})", single.query.query_text),
-                params: single.query.params,
-            },
+            query: like_sql_impl(
+                &field.name,
+                format!("{}%", escape_like(value)),
+                *ignore_case,
+            ),
             residue: Vec::new(),
         }
     }
@@ -595,44 +1458,96 @@ where
         Some(vec![self.to_sql_single().query])
     }
 
-    /// Returns a single query with no parameters, meaning **this entire (sub)query remains as
-    /// residue**.
+    /// Translated into the parameter specified by the convention, if any. If the convention does
+    /// not specify a parameter, or the source matches with different case sensitivity, this
+    /// predicate remains as residue.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_single(&self) -> Single<'_, HttpQuery<'_>, T> {
-        Single {
-            query: HttpQuery::new(),
-            residue: vec![self],
-        }
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        pattern_to_single_impl(
+            self,
+            convention.string.starts_with.as_ref(),
+            convention,
+            &field.name,
+            value,
+            *ignore_case,
+        )
     }
 
-    /// Translation is impossible.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_multi(&self) -> Option<Vec<HttpQuery<'_>>> {
-        None
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        pattern_to_multi_impl(
+            convention.string.starts_with.as_ref(),
+            convention,
+            &field.name,
+            value,
+            *ignore_case,
+        )
     }
 }
 
-impl<T, L, R> Query<T> for Either<L, R>
+impl<T, U> Query<T> for Contains<'_, Field<T, U>>
 where
-    L: Query<T>,
-    R: Query<T>,
+    U: AsRef<str> + ?Sized,
 {
     #[inline]
     fn evaluate(&self, data: &T) -> bool {
-        match self {
-            Self::Left(query) => query.evaluate(data),
-            Self::Right(query) => query.evaluate(data),
-        }
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        pattern_evaluate(
+            (field.getter)(data).as_ref(),
+            value,
+            *ignore_case,
+            |haystack, needle| haystack.contains(needle),
+        )
+    }
+
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        Some(Primitive::new(
+            &field.name,
+            Operator::Contains {
+                ignore_case: *ignore_case,
+            },
+            vec![Box::new(&**value)],
+        ))
     }
 
+    /// Translates into `LIKE '%value%'`, or `ILIKE` if ignoring case.
     #[cfg(feature = "postgres")]
     #[inline]
     fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
-        match self {
-            Self::Left(query) => query.to_sql_single(),
-            Self::Right(query) => query.to_sql_single(),
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        Single {
+            query: like_sql_impl(
+                &field.name,
+                format!("%{}%", escape_like(value)),
+                *ignore_case,
+            ),
+            residue: Vec::new(),
         }
     }
 
@@ -642,181 +1557,3251 @@ where
         Some(vec![self.to_sql_single().query])
     }
 
+    /// Translated into the parameter specified by the convention, if any. If the convention does
+    /// not specify a parameter, or the source matches with different case sensitivity, this
+    /// predicate remains as residue.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_single(&self) -> Single<'_, HttpQuery<'_>, T> {
-        match self {
-            Self::Left(query) => query.to_http_single(),
-            Self::Right(query) => query.to_http_single(),
-        }
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        pattern_to_single_impl(
+            self,
+            convention.string.contains.as_ref(),
+            convention,
+            &field.name,
+            value,
+            *ignore_case,
+        )
     }
 
-    /// Translation is impossible.
     #[cfg(feature = "rest")]
     #[inline]
-    fn to_http_multi(&self) -> Option<Vec<HttpQuery<'_>>> {
-        match self {
-            Self::Left(query) => query.to_http_multi(),
-            Self::Right(query) => query.to_http_multi(),
-        }
-    }
-}
-
-// Debug implementations support two formats:
-// - Standard (`{:?}`): structured, machine-readable
-// - Alternate (`{:#?}`): compact, human-readable query syntax
-
-impl Debug for True {
-    #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
-        if f.alternate() {
-            write!(f, "true")
-        } else {
-            f.debug_struct(name_of_type!(Self)).finish()
-        }
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        pattern_to_multi_impl(
+            convention.string.contains.as_ref(),
+            convention,
+            &field.name,
+            value,
+            *ignore_case,
+        )
     }
 }
 
-impl<T, V, U> Debug for Eq<'_, Field<T, V>, U>
+impl<T, U> Query<T> for EndsWith<'_, Field<T, U>>
 where
-    U: Debug + ?Sized,
+    U: AsRef<str> + ?Sized,
 {
     #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
-        let Self { field: _, value } = self;
-        if f.alternate() {
-            write!(f, "{} = {:#?}", self.field.name, value)
-        } else {
-            f.debug_struct(name_of_type!(Self))
-                .field(name_of!(field in Self), &self.field.name)
-                .field(name_of!(value in Self), value)
-                .finish()
+    fn evaluate(&self, data: &T) -> bool {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        pattern_evaluate(
+            (field.getter)(data).as_ref(),
+            value,
+            *ignore_case,
+            |haystack, needle| haystack.ends_with(needle),
+        )
+    }
+
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        Some(Primitive::new(
+            &field.name,
+            Operator::EndsWith {
+                ignore_case: *ignore_case,
+            },
+            vec![Box::new(&**value)],
+        ))
+    }
+
+    /// Translates into `LIKE '%value'`, or `ILIKE` if ignoring case.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        Single {
+            query: like_sql_impl(
+                &field.name,
+                format!("%{}", escape_like(value)),
+                *ignore_case,
+            ),
+            residue: Vec::new(),
         }
     }
+
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        Some(vec![self.to_sql_single().query])
+    }
+
+    /// Translated into the parameter specified by the convention, if any. If the convention does
+    /// not specify a parameter, or the source matches with different case sensitivity, this
+    /// predicate remains as residue.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        pattern_to_single_impl(
+            self,
+            convention.string.ends_with.as_ref(),
+            convention,
+            &field.name,
+            value,
+            *ignore_case,
+        )
+    }
+
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        pattern_to_multi_impl(
+            convention.string.ends_with.as_ref(),
+            convention,
+            &field.name,
+            value,
+            *ignore_case,
+        )
+    }
 }
 
-impl<T, V, U> Debug for Ne<'_, Field<T, V>, U>
+impl<T, U> Query<T> for EqIgnoreCase<'_, Field<T, U>>
 where
-    U: Debug + ?Sized,
+    U: AsRef<str> + ?Sized,
 {
     #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
-        let Self { field: _, value } = self;
-        if f.alternate() {
-            write!(f, "{} != {:#?}", self.field.name, value)
-        } else {
-            f.debug_struct(name_of_type!(Self))
-                .field(name_of!(field in Self), &self.field.name)
-                .field(name_of!(value in Self), value)
-                .finish()
+    fn evaluate(&self, data: &T) -> bool {
+        let Self { field, value } = self;
+        pattern_evaluate((field.getter)(data).as_ref(), value, true, |lhs, rhs| {
+            lhs == rhs
+        })
+    }
+
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        let Self { field, value } = self;
+        Some(Primitive::new(
+            &field.name,
+            Operator::EqIgnoreCase,
+            vec![Box::new(&**value)],
+        ))
+    }
+
+    /// Translates into `ILIKE 'value'`.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        let Self { field, value } = self;
+        Single {
+            query: like_sql_impl(&field.name, escape_like(value), true),
+            residue: Vec::new(),
         }
     }
+
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        Some(vec![self.to_sql_single().query])
+    }
+
+    /// Translated into the parameter specified by the convention, if any. Otherwise, this
+    /// predicate remains as residue.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self { field, value } = self;
+        params_to_single_impl(
+            self,
+            [convention_param(
+                convention,
+                convention.string.eq_ignore_case.as_ref(),
+                &field.name,
+                &**value,
+            )],
+        )
+    }
+
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self { field, value } = self;
+        params_to_multi_impl([convention_param(
+            convention,
+            convention.string.eq_ignore_case.as_ref(),
+            &field.name,
+            &**value,
+        )])
+    }
+}
+
+/// Implement [`Query`] for comparisons between two fields, given the comparison trait, the Rust
+/// operator, the SQL condition, the negated comparison and whether the comparison is an ordering.
+///
+/// The SQL condition refers to the left and right columns as `{0}` and `{1}`. Like in Rust, NULL
+/// must compare equal to itself and lesser than any other value, so that nullable fields are
+/// translated correctly.
+macro_rules! impl_field_comparison {
+    (
+        $(
+            $name:ident($bound:ident, $operator:tt, $sql:literal, $negation:ident, $ordering:literal)
+        ),+
+        $(,)?
+    ) => {
+        $(
+            impl<T, U, W> Query<T> for $name<Field<T, U>, Field<T, W>>
+            where
+                U: $bound<W> + ?Sized + 'static,
+                W: ?Sized + 'static,
+            {
+                #[inline]
+                fn evaluate(&self, data: &T) -> bool {
+                    let Self { left, right } = self;
+                    (left.getter)(data) $operator (right.getter)(data)
+                }
+
+                #[inline]
+                fn node(&self) -> Option<Node<'_, T>> {
+                    let Self { left, right } = self;
+                    let negation = $negation {
+                        left: left.clone(),
+                        right: right.clone(),
+                    };
+                    let leaf = Node::leaf(self, negation);
+                    Some(if $ordering {
+                        ordering_leaf::<T, W>(ordering_leaf::<T, U>(leaf))
+                    } else {
+                        leaf
+                    })
+                }
+
+                #[doc = concat!("Translates into `", $sql, "`.")]
+                #[cfg(feature = "postgres")]
+                #[inline]
+                fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+                    let Self { left, right } = self;
+                    Single {
+                        query: sql_field_comparison_impl(&left.name, &right.name, |left, right| {
+                            format!($sql, left, right)
+                        }),
+                        residue: Vec::new(),
+                    }
+                }
+
+                #[cfg(feature = "postgres")]
+                #[inline]
+                fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+                    Some(vec![self.to_sql_single().query])
+                }
+
+                /// Returns a single query with no parameters, meaning **this entire (sub)query
+                /// remains as residue**.
+                #[cfg(feature = "rest")]
+                #[inline]
+                fn to_http_single(&self, _: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+                    Single {
+                        query: HttpQuery::new(),
+                        residue: vec![self],
+                    }
+                }
+
+                /// Translation is impossible.
+                #[cfg(feature = "rest")]
+                #[inline]
+                fn to_http_multi(&self, _: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+                    None
+                }
+            }
+        )+
+    };
+}
+
+impl_field_comparison!(
+    FieldEq(PartialEq, ==, "{0} IS NOT DISTINCT FROM {1}", FieldNe, false),
+    FieldNe(PartialEq, !=, "{0} IS DISTINCT FROM {1}", FieldEq, false),
+    FieldGt(PartialOrd, >, "{0} > {1} OR ({0} IS NOT NULL AND {1} IS NULL)", FieldLe, true),
+    FieldLt(PartialOrd, <, "{0} < {1} OR ({0} IS NULL AND {1} IS NOT NULL)", FieldGe, true),
+    FieldGe(PartialOrd, >=, "{0} >= {1} OR {1} IS NULL", FieldLt, true),
+    FieldLe(PartialOrd, <=, "{0} <= {1} OR {0} IS NULL", FieldGt, true),
+);
+
+/// Implement [`Query`] for null checks, given the SQL condition and the negated check.
+macro_rules! impl_null_check {
+    ($($name:ident($method:ident, $sql:literal, $negation:ident)),+ $(,)?) => {
+        $(
+            impl<T, U> Query<T> for $name<Field<T, Option<U>>> {
+                #[inline]
+                fn evaluate(&self, data: &T) -> bool {
+                    let Self { field } = self;
+                    (field.getter)(data).$method()
+                }
+
+                #[inline]
+                fn node(&self) -> Option<Node<'_, T>> {
+                    let Self { field } = self;
+                    let negation = $negation {
+                        field: field.clone(),
+                    };
+                    Some(Node::leaf(self, negation))
+                }
+
+                #[inline]
+                fn primitive(&self) -> Option<Primitive<'_>> {
+                    let Self { field } = self;
+                    Some(Primitive::new(&field.name, Operator::$name, Vec::new()))
+                }
+
+                #[doc = concat!("Translates into `", $sql, "`.")]
+                #[cfg(feature = "postgres")]
+                #[inline]
+                fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+                    let Self { field } = self;
+                    let mut query = SqlStatement::new();
+                    let column = query.column(&field.name);
+                    query.query_text = format!(concat!("{} ", $sql), column);
+                    Single {
+                        query,
+                        residue: Vec::new(),
+                    }
+                }
+
+                #[cfg(feature = "postgres")]
+                #[inline]
+                fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+                    Some(vec![self.to_sql_single().query])
+                }
+
+                /// Returns a single query with no parameters, meaning **this entire (sub)query
+                /// remains as residue**.
+                #[cfg(feature = "rest")]
+                #[inline]
+                fn to_http_single(&self, _: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+                    Single {
+                        query: HttpQuery::new(),
+                        residue: vec![self],
+                    }
+                }
+
+                /// Translation is impossible.
+                #[cfg(feature = "rest")]
+                #[inline]
+                fn to_http_multi(&self, _: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+                    None
+                }
+            }
+        )+
+    };
 }
 
-impl<T, V, U> Debug for Gt<'_, Field<T, V>, U>
+impl_null_check!(
+    IsNone(is_none, "IS NULL", IsSome),
+    IsSome(is_some, "IS NOT NULL", IsNone),
+);
+
+impl<T, U, Q> Query<T> for AnyElement<Field<T, Vec<U>>, Q>
 where
-    U: Debug + ?Sized,
+    Q: Query<U>,
 {
     #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
-        let Self { field: _, value } = self;
-        if f.alternate() {
-            write!(f, "{} > {:#?}", self.field.name, value)
-        } else {
-            f.debug_struct(name_of_type!(Self))
-                .field(name_of!(field in Self), &self.field.name)
-                .field(name_of!(value in Self), value)
-                .finish()
+    fn evaluate(&self, data: &T) -> bool {
+        let Self { field, predicate } = self;
+        (field.getter)(data)
+            .iter()
+            .any(|element| predicate.evaluate(element))
+    }
+
+    /// Translates into `$1 = ANY(field)` if the predicate checks equality of the elements
+    /// themselves, or `EXISTS (SELECT 1 FROM unnest(field) ...)` otherwise. The elements are named
+    /// like the field within the subquery. If the predicate has residue, **this entire (sub)query
+    /// also remains as residue**. Fields of the elements have no columns, so if the predicate
+    /// refers to anything but the elements themselves, **this entire (sub)query remains as
+    /// residue** and the query is empty.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        let Self { field, predicate } = self;
+        let Single {
+            query: inner,
+            residue,
+        } = predicate.to_sql_single();
+        if inner.columns.iter().any(|column| **column != *field.name) {
+            return Single {
+                query: SqlStatement::new(),
+                residue: vec![self],
+            };
+        }
+        let equality = predicate.primitive().is_some_and(|primitive| {
+            primitive.operator == Operator::Eq && primitive.field == &*field.name
+        });
+
+        let mut query = SqlStatement::new();
+        let column = query.column(&field.name);
+        query.query_text = match &*inner.params {
+            [param] if equality => format!("{} = ANY({column})", param.placeholder(1)),
+            _ => format!(
+                "EXISTS (SELECT 1 FROM unnest({column}) AS {column} WHERE {})",
+                inner.condition()
+            ),
+        };
+        query.params = inner.params;
+
+        Single {
+            query,
+            residue: if residue.is_empty() {
+                Vec::new()
+            } else {
+                vec![self]
+            },
         }
     }
-}
 
-impl<T, V, U> Debug for Lt<'_, Field<T, V>, U>
-where
-    U: Debug + ?Sized,
-{
+    /// Translation is impossible if the predicate has residue.
+    #[cfg(feature = "postgres")]
     #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
-        let Self { field: _, value } = self;
-        if f.alternate() {
-            write!(f, "{} < {:#?}", self.field.name, value)
-        } else {
-            f.debug_struct(name_of_type!(Self))
-                .field(name_of!(field in Self), &self.field.name)
-                .field(name_of!(value in Self), value)
-                .finish()
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
+    }
+
+    /// Returns a single query with no parameters, meaning **this entire (sub)query remains as
+    /// residue**.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, _: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        Single {
+            query: HttpQuery::new(),
+            residue: vec![self],
         }
     }
+
+    /// Translation is impossible.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, _: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        None
+    }
 }
 
-impl<L, R> Debug for And<L, R>
+impl<T, L, R> Query<T> for And<L, R>
 where
-    L: Debug,
-    R: Debug,
+    L: Query<T>,
+    R: Query<T>,
 {
     #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+    fn evaluate(&self, data: &T) -> bool {
         let Self(lhs, rhs) = self;
-        if f.alternate() {
-            write!(f, "({lhs:#?}) & ({rhs:#?})")
-        } else {
-            f.debug_tuple(name_of_type!(Self))
-                .field(lhs)
-                .field(rhs)
-                .finish()
-        }
+        lhs.evaluate(data) && rhs.evaluate(data)
     }
-}
 
-impl<L, R> Debug for Or<L, R>
-where
-    L: Debug,
-    R: Debug,
-{
     #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+    fn node(&self) -> Option<Node<'_, T>> {
         let Self(lhs, rhs) = self;
-        if f.alternate() {
-            write!(f, "({lhs:#?}) | ({rhs:#?})")
-        } else {
-            f.debug_tuple(name_of_type!(Self))
-                .field(lhs)
-                .field(rhs)
-                .finish()
-        }
+        Some(Node::And(vec![Node::of(lhs), Node::of(rhs)]))
     }
-}
 
-impl<L, R> Debug for Xor<L, R>
-where
-    L: Debug,
-    R: Debug,
-{
+    #[cfg(feature = "postgres")]
     #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
         let Self(lhs, rhs) = self;
-        if f.alternate() {
-            write!(f, "({lhs:#?}) ^ ({rhs:#?})")
-        } else {
-            f.debug_tuple(name_of_type!(Self))
-                .field(lhs)
-                .field(rhs)
-                .finish()
+        let Single {
+            query: l_query,
+            mut residue,
+        } = lhs.to_sql_single();
+        let Single {
+            query: r_query,
+            residue: mut r_residue,
+        } = rhs.to_sql_single();
+        residue.append(&mut r_residue);
+
+        Single {
+            query: SqlStatement::combine(l_query, "AND", r_query),
+            residue,
         }
     }
-}
 
-impl<Q> Debug for Not<Q>
-where
-    Q: Debug,
-{
+    /// Translation is impossible if there is residue.
+    #[cfg(feature = "postgres")]
     #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
-        let Self(query) = self;
-        if f.alternate() {
-            write!(f, "!({query:#?})")
-        } else {
-            f.debug_tuple(name_of_type!(Self)).field(query).finish()
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
+    }
+
+    /// Combines both the parameter lists and the residues of both subqueries.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self(lhs, rhs) = self;
+        let Single {
+            mut query,
+            mut residue,
+        } = lhs.to_http_single(convention);
+        let mut rhs = rhs.to_http_single(convention);
+
+        query.append(&mut rhs.query);
+        residue.append(&mut rhs.residue);
+
+        Single { query, residue }
+    }
+
+    /// Creates the cartesian product of all parts from both subqueries. Although this is
+    /// theoretically a "perfect" translation, **the number of output queries grows very quickly
+    /// for complex queries**.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self(lhs, rhs) = self;
+        let lhs = lhs.to_http_multi(convention)?;
+        let rhs = rhs.to_http_multi(convention)?;
+        let mut result = Vec::new();
+
+        for l in &lhs {
+            for r in &rhs {
+                let mut l = l.clone();
+                l.extend_from_slice(r);
+                result.push(l);
+            }
         }
+
+        Some(result)
+    }
+}
+
+/// Backing implementation for [`Or::to_http_single`] and [`Xor::to_http_single`].
+#[cfg(feature = "rest")]
+fn or_to_single_impl<'a, T>(
+    lhs: &'a impl Query<T>,
+    rhs: &'a impl Query<T>,
+    convention: &HttpConvention,
+) -> Single<'a, HttpQuery<'a>, T> {
+    let Single {
+        mut query,
+        mut residue,
+    } = lhs.to_http_single(convention);
+    let mut rhs = rhs.to_http_single(convention);
+
+    // TODO: Is this needlessly complex?
+    let mut rhs_query = HashSet::with_capacity(rhs.query.len());
+    rhs_query.extend(rhs.query);
+    query.retain(|z| rhs_query.contains(z));
+
+    residue.append(&mut rhs.residue);
+
+    Single { query, residue }
+}
+
+impl<T, L, R> Query<T> for Or<L, R>
+where
+    L: Query<T>,
+    R: Query<T>,
+{
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        let Self(lhs, rhs) = self;
+        lhs.evaluate(data) || rhs.evaluate(data)
+    }
+
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        let Self(lhs, rhs) = self;
+        Some(Node::Or(vec![Node::of(lhs), Node::of(rhs)]))
+    }
+
+    /// If either subquery has residue, the translation selects a superset of the desired elements
+    /// and this entire query remains as residue.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        let Self(lhs, rhs) = self;
+        let lhs = lhs.to_sql_single();
+        let rhs = rhs.to_sql_single();
+        let exact = lhs.residue.is_empty() && rhs.residue.is_empty();
+
+        Single {
+            query: SqlStatement::combine(lhs.query, "OR", rhs.query),
+            residue: if exact { Vec::new() } else { vec![self] },
+        }
+    }
+
+    /// Translation is impossible if either subquery has residue.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
+    }
+
+    /// Retains only the parameters specified in both subqueries, and combines the residues.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self(lhs, rhs) = self;
+        or_to_single_impl(lhs, rhs, convention)
+    }
+
+    /// Combines the partial queries.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self(lhs, rhs) = self;
+        let mut lhs = lhs.to_http_multi(convention)?;
+        let mut rhs = rhs.to_http_multi(convention)?;
+
+        lhs.append(&mut rhs);
+        Some(lhs)
+    }
+}
+
+impl<T, L, R> Query<T> for Xor<L, R>
+where
+    L: Query<T>,
+    R: Query<T>,
+{
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        let Self(lhs, rhs) = self;
+        lhs.evaluate(data) ^ rhs.evaluate(data)
+    }
+
+    /// Expands into `(lhs & !rhs) | (!lhs & rhs)`.
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        let Self(lhs, rhs) = self;
+        Some(Node::Or(vec![
+            Node::And(vec![Node::of(lhs), Node::Not(Box::new(Node::of(rhs)))]),
+            Node::And(vec![Node::Not(Box::new(Node::of(lhs))), Node::of(rhs)]),
+        ]))
+    }
+
+    /// If either subquery has residue, the query is empty and **this entire query remains as
+    /// residue**, since a superset of one operand does not bound the XOR.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        let Self(lhs, rhs) = self;
+        let lhs = lhs.to_sql_single();
+        let rhs = rhs.to_sql_single();
+        if !lhs.residue.is_empty() || !rhs.residue.is_empty() {
+            return Single {
+                query: SqlStatement::new(),
+                residue: vec![self],
+            };
+        }
+
+        Single {
+            query: SqlStatement::combine(lhs.query, "!=", rhs.query),
+            residue: Vec::new(),
+        }
+    }
+
+    /// Translation is impossible if either subquery has residue.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
+    }
+
+    /// Retains only the parameters specified in both subqueries, and combines the residues. The
+    /// XOR itself also remains as residue.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self(lhs, rhs) = self;
+        // For this purpose, XOR is just an OR that might exclude some more results after the
+        // local filtering step.
+        let mut res = or_to_single_impl(lhs, rhs, convention);
+        res.residue.push(self);
+        res
+    }
+
+    /// Translation is impossible.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, _: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        None
+    }
+}
+
+impl<T, Q> Query<T> for Not<Q>
+where
+    Q: Query<T>,
+{
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        let Self(query) = self;
+        !query.evaluate(data)
+    }
+
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        let Self(query) = self;
+        Some(Node::Not(Box::new(Node::of(query))))
+    }
+
+    /// If the subquery has residue, the query is empty and **this entire query remains as
+    /// residue**, since the negation of a superset is not a superset.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        let Self(inner_query) = self;
+        let Single { mut query, residue } = inner_query.to_sql_single();
+        if !residue.is_empty() {
+            return Single {
+                query: SqlStatement::new(),
+                residue: vec![self],
+            };
+        }
+        query.query_text = format!("NOT ({})", query.condition());
+
+        Single {
+            query,
+            residue: Vec::new(),
+        }
+    }
+
+    /// Translation is impossible if the subquery has residue.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
+    }
+
+    /// Returns a single query with no parameters, meaning **this entire (sub)query remains as
+    /// residue**.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, _: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        Single {
+            query: HttpQuery::new(),
+            residue: vec![self],
+        }
+    }
+
+    /// Translation is impossible.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, _: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        None
+    }
+}
+
+/// Construct a condition matching nothing.
+#[cfg(feature = "postgres")]
+pub(super) fn sql_false() -> SqlStatement {
+    let mut query = SqlStatement::new();
+    "FALSE".clone_into(&mut query.query_text);
+    query
+}
+
+/// Backing implementation for [`All::to_sql_single`].
+#[cfg(feature = "postgres")]
+pub(super) fn all_to_sql_impl<T, Q>(queries: &[Q]) -> Single<'_, SqlStatement, T>
+where
+    Q: Query<T>,
+{
+    let mut residue = Vec::new();
+    let query = queries
+        .iter()
+        .map(|query| {
+            let mut single = query.to_sql_single();
+            residue.append(&mut single.residue);
+            single.query
+        })
+        .reduce(|lhs, rhs| SqlStatement::combine(lhs, "AND", rhs))
+        .unwrap_or_else(SqlStatement::new);
+
+    Single { query, residue }
+}
+
+/// Backing implementation for [`All::to_http_single`].
+#[cfg(feature = "rest")]
+pub(super) fn all_to_http_single_impl<'a, T, Q>(
+    queries: &'a [Q],
+    convention: &HttpConvention,
+) -> Single<'a, HttpQuery<'a>, T>
+where
+    Q: Query<T>,
+{
+    let mut query = HttpQuery::new();
+    let mut residue = Vec::new();
+
+    for subquery in queries {
+        let mut single = subquery.to_http_single(convention);
+        query.append(&mut single.query);
+        residue.append(&mut single.residue);
+    }
+
+    Single { query, residue }
+}
+
+/// Backing implementation for [`All::to_http_multi`].
+#[cfg(feature = "rest")]
+pub(super) fn all_to_http_multi_impl<'a, T, Q>(
+    queries: &'a [Q],
+    convention: &HttpConvention,
+) -> Option<Vec<HttpQuery<'a>>>
+where
+    Q: Query<T>,
+{
+    queries
+        .iter()
+        .try_fold(vec![HttpQuery::new()], |result, subquery| {
+            let parts = subquery.to_http_multi(convention)?;
+            Some(
+                result
+                    .iter()
+                    .flat_map(|l| {
+                        parts.iter().map(|r| {
+                            let mut l = l.clone();
+                            l.extend_from_slice(r);
+                            l
+                        })
+                    })
+                    .collect(),
+            )
+        })
+}
+
+impl<T, Q> Query<T> for All<Q>
+where
+    Q: Query<T>,
+{
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        let Self(queries) = self;
+        queries.iter().all(|query| query.evaluate(data))
+    }
+
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        let Self(queries) = self;
+        Some(Node::And(
+            queries.iter().map(|query| Node::of(query)).collect(),
+        ))
+    }
+
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        let Self(queries) = self;
+        all_to_sql_impl(queries)
+    }
+
+    /// Translation is impossible if there is residue.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
+    }
+
+    /// Combines the parameter lists and the residues of all subqueries.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self(queries) = self;
+        all_to_http_single_impl(queries, convention)
+    }
+
+    /// Creates the cartesian product of all parts from all subqueries. As with [`And`], **the
+    /// number of output queries grows very quickly for complex queries**.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self(queries) = self;
+        all_to_http_multi_impl(queries, convention)
+    }
+}
+
+/// Backing implementation for [`Any::to_http_single`] and [`One::to_http_single`].
+///
+/// Retains only the parameters specified in all subqueries. Also returns whether the parameters
+/// select exactly the elements matched by any subquery, which is the case when all subqueries
+/// translate into the same parameters with no residue.
+#[cfg(feature = "rest")]
+pub(super) fn any_to_http_impl<'a, T: 'a, Q>(
+    queries: &'a [Q],
+    convention: &HttpConvention,
+) -> (HttpQuery<'a>, bool)
+where
+    Q: Query<T>,
+{
+    let mut singles = queries
+        .iter()
+        .map(|subquery| subquery.to_http_single(convention));
+    let Some(first) = singles.next() else {
+        return (HttpQuery::new(), false);
+    };
+
+    let mut exact = first.residue.is_empty();
+    let mut query = first.query;
+    for single in singles {
+        let len = query.len();
+        let params = single.query.iter().collect::<HashSet<_>>();
+        query.retain(|param| params.contains(param));
+        exact &= single.residue.is_empty() && query.len() == len && params.len() == len;
+    }
+
+    (query, exact)
+}
+
+/// Backing implementation for [`Any::to_sql_single`]. Also returns whether the condition selects
+/// exactly the elements matched by any subquery, which is the case when no subquery has residue.
+#[cfg(feature = "postgres")]
+pub(super) fn any_to_sql_impl<T, Q>(queries: &[Q]) -> (SqlStatement, bool)
+where
+    Q: Query<T>,
+{
+    let mut exact = true;
+    let query = queries
+        .iter()
+        .map(|query| {
+            let single = query.to_sql_single();
+            exact &= single.residue.is_empty();
+            single.query
+        })
+        .reduce(|lhs, rhs| SqlStatement::combine(lhs, "OR", rhs))
+        .unwrap_or_else(sql_false);
+
+    (query, exact)
+}
+
+/// Backing implementation for [`Any::to_http_multi`].
+#[cfg(feature = "rest")]
+pub(super) fn any_to_http_multi_impl<'a, T, Q>(
+    queries: &'a [Q],
+    convention: &HttpConvention,
+) -> Option<Vec<HttpQuery<'a>>>
+where
+    Q: Query<T>,
+{
+    let mut result = Vec::new();
+    for subquery in queries {
+        result.append(&mut subquery.to_http_multi(convention)?);
+    }
+
+    Some(result)
+}
+
+impl<T, Q> Query<T> for Any<Q>
+where
+    Q: Query<T>,
+{
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        let Self(queries) = self;
+        queries.iter().any(|query| query.evaluate(data))
+    }
+
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        let Self(queries) = self;
+        Some(Node::Or(
+            queries.iter().map(|query| Node::of(query)).collect(),
+        ))
+    }
+
+    /// If any subquery has residue, the translation selects a superset of the desired elements
+    /// and this entire query remains as residue.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        let Self(queries) = self;
+        let (query, exact) = any_to_sql_impl(queries);
+
+        Single {
+            query,
+            residue: if exact { Vec::new() } else { vec![self] },
+        }
+    }
+
+    /// Translation is impossible if there is residue.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
+    }
+
+    /// Retains only the parameters specified in all subqueries. Unless all subqueries translate
+    /// into the same parameters with no residue, this entire query remains as residue.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self(queries) = self;
+        let (query, exact) = any_to_http_impl(queries, convention);
+
+        Single {
+            query,
+            residue: if exact { Vec::new() } else { vec![self] },
+        }
+    }
+
+    /// Combines the partial queries, producing at least one query per subquery. If there are no
+    /// subqueries, no queries need to be made.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        let Self(queries) = self;
+        any_to_http_multi_impl(queries, convention)
+    }
+}
+
+impl<T, Q> Query<T> for One<Q>
+where
+    Q: Query<T>,
+{
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        let Self(queries) = self;
+        let mut matches = queries.iter().filter(|query| query.evaluate(data));
+        matches.next().is_some() && matches.next().is_none()
+    }
+
+    /// Counts the matching subqueries. If any subquery has residue, no exact count is possible, so
+    /// the translation matches everything and this entire query remains as residue.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        let Self(queries) = self;
+        let mut exact = true;
+        let count = queries
+            .iter()
+            .map(|query| {
+                let Single { mut query, residue } = query.to_sql_single();
+                exact &= residue.is_empty();
+                query.query_text = format!("CAST(({}) AS INTEGER)", query.condition());
+                query
+            })
+            .reduce(|lhs, rhs| SqlStatement::combine(lhs, "+", rhs));
+
+        match count {
+            Some(_) if !exact => Single {
+                query: SqlStatement::new(),
+                residue: vec![self],
+            },
+            Some(mut query) => {
+                query.query_text = format!("({}) = 1", query.query_text);
+                Single {
+                    query,
+                    residue: Vec::new(),
+                }
+            },
+            None => Single {
+                query: sql_false(),
+                residue: Vec::new(),
+            },
+        }
+    }
+
+    /// Translation is impossible if there is residue.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
+    }
+
+    /// Retains only the parameters specified in all subqueries. This entire query remains as
+    /// residue.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        let Self(queries) = self;
+        // For this purpose, like XOR, this is just an `Any` that might exclude some more results
+        // after the local filtering step.
+        let (query, _) = any_to_http_impl(queries, convention);
+
+        Single {
+            query,
+            residue: vec![self],
+        }
+    }
+
+    /// Translation is impossible.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, _: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        None
+    }
+}
+
+/// Implements [`Query::node`], [`Query::primitive`] and all translation methods of a modifier by
+/// forwarding them to the modified query, which is the first field of the tuple struct.
+macro_rules! forward_translation {
+    () => {
+        #[inline]
+        fn node(&self) -> Option<Node<'_, T>> {
+            Some(Node::of(&self.0))
+        }
+
+        #[inline]
+        fn primitive(&self) -> Option<Primitive<'_>> {
+            self.0.primitive()
+        }
+
+        #[cfg(feature = "postgres")]
+        #[inline]
+        fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+            self.0.to_sql_single()
+        }
+
+        #[cfg(feature = "postgres")]
+        #[inline]
+        fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+            self.0.to_sql_multi()
+        }
+
+        #[cfg(feature = "rest")]
+        #[inline]
+        fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+            self.0.to_http_single(convention)
+        }
+
+        #[cfg(feature = "rest")]
+        #[inline]
+        fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+            self.0.to_http_multi(convention)
+        }
+    };
+}
+
+impl<T, Q> Query<T> for OrderBy<Q, T>
+where
+    Q: Query<T>,
+{
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        let Self(query, _) = self;
+        query.evaluate(data)
+    }
+
+    #[inline]
+    fn shape(&self) -> Shape<T> {
+        let Self(query, keys) = self;
+        let mut shape = query.shape();
+        let mut order = keys.clone();
+        order.append(&mut shape.order);
+        Shape { order, ..shape }
+    }
+
+    forward_translation!();
+}
+
+impl<T, Q> Query<T> for Limit<Q>
+where
+    Q: Query<T>,
+{
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        let Self(query, _) = self;
+        query.evaluate(data)
+    }
+
+    #[inline]
+    fn shape(&self) -> Shape<T> {
+        let Self(query, limit) = self;
+        let mut shape = query.shape();
+        shape.limit = Some(shape.limit.map_or(*limit, |inner| inner.min(*limit)));
+        shape
+    }
+
+    forward_translation!();
+}
+
+impl<T, Q> Query<T> for Offset<Q>
+where
+    Q: Query<T>,
+{
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        let Self(query, _) = self;
+        query.evaluate(data)
+    }
+
+    #[inline]
+    fn shape(&self) -> Shape<T> {
+        let Self(query, offset) = self;
+        let mut shape = query.shape();
+        shape.limit = shape.limit.map(|limit| limit.saturating_sub(*offset));
+        shape.offset = shape.offset.saturating_add(*offset);
+        shape
+    }
+
+    forward_translation!();
+}
+
+impl<T, L, R> Query<T> for Either<L, R>
+where
+    L: Query<T>,
+    R: Query<T>,
+{
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        match self {
+            Self::Left(query) => query.evaluate(data),
+            Self::Right(query) => query.evaluate(data),
+        }
+    }
+
+    #[inline]
+    fn shape(&self) -> Shape<T> {
+        match self {
+            Self::Left(query) => query.shape(),
+            Self::Right(query) => query.shape(),
+        }
+    }
+
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        match self {
+            Self::Left(query) => query.node(),
+            Self::Right(query) => query.node(),
+        }
+    }
+
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        match self {
+            Self::Left(query) => query.primitive(),
+            Self::Right(query) => query.primitive(),
+        }
+    }
+
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        match self {
+            Self::Left(query) => query.to_sql_single(),
+            Self::Right(query) => query.to_sql_single(),
+        }
+    }
+
+    /// Translation is impossible if there is residue.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
+    }
+
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        match self {
+            Self::Left(query) => query.to_http_single(convention),
+            Self::Right(query) => query.to_http_single(convention),
+        }
+    }
+
+    /// Translation is impossible.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        match self {
+            Self::Left(query) => query.to_http_multi(convention),
+            Self::Right(query) => query.to_http_multi(convention),
+        }
+    }
+}
+
+impl<T, Q> Query<T> for Box<Q>
+where
+    Q: Query<T> + ?Sized,
+{
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        (**self).evaluate(data)
+    }
+
+    #[inline]
+    fn shape(&self) -> Shape<T> {
+        (**self).shape()
+    }
+
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        (**self).node()
+    }
+
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        (**self).primitive()
+    }
+
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        (**self).to_sql_single()
+    }
+
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        (**self).to_sql_multi()
+    }
+
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        (**self).to_http_single(convention)
+    }
+
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        (**self).to_http_multi(convention)
+    }
+}
+
+impl IntoOwned for True {
+    type Owned = Self;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        self
+    }
+}
+
+/// Implement [`IntoOwned`] for comparisons between a field and a single value.
+macro_rules! impl_into_owned_comparison {
+    ($($name:ident),+) => {
+        $(
+            impl<F, V> IntoOwned for $name<'_, F, V>
+            where
+                V: ToOwned + ?Sized + 'static,
+            {
+                type Owned = $name<'static, F, V>;
+
+                #[inline]
+                fn into_owned(self) -> Self::Owned {
+                    let Self { field, value } = self;
+                    $name {
+                        field,
+                        value: Cow::Owned(value.into_owned()),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_into_owned_comparison!(Eq, Ne, Gt, Lt, Ge, Le);
+
+impl<F, V> IntoOwned for Between<'_, F, V>
+where
+    V: ToOwned + ?Sized + 'static,
+{
+    type Owned = Between<'static, F, V>;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        let Self { field, low, high } = self;
+        Between {
+            field,
+            low: Cow::Owned(low.into_owned()),
+            high: Cow::Owned(high.into_owned()),
+        }
+    }
+}
+
+impl<F, V> IntoOwned for In<'_, F, V>
+where
+    V: Clone + 'static,
+{
+    type Owned = In<'static, F, V>;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        let Self { field, values } = self;
+        In {
+            field,
+            values: Cow::Owned(values.into_owned()),
+        }
+    }
+}
+
+/// Implement [`IntoOwned`] for string predicates.
+macro_rules! impl_into_owned_pattern {
+    ($($name:ident),+) => {
+        $(
+            impl<F> IntoOwned for $name<'_, F> {
+                type Owned = $name<'static, F>;
+
+                #[inline]
+                fn into_owned(self) -> Self::Owned {
+                    let Self {
+                        field,
+                        value,
+                        ignore_case,
+                    } = self;
+                    $name {
+                        field,
+                        value: Cow::Owned(value.into_owned()),
+                        ignore_case,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_into_owned_pattern!(StartsWith, Contains, EndsWith);
+
+impl<F> IntoOwned for EqIgnoreCase<'_, F> {
+    type Owned = EqIgnoreCase<'static, F>;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        let Self { field, value } = self;
+        EqIgnoreCase {
+            field,
+            value: Cow::Owned(value.into_owned()),
+        }
+    }
+}
+
+/// Implement [`IntoOwned`] for comparisons between two fields, which borrow nothing.
+macro_rules! impl_into_owned_field_comparison {
+    ($($name:ident),+) => {
+        $(
+            impl<L, R> IntoOwned for $name<L, R> {
+                type Owned = Self;
+
+                #[inline]
+                fn into_owned(self) -> Self::Owned {
+                    self
+                }
+            }
+        )+
+    };
+}
+
+impl_into_owned_field_comparison!(FieldEq, FieldNe, FieldGt, FieldLt, FieldGe, FieldLe);
+
+impl<F> IntoOwned for IsNone<F> {
+    type Owned = Self;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        self
+    }
+}
+
+impl<F> IntoOwned for IsSome<F> {
+    type Owned = Self;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        self
+    }
+}
+
+impl<F, Q> IntoOwned for AnyElement<F, Q>
+where
+    Q: IntoOwned,
+{
+    type Owned = AnyElement<F, Q::Owned>;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        let Self { field, predicate } = self;
+        AnyElement {
+            field,
+            predicate: predicate.into_owned(),
+        }
+    }
+}
+
+/// Implement [`IntoOwned`] for binary combinators.
+macro_rules! impl_into_owned_binary {
+    ($($name:ident),+) => {
+        $(
+            impl<L, R> IntoOwned for $name<L, R>
+            where
+                L: IntoOwned,
+                R: IntoOwned,
+            {
+                type Owned = $name<L::Owned, R::Owned>;
+
+                #[inline]
+                fn into_owned(self) -> Self::Owned {
+                    let Self(lhs, rhs) = self;
+                    $name(lhs.into_owned(), rhs.into_owned())
+                }
+            }
+        )+
+    };
+}
+
+impl_into_owned_binary!(And, Or, Xor);
+
+impl<Q> IntoOwned for Not<Q>
+where
+    Q: IntoOwned,
+{
+    type Owned = Not<Q::Owned>;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        let Self(query) = self;
+        Not(query.into_owned())
+    }
+}
+
+/// Implement [`IntoOwned`] for variadic combinators.
+macro_rules! impl_into_owned_variadic {
+    ($($name:ident),+) => {
+        $(
+            impl<Q> IntoOwned for $name<Q>
+            where
+                Q: IntoOwned,
+            {
+                type Owned = $name<Q::Owned>;
+
+                #[inline]
+                fn into_owned(self) -> Self::Owned {
+                    let Self(queries) = self;
+                    $name(queries.into_iter().map(IntoOwned::into_owned).collect())
+                }
+            }
+        )+
+    };
+}
+
+impl_into_owned_variadic!(All, Any, One);
+
+impl<Q, T> IntoOwned for OrderBy<Q, T>
+where
+    Q: IntoOwned,
+{
+    type Owned = OrderBy<Q::Owned, T>;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        let Self(query, keys) = self;
+        OrderBy(query.into_owned(), keys)
+    }
+}
+
+impl<Q> IntoOwned for Limit<Q>
+where
+    Q: IntoOwned,
+{
+    type Owned = Limit<Q::Owned>;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        let Self(query, limit) = self;
+        Limit(query.into_owned(), limit)
+    }
+}
+
+impl<Q> IntoOwned for Offset<Q>
+where
+    Q: IntoOwned,
+{
+    type Owned = Offset<Q::Owned>;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        let Self(query, offset) = self;
+        Offset(query.into_owned(), offset)
+    }
+}
+
+impl<L, R> IntoOwned for Either<L, R>
+where
+    L: IntoOwned,
+    R: IntoOwned,
+{
+    type Owned = Either<L::Owned, R::Owned>;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        self.map_either(IntoOwned::into_owned, IntoOwned::into_owned)
+    }
+}
+
+// Operators mirror the alternate Debug format, such that `a & !b` builds `And(a, Not(b))`. Since
+// `Either` is a foreign type, it only supports binary operators with a combinator on the right, and
+// can not be negated using `!`.
+
+/// Implement the `&`, `|`, `^` and `!` operators for combinators, given their generic parameters
+/// followed by a trailing comma.
+macro_rules! impl_operators {
+    ($([$($generics:tt)*] $name:ty),+ $(,)?) => {
+        $(
+            #[allow(single_use_lifetimes, reason = "Lifetimes are named for use with `Either`.")]
+            impl<$($generics)* Rhs> BitAnd<Rhs> for $name {
+                type Output = And<Self, Rhs>;
+
+                #[inline]
+                fn bitand(self, rhs: Rhs) -> Self::Output {
+                    And(self, rhs)
+                }
+            }
+
+            #[allow(single_use_lifetimes, reason = "Lifetimes are named for use with `Either`.")]
+            impl<$($generics)* Rhs> BitOr<Rhs> for $name {
+                type Output = Or<Self, Rhs>;
+
+                #[inline]
+                fn bitor(self, rhs: Rhs) -> Self::Output {
+                    Or(self, rhs)
+                }
+            }
+
+            #[allow(single_use_lifetimes, reason = "Lifetimes are named for use with `Either`.")]
+            impl<$($generics)* Rhs> BitXor<Rhs> for $name {
+                type Output = Xor<Self, Rhs>;
+
+                #[inline]
+                fn bitxor(self, rhs: Rhs) -> Self::Output {
+                    Xor(self, rhs)
+                }
+            }
+
+            #[allow(single_use_lifetimes, reason = "Lifetimes are named for use with `Either`.")]
+            impl<$($generics)*> ops::Not for $name {
+                type Output = Not<Self>;
+
+                #[inline]
+                fn not(self) -> Self::Output {
+                    Not(self)
+                }
+            }
+
+            impl<$($generics)* A, B> BitAnd<$name> for Either<A, B> {
+                type Output = And<Self, $name>;
+
+                #[inline]
+                fn bitand(self, rhs: $name) -> Self::Output {
+                    And(self, rhs)
+                }
+            }
+
+            impl<$($generics)* A, B> BitOr<$name> for Either<A, B> {
+                type Output = Or<Self, $name>;
+
+                #[inline]
+                fn bitor(self, rhs: $name) -> Self::Output {
+                    Or(self, rhs)
+                }
+            }
+
+            impl<$($generics)* A, B> BitXor<$name> for Either<A, B> {
+                type Output = Xor<Self, $name>;
+
+                #[inline]
+                fn bitxor(self, rhs: $name) -> Self::Output {
+                    Xor(self, rhs)
+                }
+            }
+        )+
+    };
+}
+
+impl_operators!(
+    [] True,
+    ['a, F, V: ToOwned + ?Sized,] Eq<'a, F, V>,
+    ['a, F, V: ToOwned + ?Sized,] Ne<'a, F, V>,
+    ['a, F, V: ToOwned + ?Sized,] Gt<'a, F, V>,
+    ['a, F, V: ToOwned + ?Sized,] Lt<'a, F, V>,
+    ['a, F, V: ToOwned + ?Sized,] Ge<'a, F, V>,
+    ['a, F, V: ToOwned + ?Sized,] Le<'a, F, V>,
+    [L, R,] And<L, R>,
+    [L, R,] Or<L, R>,
+    [L, R,] Xor<L, R>,
+    [Q,] Not<Q>,
+);
+
+// Debug implementations support two formats:
+// - Standard (`{:?}`): structured, machine-readable
+// - Alternate (`{:#?}`): compact, human-readable query syntax
+
+impl Debug for True {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        if f.alternate() {
+            write!(f, "true")
+        } else {
+            f.debug_struct(name_of_type!(Self)).finish()
+        }
+    }
+}
+
+impl<T, V: ?Sized, U> Debug for Eq<'_, Field<T, V>, U>
+where
+    U: Debug + ToOwned + ?Sized,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { field: _, value } = self;
+        if f.alternate() {
+            write!(f, "{} = {:#?}", self.field.name, &**value)
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &self.field.name)
+                .field(name_of!(value in Self), &&**value)
+                .finish()
+        }
+    }
+}
+
+impl<T, V: ?Sized, U> Debug for Ne<'_, Field<T, V>, U>
+where
+    U: Debug + ToOwned + ?Sized,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { field: _, value } = self;
+        if f.alternate() {
+            write!(f, "{} != {:#?}", self.field.name, &**value)
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &self.field.name)
+                .field(name_of!(value in Self), &&**value)
+                .finish()
+        }
+    }
+}
+
+impl<T, V: ?Sized, U> Debug for Gt<'_, Field<T, V>, U>
+where
+    U: Debug + ToOwned + ?Sized,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { field: _, value } = self;
+        if f.alternate() {
+            write!(f, "{} > {:#?}", self.field.name, &**value)
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &self.field.name)
+                .field(name_of!(value in Self), &&**value)
+                .finish()
+        }
+    }
+}
+
+impl<T, V: ?Sized, U> Debug for Lt<'_, Field<T, V>, U>
+where
+    U: Debug + ToOwned + ?Sized,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { field: _, value } = self;
+        if f.alternate() {
+            write!(f, "{} < {:#?}", self.field.name, &**value)
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &self.field.name)
+                .field(name_of!(value in Self), &&**value)
+                .finish()
+        }
+    }
+}
+
+impl<T, V: ?Sized, U> Debug for Ge<'_, Field<T, V>, U>
+where
+    U: Debug + ToOwned + ?Sized,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { field: _, value } = self;
+        if f.alternate() {
+            write!(f, "{} >= {:#?}", self.field.name, &**value)
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &self.field.name)
+                .field(name_of!(value in Self), &&**value)
+                .finish()
+        }
+    }
+}
+
+impl<T, V: ?Sized, U> Debug for Le<'_, Field<T, V>, U>
+where
+    U: Debug + ToOwned + ?Sized,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { field: _, value } = self;
+        if f.alternate() {
+            write!(f, "{} <= {:#?}", self.field.name, &**value)
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &self.field.name)
+                .field(name_of!(value in Self), &&**value)
+                .finish()
+        }
+    }
+}
+
+impl<T, V: ?Sized, U> Debug for Between<'_, Field<T, V>, U>
+where
+    U: Debug + ToOwned + ?Sized,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { field, low, high } = self;
+        if f.alternate() {
+            write!(
+                f,
+                "({name} >= {low:#?}) & ({name} <= {high:#?})",
+                name = field.name,
+                low = &**low,
+                high = &**high,
+            )
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &field.name)
+                .field(name_of!(low in Self), &&**low)
+                .field(name_of!(high in Self), &&**high)
+                .finish()
+        }
+    }
+}
+
+impl<T, V: ?Sized, U> Debug for In<'_, Field<T, V>, U>
+where
+    U: Debug + Clone,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { field, values } = self;
+        if f.alternate() {
+            write!(f, "{}.is_in([", field.name)?;
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                value.fmt(f)?;
+            }
+            write!(f, "])")
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &field.name)
+                .field(name_of!(values in Self), values)
+                .finish()
+        }
+    }
+}
+
+impl<T, V: ?Sized> Debug for StartsWith<'_, Field<T, V>> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        if f.alternate() {
+            write!(f, "{}.starts_with({value:#?})", field.name)?;
+            if *ignore_case {
+                write!(f, ".ignore_case()")?;
+            }
+            Ok(())
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &field.name)
+                .field(name_of!(value in Self), value)
+                .field(name_of!(ignore_case in Self), ignore_case)
+                .finish()
+        }
+    }
+}
+
+impl<T, V: ?Sized> Debug for Contains<'_, Field<T, V>> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        if f.alternate() {
+            write!(f, "{}.contains({value:#?})", field.name)?;
+            if *ignore_case {
+                write!(f, ".ignore_case()")?;
+            }
+            Ok(())
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &field.name)
+                .field(name_of!(value in Self), value)
+                .field(name_of!(ignore_case in Self), ignore_case)
+                .finish()
+        }
+    }
+}
+
+impl<T, V: ?Sized> Debug for EndsWith<'_, Field<T, V>> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self {
+            field,
+            value,
+            ignore_case,
+        } = self;
+        if f.alternate() {
+            write!(f, "{}.ends_with({value:#?})", field.name)?;
+            if *ignore_case {
+                write!(f, ".ignore_case()")?;
+            }
+            Ok(())
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &field.name)
+                .field(name_of!(value in Self), value)
+                .field(name_of!(ignore_case in Self), ignore_case)
+                .finish()
+        }
+    }
+}
+
+impl<T, V: ?Sized> Debug for EqIgnoreCase<'_, Field<T, V>> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { field, value } = self;
+        if f.alternate() {
+            write!(f, "{}.eq_ignore_case({value:#?})", field.name)
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &field.name)
+                .field(name_of!(value in Self), value)
+                .finish()
+        }
+    }
+}
+
+/// Implement [`Debug`] for comparisons between two fields, given the operator used by the
+/// alternate format.
+macro_rules! impl_debug_field_comparison {
+    ($($name:ident($operator:literal)),+ $(,)?) => {
+        $(
+            impl<T, U: ?Sized, W: ?Sized> Debug for $name<Field<T, U>, Field<T, W>> {
+                #[inline]
+                fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+                    let Self { left, right } = self;
+                    if f.alternate() {
+                        write!(f, "{} {} {}", left.name, $operator, right.name)
+                    } else {
+                        f.debug_struct(name_of_type!(Self))
+                            .field(name_of!(left in Self), &left.name)
+                            .field(name_of!(right in Self), &right.name)
+                            .finish()
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_debug_field_comparison!(
+    FieldEq("="),
+    FieldNe("!="),
+    FieldGt(">"),
+    FieldLt("<"),
+    FieldGe(">="),
+    FieldLe("<="),
+);
+
+impl<T, U> Debug for IsNone<Field<T, U>> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { field } = self;
+        if f.alternate() {
+            write!(f, "{}.is_none()", field.name)
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &field.name)
+                .finish()
+        }
+    }
+}
+
+impl<T, U> Debug for IsSome<Field<T, U>> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { field } = self;
+        if f.alternate() {
+            write!(f, "{}.is_some()", field.name)
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &field.name)
+                .finish()
+        }
+    }
+}
+
+impl<T, U, Q> Debug for AnyElement<Field<T, U>, Q>
+where
+    Q: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { field, predicate } = self;
+        if f.alternate() {
+            write!(f, "{}.any_element({predicate:#?})", field.name)
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(field in Self), &field.name)
+                .field(name_of!(predicate in Self), predicate)
+                .finish()
+        }
+    }
+}
+
+impl<L, R> Debug for And<L, R>
+where
+    L: Debug,
+    R: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self(lhs, rhs) = self;
+        if f.alternate() {
+            write!(f, "({lhs:#?}) & ({rhs:#?})")
+        } else {
+            f.debug_tuple(name_of_type!(Self))
+                .field(lhs)
+                .field(rhs)
+                .finish()
+        }
+    }
+}
+
+impl<L, R> Debug for Or<L, R>
+where
+    L: Debug,
+    R: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self(lhs, rhs) = self;
+        if f.alternate() {
+            write!(f, "({lhs:#?}) | ({rhs:#?})")
+        } else {
+            f.debug_tuple(name_of_type!(Self))
+                .field(lhs)
+                .field(rhs)
+                .finish()
+        }
+    }
+}
+
+impl<L, R> Debug for Xor<L, R>
+where
+    L: Debug,
+    R: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self(lhs, rhs) = self;
+        if f.alternate() {
+            write!(f, "({lhs:#?}) ^ ({rhs:#?})")
+        } else {
+            f.debug_tuple(name_of_type!(Self))
+                .field(lhs)
+                .field(rhs)
+                .finish()
+        }
+    }
+}
+
+impl<Q> Debug for Not<Q>
+where
+    Q: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self(query) = self;
+        if f.alternate() {
+            write!(f, "!({query:#?})")
+        } else {
+            f.debug_tuple(name_of_type!(Self)).field(query).finish()
+        }
+    }
+}
+
+/// Backing implementation for the [`Debug`] implementations of variadic combinators. The alternate
+/// format is `{name}(query, ...)`.
+///
+/// # Errors
+///
+/// Propagates errors from the formatter.
+fn variadic_fmt<Q>(
+    f: &mut Formatter<'_>,
+    type_name: &str,
+    name: &str,
+    queries: &[Q],
+) -> Result<(), FmtError>
+where
+    Q: Debug,
+{
+    if f.alternate() {
+        write!(f, "{name}(")?;
+        for (i, query) in queries.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            query.fmt(f)?;
+        }
+        write!(f, ")")
+    } else {
+        f.debug_tuple(type_name).field(&queries).finish()
+    }
+}
+
+impl<Q> Debug for All<Q>
+where
+    Q: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self(queries) = self;
+        variadic_fmt(f, name_of_type!(Self), "all", queries)
+    }
+}
+
+impl<Q> Debug for Any<Q>
+where
+    Q: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self(queries) = self;
+        variadic_fmt(f, name_of_type!(Self), "any", queries)
+    }
+}
+
+impl<Q> Debug for One<Q>
+where
+    Q: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self(queries) = self;
+        variadic_fmt(f, name_of_type!(Self), "one", queries)
+    }
+}
+
+impl<Q, T> Debug for OrderBy<Q, T>
+where
+    Q: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self(query, keys) = self;
+        if f.alternate() {
+            write!(f, "order_by({query:#?}")?;
+            for key in keys {
+                write!(f, ", {key:#?}")?;
+            }
+            write!(f, ")")
+        } else {
+            f.debug_tuple(name_of_type!(Self))
+                .field(query)
+                .field(keys)
+                .finish()
+        }
+    }
+}
+
+impl<Q> Debug for Limit<Q>
+where
+    Q: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self(query, limit) = self;
+        if f.alternate() {
+            write!(f, "limit({query:#?}, {limit})")
+        } else {
+            f.debug_tuple(name_of_type!(Self))
+                .field(query)
+                .field(limit)
+                .finish()
+        }
+    }
+}
+
+impl<Q> Debug for Offset<Q>
+where
+    Q: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self(query, offset) = self;
+        if f.alternate() {
+            write!(f, "offset({query:#?}, {offset})")
+        } else {
+            f.debug_tuple(name_of_type!(Self))
+                .field(query)
+                .field(offset)
+                .finish()
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    reason = "Panics simply indicate failed tests."
+)]
+#[allow(clippy::unwrap_used, reason = "Panics simply indicate failed tests.")]
+mod tests {
+    use super::*;
+    #[cfg(feature = "postgres")]
+    use crate::query::UnknownColumn;
+    #[cfg(feature = "rest")]
+    use crate::query::http::{FieldMap, ShapeParams, StringParams, ValueFormat};
+    use crate::query::{Queryable, optimize, query};
+
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestBook {
+        title: String,
+        year: u16,
+    }
+
+    impl TestBook {
+        fn new(title: &str, year: u16) -> Self {
+            Self {
+                title: title.to_owned(),
+                year,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestEdition {
+        title: String,
+        original_title: String,
+        written: u16,
+        published: u16,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestRecord {
+        year: Option<u16>,
+        authors: Vec<String>,
+    }
+
+    #[cfg(feature = "postgres")]
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestShelf {
+        books: Vec<TestBook>,
+    }
+
+    impl TestRecord {
+        fn new(year: Option<u16>, authors: &[&str]) -> Self {
+            Self {
+                year,
+                authors: authors.iter().map(|&author| author.to_owned()).collect(),
+            }
+        }
+    }
+
+    /// Spelled differently by [`Display`] and [`Serialize`].
+    #[cfg(any(feature = "rest", feature = "postgres"))]
+    #[derive(Clone, Debug, PartialEq, Serialize)]
+    enum TestFormat {
+        Pdf,
+        Epub,
+    }
+
+    #[cfg(any(feature = "rest", feature = "postgres"))]
+    impl Display for TestFormat {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+            f.write_str(match self {
+                Self::Pdf => "PDF",
+                Self::Epub => "ePub",
+            })
+        }
+    }
+
+    #[cfg(feature = "postgres")]
+    impl ToSqlParam for TestFormat {
+        fn to_sql_param(&self) -> SqlParam {
+            SqlParam::serialized(self).unwrap()
+        }
+    }
+
+    /// Serialized as null, like [`None`].
+    #[cfg(any(feature = "rest", feature = "postgres"))]
+    #[derive(Clone, Debug, PartialEq, Serialize)]
+    struct TestUnknown;
+
+    #[cfg(any(feature = "rest", feature = "postgres"))]
+    impl Display for TestUnknown {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+            f.write_str("unknown")
+        }
+    }
+
+    #[cfg(any(feature = "rest", feature = "postgres"))]
+    impl PartialEq<TestUnknown> for Option<u16> {
+        #[inline]
+        fn eq(&self, _: &TestUnknown) -> bool {
+            self.is_none()
+        }
+    }
+
+    #[cfg(feature = "postgres")]
+    impl ToSqlParam for TestUnknown {
+        fn to_sql_param(&self) -> SqlParam {
+            SqlParam::serialized(self).unwrap()
+        }
+    }
+
+    #[cfg(any(feature = "rest", feature = "postgres"))]
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestCopy {
+        format: TestFormat,
+    }
+
+    impl TestEdition {
+        fn new(title: &str, original_title: &str, written: u16, published: u16) -> Self {
+            Self {
+                title: title.to_owned(),
+                original_title: original_title.to_owned(),
+                written,
+                published,
+            }
+        }
+    }
+
+    #[test]
+    fn into_owned() {
+        fn owned(title: &str, years: &[u16]) -> impl Query<TestBook> + Send + 'static {
+            Xor(
+                TestBook::title().starts_with(title).ignore_case(),
+                And(
+                    TestBook::year().is_in(years),
+                    TestBook::year().ne(&years[0]),
+                ),
+            )
+            .into_owned()
+        }
+
+        let query = owned(&String::from("e"), &[1815, 1817]);
+
+        assert!(query.evaluate(&TestBook::new("Emma", 1814)));
+        assert!(query.evaluate(&TestBook::new("Persuasion", 1817)));
+        assert!(!query.evaluate(&TestBook::new("Emma", 1817)));
+        assert!(!query.evaluate(&TestBook::new("Persuasion", 1815)));
+        assert_eq!(
+            format!("{query:#?}"),
+            r#"(title.starts_with("e").ignore_case()) ^ ((year.is_in([1815, 1817])) & (year != 1815))"#
+        );
+    }
+
+    #[test]
+    fn evaluate() {
+        let book = TestBook::new("Emma", 1815);
+
+        assert!(Query::evaluate(&TestBook::title().eq("Emma"), &book));
+        assert!(Query::evaluate(&TestBook::year().gt(&1800), &book));
+        assert!(!Query::evaluate(
+            &And(TestBook::title().eq("Emma"), TestBook::year().lt(&1800)),
+            &book
+        ));
+    }
+
+    #[test]
+    fn query_macro() {
+        let book = TestBook::new("Emma", 1815);
+        let since = 1800;
+        let infix = query!(
+            TestBook: year > since && (title == "Emma" || title.starts_with("P").ignore_case())
+        );
+        let typed = And(
+            TestBook::year().gt(&since),
+            Or(
+                TestBook::title().eq("Emma"),
+                TestBook::title().starts_with("P").ignore_case(),
+            ),
+        );
+
+        assert!(Query::evaluate(&infix, &book));
+        assert_eq!(format!("{infix:#?}"), format!("{typed:#?}"));
+        assert!(!Query::evaluate(
+            &query!(TestBook: !(title != "Emma") ^ true),
+            &book
+        ));
+        assert!(!Query::evaluate(
+            &query!(TestBook: year.between(&1700, &1800) || false),
+            &book
+        ));
+    }
+
+    #[test]
+    fn operators() {
+        let book = TestBook::new("Emma", 1815);
+        let query = TestBook::title().eq("Emma") & !(TestBook::year().lt(&1800) | True ^ True);
+        let combined = And(
+            TestBook::title().eq("Emma"),
+            Not(Or(TestBook::year().lt(&1800), Xor(True, True))),
+        );
+        let either = None::<&str>.map_or(Either::Right(True), |title| {
+            Either::Left(TestBook::title().eq(title))
+        });
+
+        assert!(Query::evaluate(&query, &book));
+        assert_eq!(format!("{query:#?}"), format!("{combined:#?}"));
+        assert!(Query::evaluate(
+            &(either ^ TestBook::year().ge(&1900)),
+            &book
+        ));
+    }
+
+    #[test]
+    fn evaluate_ranges() {
+        let book = TestBook::new("Emma", 1815);
+
+        assert!(Query::evaluate(&TestBook::year().ge(&1815), &book));
+        assert!(Query::evaluate(&TestBook::year().le(&1815), &book));
+        assert!(Query::evaluate(
+            &TestBook::year().between(&1800, &1815),
+            &book
+        ));
+        assert!(!Query::evaluate(
+            &TestBook::year().between(&1816, &1900),
+            &book
+        ));
+    }
+
+    /// Constructs type-erased queries, some of which match [`TestBook::new("Emma", 1815)`].
+    fn boxed_queries() -> Vec<Box<dyn Query<TestBook> + Sync>> {
+        vec![
+            Box::new(TestBook::title().eq("Emma")),
+            Box::new(TestBook::year().gt(&1800)),
+            Box::new(TestBook::year().lt(&1800)),
+        ]
+    }
+
+    #[test]
+    fn evaluate_variadic() {
+        let book = TestBook::new("Emma", 1815);
+
+        assert!(!Query::evaluate(&All(boxed_queries()), &book));
+        assert!(Query::evaluate(&Any(boxed_queries()), &book));
+        assert!(!Query::evaluate(&One(boxed_queries()), &book));
+        assert!(Query::evaluate(
+            &One(vec![TestBook::year().eq(&1815), TestBook::year().eq(&1816)]),
+            &book
+        ));
+        assert!(Query::<TestBook>::evaluate(&All::<True>(Vec::new()), &book));
+        assert!(!Query::<TestBook>::evaluate(
+            &Any::<True>(Vec::new()),
+            &book
+        ));
+    }
+
+    #[test]
+    fn debug_variadic() {
+        let query: All<Box<dyn Query<TestBook> + Sync>> = All(vec![
+            Box::new(TestBook::title().eq("Emma")),
+            Box::new(Any(vec![
+                TestBook::year().gt(&1800),
+                TestBook::year().gt(&1700),
+            ])),
+        ]);
+
+        assert_eq!(
+            format!("{query:#?}"),
+            r#"all(title = "Emma", any(year > 1800, year > 1700))"#
+        );
+    }
+
+    #[test]
+    fn evaluate_strings() {
+        let book = TestBook::new("Emma", 1815);
+
+        assert!(Query::evaluate(&TestBook::title().starts_with("Em"), &book));
+        assert!(!Query::evaluate(
+            &TestBook::title().starts_with("em"),
+            &book
+        ));
+        assert!(Query::evaluate(
+            &TestBook::title().starts_with("em").ignore_case(),
+            &book
+        ));
+        assert!(Query::evaluate(&TestBook::title().contains("mm"), &book));
+        assert!(Query::evaluate(&TestBook::title().ends_with("ma"), &book));
+        assert!(Query::evaluate(
+            &TestBook::title().eq_ignore_case("EMMA"),
+            &book
+        ));
+    }
+
+    #[test]
+    fn debug_strings() {
+        let query = And(
+            TestBook::title().contains("m"),
+            TestBook::title().starts_with("e").ignore_case(),
+        );
+
+        assert_eq!(
+            format!("{query:#?}"),
+            r#"(title.contains("m")) & (title.starts_with("e").ignore_case())"#
+        );
+    }
+
+    #[cfg(feature = "rest")]
+    #[test]
+    fn http_strings() {
+        let convention = HttpConvention::new().string(StringParams {
+            contains: Some(ParamName::suffix("_like")),
+            ignore_case: true,
+            ..StringParams::new()
+        });
+        let insensitive = TestBook::title().contains("em").ignore_case();
+        let sensitive = TestBook::title().contains("Em");
+        let unsupported = TestBook::title().ends_with("ma");
+
+        let insensitive = insensitive.to_http_single(&convention);
+        let sensitive_multi = sensitive.to_http_multi(&convention);
+        let sensitive = sensitive.to_http_single(&convention);
+        let unsupported = unsupported.to_http_single(&convention);
+
+        assert!(insensitive.residue.is_empty());
+        assert_eq!(
+            insensitive.query,
+            [(Cow::Owned("title_like".to_owned()), "em".into())]
+        );
+        assert_eq!(sensitive.query.len(), 1);
+        assert_eq!(sensitive.residue.len(), 1);
+        assert!(sensitive_multi.is_none());
+        assert!(unsupported.query.is_empty());
+        assert_eq!(unsupported.residue.len(), 1);
+    }
+
+    #[test]
+    fn evaluate_in() {
+        let book = TestBook::new("Emma", 1815);
+
+        assert!(Query::evaluate(
+            &TestBook::year().is_in(&[1814, 1815]),
+            &book
+        ));
+        assert!(!Query::evaluate(&TestBook::year().is_in(&[1816]), &book));
+        assert!(!Query::evaluate(&TestBook::year().is_in::<u16>(&[]), &book));
+    }
+
+    #[test]
+    fn debug_in() {
+        let query = TestBook::title().is_in(&["Emma", "Persuasion"]);
+
+        assert_eq!(
+            format!("{query:#?}"),
+            r#"title.is_in(["Emma", "Persuasion"])"#
+        );
+    }
+
+    #[test]
+    fn negate_in() {
+        let query = Not(TestBook::title().is_in(&["Emma", "Persuasion"]));
+        let negated = optimize(&query);
+        let emma = TestBook::new("Emma", 1815);
+        let sanditon = TestBook::new("Sanditon", 1817);
+
+        assert_eq!(
+            format!("{negated:#?}"),
+            r#"all(title != "Emma", title != "Persuasion")"#
+        );
+        assert!(!negated.evaluate(&emma));
+        assert!(negated.evaluate(&sanditon));
+    }
+
+    #[cfg(feature = "rest")]
+    #[test]
+    fn http_in() {
+        let query = TestBook::title().is_in(&["Emma", "Persuasion"]);
+        let repeated = HttpConvention::new().list(ListParams::repeated());
+        let joined = HttpConvention::new().list(ListParams::comma_joined());
+
+        let repeated = query.to_http_single(&repeated);
+        let joined = query.to_http_single(&joined);
+        let unsupported = query.to_http_single(&HttpConvention::new());
+        let multi = query.to_http_multi(&HttpConvention::new()).unwrap();
+
+        assert_eq!(
+            repeated.query,
+            [
+                (Cow::Borrowed("title"), "Emma".into()),
+                (Cow::Borrowed("title"), "Persuasion".into()),
+            ]
+        );
+        assert_eq!(
+            joined.query,
+            [(Cow::Borrowed("title"), "Emma,Persuasion".into())]
+        );
+        assert!(unsupported.query.is_empty());
+        assert_eq!(unsupported.residue.len(), 1);
+        assert_eq!(
+            multi,
+            [
+                [(Cow::Borrowed("title"), "Emma".into())],
+                [(Cow::Borrowed("title"), "Persuasion".into())],
+            ]
+        );
+    }
+
+    #[cfg(feature = "rest")]
+    #[test]
+    fn http_in_joined_separator() {
+        let convention = HttpConvention::new().list(ListParams::comma_joined());
+        let query = TestBook::title().is_in(&["Emma", "Pride, Prejudice"]);
+
+        let single = query.to_http_single(&convention);
+
+        assert!(single.query.is_empty());
+        assert_eq!(single.residue.len(), 1);
+    }
+
+    #[cfg(feature = "rest")]
+    #[test]
+    fn http_variadic() {
+        let convention = HttpConvention::new();
+        let query = Any(vec![
+            And(TestBook::title().eq("Emma"), TestBook::year().eq(&1815)),
+            And(TestBook::title().eq("Emma"), TestBook::year().eq(&1816)),
+        ]);
+
+        let single = query.to_http_single(&convention);
+        let multi = query.to_http_multi(&convention).unwrap();
+
+        assert_eq!(single.query, [(Cow::Borrowed("title"), "Emma".into())]);
+        assert_eq!(single.residue.len(), 1);
+        assert_eq!(multi.len(), 2);
+        assert_eq!(multi[1][1], (Cow::Borrowed("year"), "1816".into()));
+    }
+
+    #[cfg(feature = "rest")]
+    #[test]
+    fn http_range_params() {
+        let convention = HttpConvention::new().range(RangeParams::suffixed());
+        let query = And(
+            TestBook::title().eq("Emma"),
+            TestBook::year().between(&1800, &1900),
+        );
+
+        let single = query.to_http_single(&convention);
+
+        assert!(single.residue.is_empty());
+        assert_eq!(
+            single.query,
+            [
+                (Cow::Borrowed("title"), "Emma".into()),
+                (Cow::Owned("year_gte".to_owned()), "1800".into()),
+                (Cow::Owned("year_lte".to_owned()), "1900".into()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "rest")]
+    #[test]
+    fn http_range_residue() {
+        let convention = HttpConvention::new();
+        let query = And(TestBook::title().eq("Emma"), TestBook::year().gt(&1800));
+
+        let single = query.to_http_single(&convention);
+
+        assert_eq!(single.query, [(Cow::Borrowed("title"), "Emma".into())]);
+        assert_eq!(single.residue.len(), 1);
+        assert!(query.to_http_multi(&convention).is_none());
+    }
+
+    #[cfg(feature = "rest")]
+    #[test]
+    fn http_field_map() {
+        let fields = FieldMap::new()
+            .rename("title", "name")
+            .map_value("title", "Emma", "EMMA")
+            .unsupported("year");
+        let convention = HttpConvention::new()
+            .list(ListParams::comma_joined())
+            .fields(fields);
+        let query = And(
+            TestBook::title().is_in(&["Emma", "Persuasion"]),
+            TestBook::year().eq(&1815),
+        );
+        let ranges = HttpConvention::new()
+            .range(RangeParams::suffixed())
+            .fields(FieldMap::new().rename("year", "published").exhaustive());
+        let range = TestBook::year().gt(&1800);
+        let unmapped = TestBook::title().eq("Emma");
+
+        let single = query.to_http_single(&convention);
+        let range = range.to_http_single(&ranges);
+        let unmapped = unmapped.to_http_single(&ranges);
+
+        assert_eq!(
+            single.query,
+            [(Cow::Borrowed("name"), "EMMA,Persuasion".into())]
+        );
+        assert_eq!(single.residue.len(), 1);
+        assert!(query.to_http_multi(&convention).is_none());
+        assert_eq!(
+            range.query,
+            [(Cow::Borrowed("published_gt"), "1800".into())]
+        );
+        assert!(unmapped.query.is_empty());
+        assert_eq!(unmapped.residue.len(), 1);
+    }
+
+    #[cfg(feature = "rest")]
+    #[test]
+    fn http_values() {
+        let query = TestCopy::format().is_in(&[TestFormat::Pdf, TestFormat::Epub]);
+        let serde = HttpConvention::new().list(ListParams::comma_joined());
+        let display = serde.clone().values(ValueFormat::Display);
+
+        assert_eq!(
+            query.to_http_single(&serde).query,
+            [(Cow::Borrowed("format"), "Pdf,Epub".into())]
+        );
+        assert_eq!(
+            query.to_http_single(&display).query,
+            [(Cow::Borrowed("format"), "PDF,ePub".into())]
+        );
+        assert_eq!(ValueFormat::Serde.write("Emma"), Some("Emma".to_owned()));
+    }
+
+    #[cfg(feature = "rest")]
+    #[test]
+    fn http_null() {
+        let query = TestRecord::year().eq(&TestUnknown);
+        let single = query.to_http_single(&HttpConvention::new());
+
+        assert_eq!(ValueFormat::Serde.write(&TestUnknown), None);
+        assert!(single.query.is_empty());
+        assert_eq!(single.residue.len(), 1);
+        assert!(single.residue[0].evaluate(&TestRecord::new(None, &[])));
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_serialized() {
+        let query = TestCopy::format().eq(&TestFormat::Epub);
+
+        assert_eq!(
+            query.to_sql_single().query.params,
+            [SqlParam::Text("Epub".to_owned())]
+        );
+        assert_eq!(
+            SqlParam::serialized(&TestFormat::Epub).unwrap(),
+            SqlParam::Text("Epub".to_owned())
+        );
+        assert_eq!(
+            SqlParam::serialized(&1815_u16).unwrap(),
+            SqlParam::BigInt(1815)
+        );
+        assert_eq!(SqlParam::serialized(&0.5).unwrap(), SqlParam::Double(0.5));
+        assert_eq!(
+            SqlParam::cast_serialized(&TestFormat::Pdf, "format").unwrap(),
+            SqlParam::Cast {
+                value: "Pdf".to_owned(),
+                sql_type: "format",
+            }
+        );
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_null() {
+        let eq = TestRecord::year().eq(&TestUnknown);
+        let single = eq.to_sql_single();
+
+        assert_eq!(SqlParam::serialized(&TestUnknown).unwrap(), SqlParam::Null);
+        assert_eq!(
+            SqlParam::cast_serialized(&TestUnknown, "format").unwrap(),
+            SqlParam::Null
+        );
+        assert_eq!(single.query.query_text, "");
+        assert!(single.query.params.is_empty());
+        assert_eq!(single.residue.len(), 1);
+        assert!(eq.to_sql_multi().is_none());
+    }
+
+    #[test]
+    fn field_comparisons() {
+        let renamed = TestEdition::title().ne_field(TestEdition::original_title());
+        let delayed = TestEdition::published().gt_field(TestEdition::written());
+        let first_impressions =
+            TestEdition::new("Pride and Prejudice", "First Impressions", 1797, 1813);
+        let emma = TestEdition::new("Emma", "Emma", 1815, 1815);
+
+        assert!(renamed.evaluate(&first_impressions));
+        assert!(!renamed.evaluate(&emma));
+        assert!(delayed.evaluate(&first_impressions));
+        assert!(!delayed.evaluate(&emma));
+        assert_eq!(format!("{delayed:#?}"), "published > written");
+        assert_eq!(
+            format!("{:#?}", optimize(&Not(delayed))),
+            "published <= written"
+        );
+    }
+
+    #[cfg(feature = "rest")]
+    #[test]
+    fn http_field_comparison() {
+        let query = TestEdition::title().eq_field(TestEdition::original_title());
+        let convention = HttpConvention::new();
+        let single = query.to_http_single(&convention);
+
+        assert!(single.query.is_empty());
+        assert_eq!(single.residue.len(), 1);
+        assert!(query.to_http_multi(&convention).is_none());
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_field_comparison() {
+        let statement = TestEdition::published()
+            .le_field(TestEdition::written())
+            .to_sql_single()
+            .query;
+
+        assert_eq!(
+            statement.query_text,
+            r#""published" <= "written" OR "published" IS NULL"#
+        );
+        assert!(statement.params.is_empty());
+        assert_eq!(statement.columns, ["published", "written"]);
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_nullable_field_comparison() {
+        let eq = TestRecord::year().eq_field(TestRecord::year());
+        let lt = TestRecord::year().lt_field(TestRecord::year());
+        let unknown = TestRecord::new(None, &[]);
+
+        assert!(eq.evaluate(&unknown));
+        assert!(!lt.evaluate(&unknown));
+        assert_eq!(
+            eq.to_sql_single().query.query_text,
+            r#""year" IS NOT DISTINCT FROM "year""#
+        );
+        assert_eq!(
+            Not(eq).to_sql_single().query.query_text,
+            r#"NOT ("year" IS NOT DISTINCT FROM "year")"#
+        );
+        assert_eq!(
+            lt.to_sql_single().query.query_text,
+            r#""year" < "year" OR ("year" IS NULL AND "year" IS NOT NULL)"#
+        );
+    }
+
+    #[test]
+    fn null_checks() {
+        let known = TestRecord::new(Some(1811), &["Jane Austen"]);
+        let unknown = TestRecord::new(None, &[]);
+
+        assert!(TestRecord::year().is_some().evaluate(&known));
+        assert!(TestRecord::year().is_none().evaluate(&unknown));
+        assert!(!TestRecord::year().is_none().evaluate(&known));
+        assert_eq!(
+            format!("{:#?}", optimize(&Not(TestRecord::year().is_none()))),
+            "year.is_some()"
+        );
+    }
+
+    #[test]
+    fn any_element() {
+        let query = TestRecord::authors().any_element(|author| author.starts_with("Jane"));
+        let austen = TestRecord::new(None, &["Jane Austen"]);
+        let anonymous = TestRecord::new(None, &[]);
+        let bronte = TestRecord::new(None, &["Charlotte Brontë", "Emily Brontë"]);
+
+        assert!(query.evaluate(&austen));
+        assert!(!query.evaluate(&anonymous));
+        assert!(!query.evaluate(&bronte));
+        assert_eq!(
+            format!("{query:#?}"),
+            r#"authors.any_element(authors.starts_with("Jane"))"#
+        );
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_null_checks() {
+        let none = TestRecord::year().is_none().to_sql_single().query;
+        let some = TestRecord::year().is_some().to_sql_single().query;
+
+        assert_eq!(none.query_text, r#""year" IS NULL"#);
+        assert_eq!(some.query_text, r#""year" IS NOT NULL"#);
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_any_element() {
+        let eq = TestRecord::authors().any_element(|author| author.eq("Jane Austen"));
+        let pattern = TestRecord::authors()
+            .any_element(|author| author.contains("Austen").ignore_case())
+            .to_sql_single()
+            .query;
+        let eq = eq.to_sql_single();
+
+        assert_eq!(eq.query.query_text, r#"$1 = ANY("authors")"#);
+        assert_eq!(eq.query.params, [SqlParam::Text("Jane Austen".to_owned())]);
+        assert_eq!(eq.query.columns, ["authors"]);
+        assert!(eq.residue.is_empty());
+        assert_eq!(
+            pattern.query_text,
+            r#"EXISTS (SELECT 1 FROM unnest("authors") AS "authors" WHERE "authors" ILIKE $1)"#
+        );
+        assert_eq!(pattern.columns, ["authors"]);
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_residue_propagated() {
+        let unknown = || TestRecord::year().eq(&TestUnknown);
+        let austen = || TestRecord::authors().any_element(|author| author.eq("Jane Austen"));
+        let not_null = Not(unknown());
+        let or_null = Or(unknown(), austen());
+        let xor_null = Xor(austen(), unknown());
+        let shelved =
+            || TestShelf::books().any_element(|book| book.then(&TestBook::year()).gt(&1815));
+        let not_shelved = Not(shelved());
+        let or_shelved = Or(True, shelved());
+
+        for single in [not_null.to_sql_single(), xor_null.to_sql_single()] {
+            assert_eq!(single.query.query_text, "");
+            assert_eq!(single.residue.len(), 1);
+        }
+        let or = or_null.to_sql_single();
+        assert_eq!(or.query.query_text, r#"(TRUE) OR ($1 = ANY("authors"))"#);
+        assert_eq!(or.residue.len(), 1);
+        assert!(not_null.to_sql_multi().is_none());
+        assert!(or_null.to_sql_multi().is_none());
+        assert!(xor_null.to_sql_multi().is_none());
+
+        assert_eq!(not_shelved.to_sql_single().query.query_text, "");
+        assert_eq!(not_shelved.to_sql_single().residue.len(), 1);
+        assert_eq!(or_shelved.to_sql_single().residue.len(), 1);
+        assert!(not_shelved.to_sql_multi().is_none());
+        assert!(or_shelved.to_sql_multi().is_none());
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_any_element_field() {
+        let query = TestShelf::books().any_element(|book| book.then(&TestBook::year()).gt(&1815));
+        let Single {
+            query: sql,
+            residue,
+        } = query.to_sql_single();
+
+        assert_eq!(sql.query_text, "");
+        assert!(sql.columns.is_empty());
+        assert_eq!(residue.len(), 1);
+        assert!(query.to_sql_multi().is_none());
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_between() {
+        let statement = TestBook::year().between(&1800, &1900).to_sql_single().query;
+
+        assert_eq!(statement.query_text, r#""year" BETWEEN $1 AND $2"#);
+        assert_eq!(
+            statement.params,
+            [SqlParam::BigInt(1800), SqlParam::BigInt(1900)]
+        );
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_variadic() {
+        let any = Any(vec![TestBook::year().eq(&1815), TestBook::year().eq(&1816)]);
+        let one = One(boxed_queries());
+
+        assert_eq!(
+            any.to_sql_single().query.query_text,
+            r#"("year" = $1) OR ("year" = $2)"#
+        );
+        assert_eq!(
+            one.to_sql_single().query.query_text,
+            r#"(((CAST(("title" = $1) AS INTEGER)) + (CAST(("year" > $2) AS INTEGER))) + (CAST(("year" < $3) AS INTEGER))) = 1"#
+        );
+        assert_eq!(
+            Query::<TestBook>::to_sql_single(&Any::<True>(Vec::new()))
+                .query
+                .query_text,
+            "FALSE"
+        );
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_strings() {
+        let starts_with = TestBook::title().starts_with("50%_").to_sql_single().query;
+        let eq = TestBook::title()
+            .eq_ignore_case(r"a\b")
+            .to_sql_single()
+            .query;
+
+        assert_eq!(starts_with.query_text, r#""title" LIKE $1"#);
+        assert_eq!(starts_with.params, [SqlParam::Text(r"50\%\_%".to_owned())]);
+        assert_eq!(eq.query_text, r#""title" ILIKE $1"#);
+        assert_eq!(eq.params, [SqlParam::Text(r"a\\b".to_owned())]);
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_in() {
+        let statement = TestBook::year().is_in(&[1814, 1815]).to_sql_single().query;
+        let empty = TestBook::year().is_in::<u16>(&[]).to_sql_single().query;
+
+        assert_eq!(statement.query_text, r#""year" IN ($1, $2)"#);
+        assert_eq!(
+            statement.params,
+            [SqlParam::BigInt(1814), SqlParam::BigInt(1815)]
+        );
+        assert_eq!(empty.query_text, "FALSE");
+    }
+
+    #[test]
+    fn shape_composition() {
+        let query = Limit(
+            Offset(
+                OrderBy(
+                    OrderBy(TestBook::year().gt(&1800), vec![TestBook::title().asc()]),
+                    vec![TestBook::year().desc()],
+                ),
+                10,
+            ),
+            5,
+        );
+        let shape = query.shape();
+        let names = shape.order.iter().map(SortKey::name).collect::<Vec<_>>();
+
+        assert_eq!(names, ["year", "title"]);
+        assert!(shape.order[0].is_descending());
+        assert_eq!((shape.limit, shape.offset), (Some(5), 10));
+
+        let windowed = Offset(Limit(TestBook::year().gt(&1800), 5), 2).shape();
+        assert_eq!((windowed.limit, windowed.offset), (Some(3), 2));
+
+        assert!(Query::<TestBook>::shape(&True).is_unshaped());
+    }
+
+    #[test]
+    fn shape_apply() {
+        let mut books = vec![
+            TestBook::new("Persuasion", 1817),
+            TestBook::new("Emma", 1815),
+            TestBook::new("Northanger Abbey", 1817),
+            TestBook::new("Mansfield Park", 1814),
+        ];
+        let shape = Offset(
+            Limit(
+                OrderBy(True, vec![TestBook::year().desc(), TestBook::title().asc()]),
+                3,
+            ),
+            1,
+        )
+        .shape();
+
+        let mut windowed = books.clone();
+        shape.apply(&mut windowed, true);
+        shape.apply(&mut books, false);
+
+        assert_eq!(windowed.len(), 4);
+        assert_eq!(windowed[0].title, "Northanger Abbey");
+        assert_eq!(
+            books,
+            [
+                TestBook::new("Persuasion", 1817),
+                TestBook::new("Emma", 1815),
+            ]
+        );
+    }
+
+    #[test]
+    fn shape_debug() {
+        let query = Limit(
+            OrderBy(
+                TestBook::year().gt(&1800),
+                vec![TestBook::year().desc(), TestBook::title().asc()],
+            ),
+            5,
+        );
+
+        assert_eq!(
+            format!("{query:#?}"),
+            "limit(order_by(year > 1800, year.desc(), title.asc()), 5)"
+        );
+    }
+
+    #[cfg(feature = "rest")]
+    #[test]
+    fn http_shape() {
+        let query = Limit(
+            Offset(
+                OrderBy(
+                    TestBook::title().eq("Emma"),
+                    vec![TestBook::year().desc(), TestBook::title().asc()],
+                ),
+                10,
+            ),
+            5,
+        );
+        let shape = query.shape();
+
+        let mut params = Vec::new();
+        assert!(!ShapeParams::new().translate(&shape, &FieldMap::new(), &mut params));
+        assert!(params.is_empty());
+
+        assert!(ShapeParams::standard().translate(&shape, &FieldMap::new(), &mut params));
+        assert_eq!(
+            params,
+            [
+                ("sort".into(), "-year,title".into()),
+                ("limit".into(), "5".into()),
+                ("offset".into(), "10".into()),
+            ]
+        );
+
+        let mut renamed = Vec::new();
+        let renaming = FieldMap::new().rename("year", "published");
+        assert!(ShapeParams::standard().translate(&shape, &renaming, &mut renamed));
+        assert_eq!(renamed[0], ("sort".into(), "-published,title".into()));
+
+        let mut unsupported = Vec::new();
+        let partial = FieldMap::new().unsupported("title");
+        assert!(!ShapeParams::standard().translate(&shape, &partial, &mut unsupported));
+        assert!(unsupported.is_empty());
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_shape() {
+        let query = Limit(
+            OrderBy(TestBook::year().gt(&1800), vec![TestBook::year().desc()]),
+            5,
+        );
+        let shape = query.shape();
+
+        let mut windowed = query.to_sql_single().query;
+        windowed.shape(&shape, true);
+        let mut unwindowed = query.to_sql_single().query;
+        unwindowed.shape(&shape, false);
+
+        assert_eq!(windowed.clauses(), r#" ORDER BY "year" DESC LIMIT 5"#);
+        assert_eq!(unwindowed.clauses(), r#" ORDER BY "year" DESC"#);
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_binds_values() {
+        let query = TestBook::title().eq("Robert'); DROP TABLE books; --");
+
+        let statement = Query::<TestBook>::to_sql_single(&query).query;
+
+        assert_eq!(statement.query_text, r#""title" = $1"#);
+        assert_eq!(
+            statement.params,
+            [SqlParam::Text("Robert'); DROP TABLE books; --".to_owned())]
+        );
+        assert_eq!(statement.columns, ["title"]);
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_renumbers_placeholders() {
+        let query = And(
+            TestBook::title().ne("Emma"),
+            Or(TestBook::year().gt(&1800), Not(TestBook::year().lt(&1900))),
+        );
+
+        let statement = query.to_sql_single().query;
+
+        assert_eq!(
+            statement.query_text,
+            r#"("title" != $1) AND (("year" > $2) OR (NOT ("year" < $3)))"#
+        );
+        assert_eq!(
+            statement.params,
+            [
+                SqlParam::Text("Emma".to_owned()),
+                SqlParam::BigInt(1800),
+                SqlParam::BigInt(1900),
+            ]
+        );
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_true_is_empty() {
+        let statement = Query::<TestBook>::to_sql_single(&True).query;
+        let combined = And(True, TestBook::year().eq(&1815)).to_sql_single().query;
+
+        assert!(statement.query_text.is_empty());
+        assert_eq!(combined.query_text, r#"(TRUE) AND ("year" = $1)"#);
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_quotes_columns() {
+        let mut statement = SqlStatement::new();
+        let column = statement.column(r#"a"$1"#);
+        let param = statement.param(SqlParam::Bool(true));
+        statement.query_text = format!("{column} = {param}");
+
+        let combined = SqlStatement::combine(statement.clone(), "OR", statement);
+
+        assert_eq!(combined.query_text, r#"("a""$1" = $1) OR ("a""$1" = $2)"#);
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql_check_columns() {
+        let query = And(TestBook::title().eq("Emma"), TestBook::year().eq(&1815));
+
+        let statement = query.to_sql_single().query;
+
+        statement.check_columns(&["title", "year"]).unwrap();
+        assert_eq!(
+            statement.check_columns(&["title"]),
+            Err(UnknownColumn("year".to_owned()))
+        );
     }
 }
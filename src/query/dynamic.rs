@@ -0,0 +1,660 @@
+//! Queries constructed at runtime.
+//!
+//! Queries are otherwise built out of generic types like `Eq<'a, Field<T, U>, V>`, which can not
+//! be sent over a network. A [`DynQuery`] instead refers to fields by name and holds values as
+//! JSON, and can be serialized and deserialized using [`serde`]. It is then resolved against a
+//! type implementing [`Queryable`] (usually through `#[derive(Queryable)]`) into a regular query.
+//!
+//! Queries are represented as externally tagged enums, for example:
+//!
+//! ```json
+//! {"all": [
+//!     {"eq": {"field": "title", "value": "Emma"}},
+//!     {"between": {"field": "year", "low": 1800, "high": 1900}},
+//!     {"not": {"starts_with": {"field": "author", "value": "j", "ignore_case": true}}}
+//! ]}
+//! ```
+
+#[cfg(feature = "postgres")]
+use super::ToSqlParam;
+use super::combinators::{All, Any, Not, One, True, Xor};
+use super::{Field, IntoOwned as _, Query};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Error as JsonError, Value, from_value};
+use std::fmt::{Debug, Display};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// A query constructed at runtime, referring to fields by name. See the
+/// [module-level documentation](self) for the serialized format.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum DynQuery {
+    /// See [`True`].
+    True,
+    /// See [`Eq`](super::combinators::Eq).
+    Eq {
+        /// The field name.
+        field: String,
+        /// The value to compare the field to.
+        value: Value,
+    },
+    /// See [`Ne`](super::combinators::Ne).
+    Ne {
+        /// The field name.
+        field: String,
+        /// The value to compare the field to.
+        value: Value,
+    },
+    /// See [`Gt`](super::combinators::Gt).
+    Gt {
+        /// The field name.
+        field: String,
+        /// The value to compare the field to.
+        value: Value,
+    },
+    /// See [`Lt`](super::combinators::Lt).
+    Lt {
+        /// The field name.
+        field: String,
+        /// The value to compare the field to.
+        value: Value,
+    },
+    /// See [`Ge`](super::combinators::Ge).
+    Ge {
+        /// The field name.
+        field: String,
+        /// The value to compare the field to.
+        value: Value,
+    },
+    /// See [`Le`](super::combinators::Le).
+    Le {
+        /// The field name.
+        field: String,
+        /// The value to compare the field to.
+        value: Value,
+    },
+    /// See [`Between`](super::combinators::Between).
+    Between {
+        /// The field name.
+        field: String,
+        /// The lower bound.
+        low: Value,
+        /// The upper bound.
+        high: Value,
+    },
+    /// See [`In`](super::combinators::In).
+    In {
+        /// The field name.
+        field: String,
+        /// The values to compare the field to.
+        values: Vec<Value>,
+    },
+    /// See [`StartsWith`](super::combinators::StartsWith).
+    StartsWith {
+        /// The field name.
+        field: String,
+        /// The prefix.
+        value: String,
+        /// Whether to ignore ASCII case.
+        #[serde(default)]
+        ignore_case: bool,
+    },
+    /// See [`Contains`](super::combinators::Contains).
+    Contains {
+        /// The field name.
+        field: String,
+        /// The substring.
+        value: String,
+        /// Whether to ignore ASCII case.
+        #[serde(default)]
+        ignore_case: bool,
+    },
+    /// See [`EndsWith`](super::combinators::EndsWith).
+    EndsWith {
+        /// The field name.
+        field: String,
+        /// The suffix.
+        value: String,
+        /// Whether to ignore ASCII case.
+        #[serde(default)]
+        ignore_case: bool,
+    },
+    /// See [`EqIgnoreCase`](super::combinators::EqIgnoreCase).
+    EqIgnoreCase {
+        /// The field name.
+        field: String,
+        /// The value to compare the field to.
+        value: String,
+    },
+    /// See [`Not`].
+    Not(Box<Self>),
+    /// See [`Xor`].
+    Xor(Box<Self>, Box<Self>),
+    /// See [`All`].
+    All(Vec<Self>),
+    /// See [`Any`].
+    Any(Vec<Self>),
+    /// See [`One`].
+    One(Vec<Self>),
+}
+
+impl DynQuery {
+    /// Resolve field names and values against `T`, producing a query that can be passed to
+    /// connectors.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a field does not exist or can not be queried dynamically, or if a value can not
+    /// be converted into the type of its field.
+    #[inline]
+    pub fn resolve<T: Queryable>(&self) -> Result<Box<dyn Query<T> + Send + Sync>, ResolveError> {
+        /// Resolve each query in `queries`.
+        ///
+        /// # Errors
+        ///
+        /// Fails with the first error encountered.
+        fn each<T: Queryable>(
+            queries: &[DynQuery],
+        ) -> Result<Vec<Box<dyn Query<T> + Send + Sync>>, ResolveError> {
+            queries.iter().map(DynQuery::resolve).collect()
+        }
+
+        Ok(match self {
+            Self::True => Box::new(True),
+            Self::Not(query) => Box::new(Not(query.resolve()?)),
+            Self::Xor(lhs, rhs) => Box::new(Xor(lhs.resolve()?, rhs.resolve()?)),
+            Self::All(queries) => Box::new(All(each(queries)?)),
+            Self::Any(queries) => Box::new(Any(each(queries)?)),
+            Self::One(queries) => Box::new(One(each(queries)?)),
+            Self::Eq { field, .. }
+            | Self::Ne { field, .. }
+            | Self::Gt { field, .. }
+            | Self::Lt { field, .. }
+            | Self::Ge { field, .. }
+            | Self::Le { field, .. }
+            | Self::Between { field, .. }
+            | Self::In { field, .. }
+            | Self::StartsWith { field, .. }
+            | Self::Contains { field, .. }
+            | Self::EndsWith { field, .. }
+            | Self::EqIgnoreCase { field, .. } => T::resolve_field(field, self)?,
+        })
+    }
+}
+
+/// Errors that may occur when resolving a [`DynQuery`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ResolveError {
+    /// The field does not exist.
+    #[error("The field `{0}` does not exist.")]
+    UnknownField(String),
+    /// The type of the field does not implement [`DynValue`].
+    #[error("The field `{0}` can not be queried dynamically.")]
+    UnsupportedField(String),
+    /// A value could not be converted into the type of the field.
+    #[error("The value {value} is incompatible with the field `{field}`: {source}")]
+    IncompatibleValue {
+        /// The field name.
+        field: String,
+        /// The value.
+        value: Value,
+        /// The source error.
+        #[source]
+        source: JsonError,
+    },
+    /// A string predicate was applied to a field that is not a string.
+    #[error("The field `{0}` is not a string.")]
+    NotText(String),
+}
+
+/// A type whose fields can be queried by name at runtime, implemented by `#[derive(Queryable)]`.
+pub trait Queryable: Sized + 'static {
+    /// Resolve `query` on the field named `field`. The field is only used for predicates on a
+    /// single field; other queries are resolved using [`DynQuery::resolve`].
+    ///
+    /// # Errors
+    ///
+    /// See [`DynQuery::resolve`].
+    fn resolve_field(
+        field: &str,
+        query: &DynQuery,
+    ) -> Result<Box<dyn Query<Self> + Send + Sync>, ResolveError>;
+
+    /// Resolve `query` on the field named `field` of a value reached through `parent`, as is done
+    /// for fields marked `#[queryable(nested)]`. The resolved field is `parent` followed by the
+    /// field using [`Field::then`].
+    ///
+    /// # Errors
+    ///
+    /// See [`DynQuery::resolve`].
+    fn resolve_nested<T: Queryable>(
+        parent: Field<T, Self>,
+        field: &str,
+        query: &DynQuery,
+    ) -> Result<Box<dyn Query<T> + Send + Sync>, ResolveError>;
+}
+
+/// A type of field values that can be compared to values in a [`DynQuery`].
+pub trait DynValue:
+    DeserializeOwned + Serialize + PartialOrd + Clone + Debug + Display + Send + Sync + 'static
+where
+    #[cfg(feature = "postgres")]
+    Self: ToSqlParam,
+{
+    /// The field as a string field, allowing string predicates. The default implementation
+    /// returns [`None`].
+    #[must_use]
+    #[inline]
+    fn text<T: 'static>(field: Field<T, Self>) -> Option<Field<T, str>> {
+        drop(field);
+        None
+    }
+}
+
+/// Implement [`DynValue`] for types which are not strings.
+macro_rules! impl_dyn_value {
+    ($($ty:ty),+) => {
+        $(
+            impl DynValue for $ty {}
+        )+
+    };
+}
+
+impl_dyn_value!(bool, i8, i16, i32, i64, u8, u16, u32, f32, f64);
+
+impl DynValue for String {
+    #[inline]
+    fn text<T: 'static>(field: Field<T, Self>) -> Option<Field<T, str>> {
+        let name = Arc::clone(&field.name);
+        Some(Field::new(name, move |data| (field.getter)(data).as_str()))
+    }
+}
+
+/// Resolves a predicate on a field whose type implements [`DynValue`]. Used by
+/// `#[derive(Queryable)]`; see [`ResolveSupported`] and [`ResolveUnsupported`].
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct FieldResolver<'q, T, U>(pub Field<T, U>, pub &'q DynQuery);
+
+/// Resolution of predicates on fields whose type implements [`DynValue`].
+#[doc(hidden)]
+pub trait ResolveSupported<T> {
+    /// Resolve the predicate.
+    ///
+    /// # Errors
+    ///
+    /// See [`DynQuery::resolve`].
+    fn resolve(&self) -> Result<Box<dyn Query<T> + Send + Sync>, ResolveError>;
+}
+
+/// Fallback for fields whose type does not implement [`DynValue`]. Since it is implemented on a
+/// reference, method resolution prefers [`ResolveSupported`] when applicable.
+#[doc(hidden)]
+pub trait ResolveUnsupported<T> {
+    /// Fail to resolve the predicate.
+    ///
+    /// # Errors
+    ///
+    /// Always fails with [`ResolveError::UnsupportedField`].
+    fn resolve(&self) -> Result<Box<dyn Query<T> + Send + Sync>, ResolveError>;
+}
+
+impl<T, U> ResolveUnsupported<T> for &FieldResolver<'_, T, U> {
+    #[inline]
+    fn resolve(&self) -> Result<Box<dyn Query<T> + Send + Sync>, ResolveError> {
+        let FieldResolver(field, _) = self;
+        Err(ResolveError::UnsupportedField(field.name.to_string()))
+    }
+}
+
+impl<T, U> ResolveSupported<T> for FieldResolver<'_, T, U>
+where
+    T: Queryable,
+    U: DynValue,
+{
+    #[inline]
+    fn resolve(&self) -> Result<Box<dyn Query<T> + Send + Sync>, ResolveError> {
+        let Self(field, query) = self;
+        let parse = |value: &Value| {
+            from_value(value.clone()).map_err(|source| ResolveError::IncompatibleValue {
+                field: field.name.to_string(),
+                value: value.clone(),
+                source,
+            })
+        };
+        let text =
+            || U::text(field.clone()).ok_or_else(|| ResolveError::NotText(field.name.to_string()));
+        let field = field.clone();
+
+        Ok(match query {
+            DynQuery::Eq { value, .. } => Box::new(field.eq(&parse(value)?).into_owned()),
+            DynQuery::Ne { value, .. } => Box::new(field.ne(&parse(value)?).into_owned()),
+            DynQuery::Gt { value, .. } => Box::new(field.gt(&parse(value)?).into_owned()),
+            DynQuery::Lt { value, .. } => Box::new(field.lt(&parse(value)?).into_owned()),
+            DynQuery::Ge { value, .. } => Box::new(field.ge(&parse(value)?).into_owned()),
+            DynQuery::Le { value, .. } => Box::new(field.le(&parse(value)?).into_owned()),
+            DynQuery::Between { low, high, .. } => {
+                Box::new(field.between(&parse(low)?, &parse(high)?).into_owned())
+            },
+            DynQuery::In { values, .. } => {
+                let values = values.iter().map(parse).collect::<Result<Vec<_>, _>>()?;
+                Box::new(field.is_in(&values).into_owned())
+            },
+            DynQuery::StartsWith {
+                value, ignore_case, ..
+            } => {
+                let pattern = text()?.starts_with(value).into_owned();
+                Box::new(if *ignore_case {
+                    pattern.ignore_case()
+                } else {
+                    pattern
+                })
+            },
+            DynQuery::Contains {
+                value, ignore_case, ..
+            } => {
+                let pattern = text()?.contains(value).into_owned();
+                Box::new(if *ignore_case {
+                    pattern.ignore_case()
+                } else {
+                    pattern
+                })
+            },
+            DynQuery::EndsWith {
+                value, ignore_case, ..
+            } => {
+                let pattern = text()?.ends_with(value).into_owned();
+                Box::new(if *ignore_case {
+                    pattern.ignore_case()
+                } else {
+                    pattern
+                })
+            },
+            DynQuery::EqIgnoreCase { value, .. } => {
+                Box::new(text()?.eq_ignore_case(value).into_owned())
+            },
+            other @ (DynQuery::True
+            | DynQuery::Not(_)
+            | DynQuery::Xor(..)
+            | DynQuery::All(_)
+            | DynQuery::Any(_)
+            | DynQuery::One(_)) => other.resolve()?,
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    clippy::unwrap_used,
+    reason = "Panics simply indicate failed tests."
+)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "rest")]
+    use crate::query::{HttpConvention, combinators::And, http::RangeParams};
+    use crate::query::{Queryable, combinators::Or, optimize};
+    use serde_json::{from_str, json, to_value};
+
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestBook {
+        title: String,
+        year: u16,
+        tags: Vec<String>,
+    }
+
+    impl TestBook {
+        fn new(title: &str, year: u16) -> Self {
+            Self {
+                title: title.to_owned(),
+                year,
+                tags: Vec::new(),
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestPublisher {
+        name: String,
+        #[queryable(rename = "founded_in")]
+        founded: u16,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestEdition<M> {
+        #[queryable(rename = "name")]
+        title: String,
+        #[queryable(nested)]
+        publisher: TestPublisher,
+        #[queryable(skip)]
+        metadata: M,
+    }
+
+    #[test]
+    fn deserialize() {
+        let query: DynQuery = from_str(
+            r#"{"all": [
+                {"eq": {"field": "title", "value": "Emma"}},
+                {"not": {"starts_with": {"field": "title", "value": "p", "ignore_case": true}}},
+                {"between": {"field": "year", "low": 1800, "high": 1900}},
+                "true"
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            query,
+            DynQuery::All(vec![
+                DynQuery::Eq {
+                    field: "title".to_owned(),
+                    value: json!("Emma"),
+                },
+                DynQuery::Not(Box::new(DynQuery::StartsWith {
+                    field: "title".to_owned(),
+                    value: "p".to_owned(),
+                    ignore_case: true,
+                })),
+                DynQuery::Between {
+                    field: "year".to_owned(),
+                    low: json!(1800),
+                    high: json!(1900),
+                },
+                DynQuery::True,
+            ])
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let query = DynQuery::Xor(
+            Box::new(DynQuery::In {
+                field: "year".to_owned(),
+                values: vec![json!(1811), json!(1815)],
+            }),
+            Box::new(DynQuery::One(vec![DynQuery::EqIgnoreCase {
+                field: "title".to_owned(),
+                value: "emma".to_owned(),
+            }])),
+        );
+
+        let serialized = to_value(&query).unwrap();
+
+        assert_eq!(from_value::<DynQuery>(serialized).unwrap(), query);
+    }
+
+    #[test]
+    fn resolve() {
+        let books = [
+            TestBook::new("Emma", 1815),
+            TestBook::new("Evelina", 1778),
+            TestBook::new("Persuasion", 1817),
+        ];
+        let query = DynQuery::Any(vec![
+            DynQuery::Lt {
+                field: "year".to_owned(),
+                value: json!(1800),
+            },
+            DynQuery::Contains {
+                field: "title".to_owned(),
+                value: "SUAS".to_owned(),
+                ignore_case: true,
+            },
+        ]);
+        let expected = Or(
+            TestBook::year().lt(&1800),
+            TestBook::title().contains("SUAS").ignore_case(),
+        );
+
+        let resolved = query.resolve::<TestBook>().unwrap();
+
+        assert_eq!(
+            format!("{resolved:#?}"),
+            r#"any(year < 1800, title.contains("SUAS").ignore_case())"#
+        );
+        for book in &books {
+            assert_eq!(resolved.evaluate(book), expected.evaluate(book));
+        }
+    }
+
+    #[test]
+    fn resolve_errors() {
+        let resolve = |query: Value| from_value::<DynQuery>(query).unwrap().resolve::<TestBook>();
+
+        assert!(matches!(
+            resolve(json!({"eq": {"field": "isbn", "value": "0"}})),
+            Err(ResolveError::UnknownField(field)) if field == "isbn"
+        ));
+        assert!(matches!(
+            resolve(json!({"gt": {"field": "year", "value": "1800"}})),
+            Err(ResolveError::IncompatibleValue { field, .. }) if field == "year"
+        ));
+        assert!(matches!(
+            resolve(json!({"lt": {"field": "year", "value": 100_000}})),
+            Err(ResolveError::IncompatibleValue { .. })
+        ));
+        assert!(matches!(
+            resolve(json!({"ends_with": {"field": "year", "value": "5"}})),
+            Err(ResolveError::NotText(field)) if field == "year"
+        ));
+        assert!(matches!(
+            resolve(json!({"not": {"eq": {"field": "tags", "value": []}}})),
+            Err(ResolveError::UnsupportedField(field)) if field == "tags"
+        ));
+    }
+
+    #[test]
+    fn derive_attributes() {
+        let resolve = |query: Value| {
+            from_value::<DynQuery>(query)
+                .unwrap()
+                .resolve::<TestEdition<()>>()
+        };
+        let edition = TestEdition {
+            title: "Evelina".to_owned(),
+            publisher: TestPublisher {
+                name: "Thomas Lowndes".to_owned(),
+                founded: 1756,
+            },
+            metadata: (),
+        };
+
+        let renamed = resolve(json!({"eq": {"field": "name", "value": "Evelina"}})).unwrap();
+        let nested =
+            resolve(json!({"lt": {"field": "publisher.founded_in", "value": 1800}})).unwrap();
+
+        assert!(renamed.evaluate(&edition));
+        assert!(nested.evaluate(&edition));
+        assert_eq!(format!("{nested:#?}"), "publisher.founded_in < 1800");
+        assert_eq!(
+            format!("{:#?}", TestEdition::<()>::title().eq("Evelina")),
+            r#"name = "Evelina""#
+        );
+        for field in ["title", "metadata", "publisher.founded", "publisher.isbn"] {
+            assert!(matches!(
+                resolve(json!({"eq": {"field": field, "value": 0}})),
+                Err(ResolveError::UnknownField(unknown)) if unknown == field
+            ));
+        }
+    }
+
+    #[test]
+    fn optimize_resolved() {
+        let query = DynQuery::Not(Box::new(DynQuery::All(vec![
+            DynQuery::Eq {
+                field: "title".to_owned(),
+                value: json!("Emma"),
+            },
+            DynQuery::Between {
+                field: "year".to_owned(),
+                low: json!(1800),
+                high: json!(1900),
+            },
+        ])));
+
+        let resolved = query.resolve::<TestBook>().unwrap();
+
+        assert_eq!(
+            format!("{:#?}", optimize(&*resolved)),
+            r#"any(title != "Emma", year < 1800, year > 1900)"#
+        );
+    }
+
+    #[cfg(feature = "rest")]
+    #[test]
+    fn http() {
+        let convention = HttpConvention::new().range(RangeParams::suffixed());
+        let query = DynQuery::All(vec![
+            DynQuery::Eq {
+                field: "title".to_owned(),
+                value: json!("Emma"),
+            },
+            DynQuery::Ge {
+                field: "year".to_owned(),
+                value: json!(1800),
+            },
+            DynQuery::Ne {
+                field: "year".to_owned(),
+                value: json!(1811),
+            },
+        ]);
+        let expected = And(TestBook::title().eq("Emma"), TestBook::year().ge(&1800));
+
+        let resolved = query.resolve::<TestBook>().unwrap();
+        let single = resolved.to_http_single(&convention);
+
+        assert_eq!(single.query, expected.to_http_single(&convention).query);
+        assert_eq!(single.residue.len(), 1);
+        assert_eq!(format!("{:#?}", single.residue[0]), "year != 1811");
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn sql() {
+        let query = DynQuery::Any(vec![
+            DynQuery::In {
+                field: "year".to_owned(),
+                values: vec![json!(1811), json!(1815)],
+            },
+            DynQuery::StartsWith {
+                field: "title".to_owned(),
+                value: "E".to_owned(),
+                ignore_case: false,
+            },
+        ]);
+        let years = [1811, 1815];
+        let expected = Or(
+            TestBook::year().is_in(&years),
+            TestBook::title().starts_with("E"),
+        );
+
+        let resolved = query.resolve::<TestBook>().unwrap();
+
+        assert_eq!(
+            format!("{:?}", resolved.to_sql_single().query),
+            format!("{:?}", expected.to_sql_single().query)
+        );
+    }
+}
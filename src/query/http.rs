@@ -0,0 +1,559 @@
+//! Conventions for expressing queries as HTTP parameters.
+//!
+//! There is no standard for how query parameters express anything beyond equality, so each source
+//! may need its own [`HttpConvention`]. Anything a convention cannot express remains as residue.
+//! Sources also tend to name fields and spell values differently, which a [`FieldMap`] accounts
+//! for. Sources accepting a filter expression, rather than one parameter per comparison, are
+//! supported by the [`dialect`]s.
+
+use super::{HttpQuery, Shape};
+use serde::Serialize;
+use serde_json::{Value, to_value};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+pub mod dialect;
+
+/// Describes how a source expects queries to be expressed as HTTP parameters.
+///
+/// The default convention only expresses equality, as `field=value`, which is assumed to be
+/// understood by most sources. Everything else remains as residue.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[expect(clippy::module_name_repetitions, reason = "Established terminology.")]
+pub struct HttpConvention {
+    /// Parameters used for range comparisons.
+    pub range: RangeParams,
+    /// Parameters used for string predicates.
+    pub string: StringParams,
+    /// Parameters used for set membership.
+    pub list: ListParams,
+    /// Parameters used to order and limit results.
+    pub shape: ShapeParams,
+    /// How fields and their values are named by the source.
+    pub fields: FieldMap,
+    /// How values are written as parameters.
+    pub values: ValueFormat,
+}
+
+impl Default for HttpConvention {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpConvention {
+    /// Constructs the default convention.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            range: RangeParams::new(),
+            string: StringParams::new(),
+            list: ListParams::Unsupported,
+            shape: ShapeParams::new(),
+            fields: FieldMap::new(),
+            values: ValueFormat::Serde,
+        }
+    }
+
+    /// Sets the parameters used for range comparisons.
+    #[must_use]
+    #[inline]
+    pub fn range(self, range: RangeParams) -> Self {
+        Self { range, ..self }
+    }
+
+    /// Sets the parameters used for string predicates.
+    #[must_use]
+    #[inline]
+    pub fn string(self, string: StringParams) -> Self {
+        Self { string, ..self }
+    }
+
+    /// Sets the parameters used for set membership.
+    #[must_use]
+    #[inline]
+    pub fn list(self, list: ListParams) -> Self {
+        Self { list, ..self }
+    }
+
+    /// Sets the parameters used to order and limit results.
+    #[must_use]
+    #[inline]
+    pub fn shape(self, shape: ShapeParams) -> Self {
+        Self { shape, ..self }
+    }
+
+    /// Sets how fields and their values are named by the source.
+    #[must_use]
+    #[inline]
+    pub fn fields(self, fields: FieldMap) -> Self {
+        Self { fields, ..self }
+    }
+
+    /// Sets how values are written as parameters.
+    #[must_use]
+    #[inline]
+    pub fn values(self, values: ValueFormat) -> Self {
+        Self { values, ..self }
+    }
+}
+
+/// How values are written as parameters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ValueFormat {
+    /// Use the [`Serialize`] implementation, such that values are spelled the way the source
+    /// deserializes them. Strings and unit enum variants are written as they are, numbers and
+    /// booleans as literals, and anything else as JSON. A value which fails to serialize, or is
+    /// null such as [`None`], remains as residue.
+    #[default]
+    Serde,
+    /// Use the [`Display`] implementation, which may differ from the spelling used by the source.
+    Display,
+}
+
+impl ValueFormat {
+    /// Write `value` as a parameter, or [`None`] if it can not be.
+    #[inline]
+    pub fn write<V>(self, value: &V) -> Option<String>
+    where
+        V: Display + Serialize + ?Sized,
+    {
+        match self {
+            Self::Serde => Some(match to_value(value).ok()? {
+                Value::String(string) => string,
+                Value::Null => return None,
+                other
+                @ (Value::Bool(_) | Value::Number(_) | Value::Array(_) | Value::Object(_)) => {
+                    other.to_string()
+                },
+            }),
+            Self::Display => Some(value.to_string()),
+        }
+    }
+}
+
+/// Maps the fields of queries to the parameters of a source, and their values to its spelling.
+///
+/// By default, fields are passed through unchanged. A field the source does not support remains as
+/// residue, rather than being sent as an unknown parameter that the source may ignore or reject.
+/// Conventions like [`RangeParams::suffixed`] apply to the mapped name, so that renaming `author`
+/// to `creator` results in parameters like `creator_gt`.
+///
+/// ```
+/// # use broker::query::http::FieldMap;
+/// let fields = FieldMap::new()
+///     .rename("author", "creator")
+///     .map_value("format", "Hardcover", "HARDBACK")
+///     .unsupported("rating");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FieldMap {
+    /// The mapped fields, where [`None`] marks a field the source does not support.
+    pub fields: BTreeMap<Box<str>, Option<FieldParam>>,
+    /// Whether fields without an entry in [`fields`](Self::fields) are unsupported, rather than
+    /// passed through unchanged.
+    pub exhaustive: bool,
+}
+
+impl FieldMap {
+    /// Constructs a map passing all fields through unchanged.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            fields: BTreeMap::new(),
+            exhaustive: false,
+        }
+    }
+
+    /// Only support fields with a mapping, that is, fields that were renamed or have mapped values.
+    #[must_use]
+    #[inline]
+    pub fn exhaustive(self) -> Self {
+        Self {
+            exhaustive: true,
+            ..self
+        }
+    }
+
+    /// Name the parameter for `field` as `param` instead.
+    #[must_use]
+    #[inline]
+    pub fn rename(mut self, field: impl Into<Box<str>>, param: impl Into<Box<str>>) -> Self {
+        let param = param.into();
+        self.entry(field.into()).name = param;
+        self
+    }
+
+    /// Send `replacement` instead of `value` in parameters for `field`.
+    #[must_use]
+    #[inline]
+    pub fn map_value(
+        mut self,
+        field: impl Into<Box<str>>,
+        value: impl Into<Box<str>>,
+        replacement: impl Into<Box<str>>,
+    ) -> Self {
+        *self
+            .entry(field.into())
+            .values
+            .entry(value.into())
+            .or_default() = replacement.into();
+        self
+    }
+
+    /// Mark `field` as not supported by the source, such that predicates on it remain as residue.
+    #[must_use]
+    #[inline]
+    pub fn unsupported(mut self, field: impl Into<Box<str>>) -> Self {
+        *self.fields.entry(field.into()).or_default() = None;
+        self
+    }
+
+    /// The mapping of `field`, created if it is missing or unsupported.
+    fn entry(&mut self, field: Box<str>) -> &mut FieldParam {
+        let entry = self.fields.entry(field);
+        let name = entry.key().clone();
+        entry
+            .or_default()
+            .get_or_insert_with(|| FieldParam::new(name))
+    }
+
+    /// The parameter for `field` according to `param`, or [`None`] if the source does not support
+    /// the field.
+    #[must_use]
+    #[inline]
+    pub fn param<'a>(&self, field: &'a str, param: &ParamName) -> Option<Cow<'a, str>> {
+        match self.fields.get(field) {
+            Some(Some(mapped)) => Some(Cow::Owned(param.apply(&mapped.name).into_owned())),
+            Some(None) => None,
+            None if self.exhaustive => None,
+            None => Some(param.apply(field)),
+        }
+    }
+
+    /// The spelling of `value` for `field` used by the source.
+    #[must_use]
+    #[inline]
+    pub fn value(&self, field: &str, value: String) -> Box<str> {
+        self.fields
+            .get(field)
+            .and_then(|mapped| mapped.as_ref()?.values.get(&*value))
+            .map_or_else(|| value.into(), Clone::clone)
+    }
+}
+
+/// How the source names a field and spells its values.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FieldParam {
+    /// The name of the parameter, to which conventions like [`RangeParams::suffixed`] are applied.
+    pub name: Box<str>,
+    /// Values replaced before being sent, such as enum variants which the source spells
+    /// differently.
+    pub values: BTreeMap<Box<str>, Box<str>>,
+}
+
+impl FieldParam {
+    /// A parameter named `name`, with values sent as they are.
+    #[inline]
+    pub fn new(name: impl Into<Box<str>>) -> Self {
+        Self {
+            name: name.into(),
+            values: BTreeMap::new(),
+        }
+    }
+}
+
+/// The name of a parameter derived from a field name, by surrounding it with a prefix and a
+/// suffix.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ParamName {
+    /// Placed before the field name.
+    pub prefix: Box<str>,
+    /// Placed after the field name.
+    pub suffix: Box<str>,
+}
+
+impl ParamName {
+    /// A parameter name consisting of the field name followed by `suffix`.
+    #[inline]
+    pub fn suffix(suffix: impl Into<Box<str>>) -> Self {
+        Self {
+            prefix: Box::default(),
+            suffix: suffix.into(),
+        }
+    }
+
+    /// Apply to a field name.
+    #[must_use]
+    #[inline]
+    pub fn apply<'a>(&self, field: &'a str) -> Cow<'a, str> {
+        if self.prefix.is_empty() && self.suffix.is_empty() {
+            Cow::Borrowed(field)
+        } else {
+            Cow::Owned(format!("{}{field}{}", self.prefix, self.suffix))
+        }
+    }
+}
+
+/// Parameters used to express range comparisons. A comparison with no corresponding parameter
+/// remains as residue.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RangeParams {
+    /// Greater than.
+    pub gt: Option<ParamName>,
+    /// Lesser than.
+    pub lt: Option<ParamName>,
+    /// Greater than or equal to.
+    pub ge: Option<ParamName>,
+    /// Lesser than or equal to.
+    pub le: Option<ParamName>,
+}
+
+impl Default for RangeParams {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RangeParams {
+    /// Constructs parameters supporting no range comparisons.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            gt: None,
+            lt: None,
+            ge: None,
+            le: None,
+        }
+    }
+
+    /// Parameters of the form `field_gt`, `field_lt`, `field_gte` and `field_lte`.
+    #[must_use]
+    #[inline]
+    pub fn suffixed() -> Self {
+        Self {
+            gt: Some(ParamName::suffix("_gt")),
+            lt: Some(ParamName::suffix("_lt")),
+            ge: Some(ParamName::suffix("_gte")),
+            le: Some(ParamName::suffix("_lte")),
+        }
+    }
+
+    /// Parameters of the form `field[gt]`, `field[lt]`, `field[gte]` and `field[lte]`.
+    #[must_use]
+    #[inline]
+    pub fn bracketed() -> Self {
+        Self {
+            gt: Some(ParamName::suffix("[gt]")),
+            lt: Some(ParamName::suffix("[lt]")),
+            ge: Some(ParamName::suffix("[gte]")),
+            le: Some(ParamName::suffix("[lte]")),
+        }
+    }
+}
+
+/// Parameters used to express string predicates. A predicate with no corresponding parameter
+/// remains as residue.
+///
+/// Since sources differ in whether they match patterns case-sensitively,
+/// [`ignore_case`](Self::ignore_case) describes how the source treats the pattern parameters. A
+/// case-sensitive predicate sent to a case-insensitive source selects a superset of the desired
+/// elements, so the predicate also remains as residue. A case-insensitive predicate can not be sent
+/// to a case-sensitive source at all.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StringParams {
+    /// Starts with a pattern.
+    pub starts_with: Option<ParamName>,
+    /// Contains a pattern.
+    pub contains: Option<ParamName>,
+    /// Ends with a pattern.
+    pub ends_with: Option<ParamName>,
+    /// Whether the source matches [`starts_with`](Self::starts_with),
+    /// [`contains`](Self::contains) and [`ends_with`](Self::ends_with) case-insensitively.
+    pub ignore_case: bool,
+    /// Equal to a value, ignoring case.
+    pub eq_ignore_case: Option<ParamName>,
+}
+
+impl Default for StringParams {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StringParams {
+    /// Constructs parameters supporting no string predicates.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            starts_with: None,
+            contains: None,
+            ends_with: None,
+            ignore_case: false,
+            eq_ignore_case: None,
+        }
+    }
+}
+
+/// Parameters used to express set membership, i.e. that a field is equal to any of several values.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ListParams {
+    /// Set membership can not be expressed in one request, so it remains as residue.
+    #[default]
+    Unsupported,
+    /// One parameter per value, such as `field=a&field=b`.
+    Repeated(ParamName),
+    /// One parameter with all values joined by a separator, such as `field=a,b`.
+    ///
+    /// If any value contains the separator, set membership remains as residue.
+    Joined {
+        /// The name of the parameter.
+        name: ParamName,
+        /// Placed between values.
+        separator: Box<str>,
+    },
+}
+
+impl ListParams {
+    /// Parameters of the form `field=a&field=b`.
+    #[must_use]
+    #[inline]
+    pub fn repeated() -> Self {
+        Self::Repeated(ParamName::default())
+    }
+
+    /// Parameters of the form `field=a,b`.
+    #[must_use]
+    #[inline]
+    pub fn comma_joined() -> Self {
+        Self::Joined {
+            name: ParamName::default(),
+            separator: ",".into(),
+        }
+    }
+}
+
+/// Parameters used to order and limit results, as specified by a [`Shape`].
+///
+/// The shape is only sent to the source if the query is translated without residue, and if the
+/// convention can express all of it. Otherwise, the connector applies it to the fetched results.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ShapeParams {
+    /// The parameter used to order results.
+    pub sort: Option<SortParams>,
+    /// The name of the parameter specifying the maximum number of results.
+    pub limit: Option<Box<str>>,
+    /// The name of the parameter specifying the number of results to skip.
+    pub offset: Option<Box<str>>,
+}
+
+impl ShapeParams {
+    /// Constructs parameters supporting no ordering or limits.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            sort: None,
+            limit: None,
+            offset: None,
+        }
+    }
+
+    /// Append parameters expressing `shape` to `query`, if the convention can express all of it.
+    /// Returns whether it did, in which case the source is expected to apply the limit and
+    /// offset. Sort keys are named according to `fields`, and the shape can not be expressed if
+    /// the source does not support any of them.
+    #[inline]
+    pub fn translate<T>(
+        &self,
+        shape: &Shape<T>,
+        fields: &FieldMap,
+        query: &mut HttpQuery<'_>,
+    ) -> bool {
+        let sort = match &self.sort {
+            _ if shape.order.is_empty() => None,
+            Some(sort) => Some(sort),
+            None => return false,
+        };
+        if (shape.limit.is_some() && self.limit.is_none())
+            || (shape.offset > 0 && self.offset.is_none())
+        {
+            return false;
+        }
+
+        if let Some(sort) = sort {
+            let Some(keys) = shape
+                .order
+                .iter()
+                .map(|key| {
+                    let name = fields.param(key.name(), &ParamName::default())?;
+                    Some(if key.is_descending() {
+                        format!("{}{name}", sort.descending_prefix)
+                    } else {
+                        name.into_owned()
+                    })
+                })
+                .collect::<Option<Vec<_>>>()
+            else {
+                return false;
+            };
+            query.push((
+                sort.name.to_string().into(),
+                keys.join(&sort.separator).into(),
+            ));
+        }
+        if let (Some(limit), Some(name)) = (shape.limit, &self.limit) {
+            query.push((name.to_string().into(), limit.to_string().into()));
+        }
+        if let (offset @ 1.., Some(name)) = (shape.offset, &self.offset) {
+            query.push((name.to_string().into(), offset.to_string().into()));
+        }
+
+        true
+    }
+
+    /// Parameters of the form `sort=-year,title&limit=10&offset=20`.
+    #[must_use]
+    #[inline]
+    pub fn standard() -> Self {
+        Self {
+            sort: Some(SortParams::prefixed("sort")),
+            limit: Some("limit".into()),
+            offset: Some("offset".into()),
+        }
+    }
+}
+
+/// A parameter listing the keys to order results by.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SortParams {
+    /// The name of the parameter.
+    pub name: Box<str>,
+    /// Placed between keys.
+    pub separator: Box<str>,
+    /// Placed before the field name of descending keys.
+    pub descending_prefix: Box<str>,
+}
+
+impl SortParams {
+    /// A parameter of the form `name=-year,title`.
+    #[inline]
+    pub fn prefixed(name: impl Into<Box<str>>) -> Self {
+        Self {
+            name: name.into(),
+            separator: ",".into(),
+            descending_prefix: "-".into(),
+        }
+    }
+}
@@ -0,0 +1,540 @@
+//! Dialects for expressing whole queries as HTTP parameters.
+//!
+//! [`Params`] is the default dialect. It sends one parameter per comparison, following the
+//! parameter conventions of [`HttpConvention`]. This covers the flat (`year_gt=2000`) and bracketed
+//! (`year[gt]=2000`) styles through [`RangeParams`](super::RangeParams), but can not express
+//! disjunctions or negations. Sources accepting a filter expression can use [`OData`] or [`Rsql`]
+//! instead, which also translate `Or`, `Not` and `Ne`.
+//!
+//! All dialects name fields using the [`FieldMap`](super::FieldMap) of the convention and write
+//! values using its [`ValueFormat`](super::ValueFormat). Ordering and limits are still expressed
+//! using [`ShapeParams`](super::ShapeParams). Parts of a query which a dialect can not express
+//! remain as residue.
+
+use super::{HttpConvention, HttpQuery, ParamName};
+use crate::query::{Node, Operand, Operator, Primitive, Query, Single, optimize::Predicate};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::fmt::Debug;
+
+/// Translates queries into the parameters understood by a source.
+///
+/// Used by [`rest`](crate::rest) connectors, and selected using
+/// [`Builder::dialect`](crate::rest::Builder::dialect).
+#[expect(clippy::module_name_repetitions, reason = "Established terminology.")]
+pub trait HttpDialect<T>: Debug + Send + Sync {
+    /// Translate `query`, naming fields and writing values as specified by `convention`. See
+    /// [`Single`] documentation for caveats.
+    fn translate<'a>(
+        &self,
+        query: &'a (dyn Query<T> + Sync),
+        convention: &HttpConvention,
+    ) -> Single<'a, HttpQuery<'a>, T>;
+}
+
+/// One parameter per comparison, as translated by [`Query::to_http_single`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Params;
+
+impl<T> HttpDialect<T> for Params {
+    #[inline]
+    fn translate<'a>(
+        &self,
+        query: &'a (dyn Query<T> + Sync),
+        convention: &HttpConvention,
+    ) -> Single<'a, HttpQuery<'a>, T> {
+        query.to_http_single(convention)
+    }
+}
+
+/// An [OData] `$filter` expression, such as `$filter=year gt 2000 and author eq 'Orwell'`.
+///
+/// Case-insensitive predicates are expressed using `tolower`, and set membership as a disjunction
+/// of equalities.
+///
+/// [OData]: https://www.odata.org/
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OData;
+
+impl<T> HttpDialect<T> for OData {
+    #[inline]
+    fn translate<'a>(
+        &self,
+        query: &'a (dyn Query<T> + Sync),
+        convention: &HttpConvention,
+    ) -> Single<'a, HttpQuery<'a>, T> {
+        filter(self, Cow::Borrowed("$filter"), query, convention)
+    }
+}
+
+/// An [RSQL] expression, such as `filter=year=gt=2000;author==Orwell`.
+///
+/// RSQL has no negation, so negated comparisons are rewritten as their opposites where possible.
+/// Patterns are expressed using `*` wildcards, which makes values containing `*` impossible to
+/// compare for equality. Case-insensitive predicates and null checks are not standardized, and
+/// remain as residue.
+///
+/// [RSQL]: https://github.com/jirutka/rsql-parser
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rsql {
+    /// The name of the parameter containing the expression.
+    pub param: Box<str>,
+}
+
+impl Rsql {
+    /// Send the expression in the parameter named `param`.
+    #[inline]
+    pub fn new(param: impl Into<Box<str>>) -> Self {
+        Self {
+            param: param.into(),
+        }
+    }
+}
+
+impl Default for Rsql {
+    /// Send the expression in the parameter `filter`.
+    #[inline]
+    fn default() -> Self {
+        Self::new("filter")
+    }
+}
+
+impl<T> HttpDialect<T> for Rsql {
+    #[inline]
+    fn translate<'a>(
+        &self,
+        query: &'a (dyn Query<T> + Sync),
+        convention: &HttpConvention,
+    ) -> Single<'a, HttpQuery<'a>, T> {
+        filter(
+            self,
+            Cow::Owned(self.param.clone().into()),
+            query,
+            convention,
+        )
+    }
+}
+
+/// The syntax of a filter expression.
+trait Grammar {
+    /// Placed between the terms of a conjunction.
+    const AND: &'static str;
+    /// Placed between the terms of a disjunction.
+    const OR: &'static str;
+
+    /// Write a constant, or [`None`] if it can not be.
+    fn constant(&self, value: bool) -> Option<String>;
+
+    /// Write the negation of `operand`, or [`None`] if it can not be.
+    fn not(&self, operand: &str) -> Option<String>;
+
+    /// Write a comparison of the mapped `field`, or [`None`] if it can not be.
+    fn primitive(
+        &self,
+        field: &str,
+        primitive: &Primitive<'_>,
+        convention: &HttpConvention,
+    ) -> Option<String>;
+}
+
+impl Grammar for OData {
+    const AND: &'static str = " and ";
+    const OR: &'static str = " or ";
+
+    #[inline]
+    fn constant(&self, value: bool) -> Option<String> {
+        Some(value.to_string())
+    }
+
+    #[inline]
+    fn not(&self, operand: &str) -> Option<String> {
+        Some(format!("not ({operand})"))
+    }
+
+    #[inline]
+    fn primitive(
+        &self,
+        field: &str,
+        primitive: &Primitive<'_>,
+        convention: &HttpConvention,
+    ) -> Option<String> {
+        let literal = |operand: &dyn Operand| -> Option<String> {
+            let value = convention
+                .fields
+                .value(primitive.field, convention.values.write(operand)?);
+            Some(match operand.to_json() {
+                Ok(Value::Null) => "null".to_owned(),
+                Ok(Value::String(_)) => format!("'{}'", value.replace('\'', "''")),
+                Ok(Value::Bool(_) | Value::Number(_) | Value::Array(_) | Value::Object(_))
+                | Err(_) => value.into(),
+            })
+        };
+        let operands = primitive
+            .operands
+            .iter()
+            .map(|operand| literal(&**operand))
+            .collect::<Option<Vec<_>>>()?;
+        let function = |name, ignore_case| {
+            Some(if ignore_case {
+                format!("{name}(tolower({field}),tolower({}))", operands.first()?)
+            } else {
+                format!("{name}({field},{})", operands.first()?)
+            })
+        };
+
+        match primitive.operator {
+            Operator::Eq => Some(format!("{field} eq {}", operands.first()?)),
+            Operator::Ne => Some(format!("{field} ne {}", operands.first()?)),
+            Operator::Gt => Some(format!("{field} gt {}", operands.first()?)),
+            Operator::Lt => Some(format!("{field} lt {}", operands.first()?)),
+            Operator::Ge => Some(format!("{field} ge {}", operands.first()?)),
+            Operator::Le => Some(format!("{field} le {}", operands.first()?)),
+            Operator::Between => Some(format!(
+                "{field} ge {} and {field} le {}",
+                operands.first()?,
+                operands.get(1)?
+            )),
+            Operator::In if operands.is_empty() => self.constant(false),
+            Operator::In => {
+                let terms = operands.iter().map(|value| format!("{field} eq {value}"));
+                Some(format!("({})", terms.collect::<Vec<_>>().join(Self::OR)))
+            },
+            Operator::StartsWith { ignore_case } => function("startswith", ignore_case),
+            Operator::Contains { ignore_case } => function("contains", ignore_case),
+            Operator::EndsWith { ignore_case } => function("endswith", ignore_case),
+            Operator::EqIgnoreCase => Some(format!(
+                "tolower({field}) eq tolower({})",
+                operands.first()?
+            )),
+            Operator::IsNone => Some(format!("{field} eq null")),
+            Operator::IsSome => Some(format!("{field} ne null")),
+        }
+    }
+}
+
+impl Grammar for Rsql {
+    const AND: &'static str = ";";
+    const OR: &'static str = ",";
+
+    #[inline]
+    fn constant(&self, _value: bool) -> Option<String> {
+        None
+    }
+
+    #[inline]
+    fn not(&self, _operand: &str) -> Option<String> {
+        None
+    }
+
+    #[inline]
+    fn primitive(
+        &self,
+        field: &str,
+        primitive: &Primitive<'_>,
+        convention: &HttpConvention,
+    ) -> Option<String> {
+        let values = primitive
+            .operands
+            .iter()
+            .map(|operand| {
+                if matches!(operand.to_json(), Ok(Value::Null)) {
+                    return None;
+                }
+                let value = convention
+                    .fields
+                    .value(primitive.field, convention.values.write(&**operand)?);
+                // Unquoted and quoted values alike are matched as patterns by most servers.
+                (!value.contains('*')).then_some(value)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let operand = || values.first().map(|value| rsql_quote(value));
+        let pattern = |format: fn(&str) -> String| Some(rsql_quote(&format(values.first()?)));
+
+        match primitive.operator {
+            Operator::Eq => Some(format!("{field}=={}", operand()?)),
+            Operator::Ne => Some(format!("{field}!={}", operand()?)),
+            Operator::Gt => Some(format!("{field}=gt={}", operand()?)),
+            Operator::Lt => Some(format!("{field}=lt={}", operand()?)),
+            Operator::Ge => Some(format!("{field}=ge={}", operand()?)),
+            Operator::Le => Some(format!("{field}=le={}", operand()?)),
+            Operator::Between => Some(format!(
+                "{field}=ge={};{field}=le={}",
+                operand()?,
+                rsql_quote(values.get(1)?)
+            )),
+            Operator::In if values.is_empty() => None,
+            Operator::In => {
+                let values = values.iter().map(|value| rsql_quote(value));
+                Some(format!(
+                    "{field}=in=({})",
+                    values.collect::<Vec<_>>().join(",")
+                ))
+            },
+            Operator::StartsWith { ignore_case: false } => Some(format!(
+                "{field}=={}",
+                pattern(|value| format!("{value}*"))?
+            )),
+            Operator::Contains { ignore_case: false } => Some(format!(
+                "{field}=={}",
+                pattern(|value| format!("*{value}*"))?
+            )),
+            Operator::EndsWith { ignore_case: false } => Some(format!(
+                "{field}=={}",
+                pattern(|value| format!("*{value}"))?
+            )),
+            Operator::StartsWith { ignore_case: true }
+            | Operator::Contains { ignore_case: true }
+            | Operator::EndsWith { ignore_case: true }
+            | Operator::EqIgnoreCase
+            | Operator::IsNone
+            | Operator::IsSome => None,
+        }
+    }
+}
+
+/// Quote an RSQL value if it contains reserved characters.
+fn rsql_quote(value: &str) -> String {
+    const RESERVED: &[char] = &['"', '\'', '(', ')', ';', ',', '=', '!', '~', '<', '>', ' '];
+
+    if !value.is_empty() && !value.contains(RESERVED) {
+        return value.to_owned();
+    }
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
+
+/// Write `node` as an expression, or [`None`] if any part of it can not be expressed.
+fn expression<T, G: Grammar>(
+    grammar: &G,
+    node: &Node<'_, T>,
+    convention: &HttpConvention,
+) -> Option<String> {
+    let junction = |nodes: &[Node<'_, T>], separator| {
+        let terms = nodes
+            .iter()
+            .map(|operand| term(grammar, operand, convention));
+        Some(terms.collect::<Option<Vec<_>>>()?.join(separator))
+    };
+
+    match node {
+        Node::True => grammar.constant(true),
+        Node::False => grammar.constant(false),
+        Node::Leaf { predicate, .. } => {
+            let primitive = predicate.get().primitive()?;
+            let field = convention
+                .fields
+                .param(primitive.field, &ParamName::default())?;
+            grammar.primitive(&field, &primitive, convention)
+        },
+        Node::Not(operand) => grammar.not(&expression(grammar, operand, convention)?),
+        Node::And(nodes) => junction(nodes, G::AND),
+        Node::Or(nodes) => junction(nodes, G::OR),
+    }
+}
+
+/// Write `node` as an operand of a junction, parenthesizing nested junctions.
+fn term<T, G: Grammar>(
+    grammar: &G,
+    node: &Node<'_, T>,
+    convention: &HttpConvention,
+) -> Option<String> {
+    let expression = expression(grammar, node, convention)?;
+    Some(match node {
+        Node::And(_) | Node::Or(_) => format!("({expression})"),
+        Node::True | Node::False | Node::Leaf { .. } | Node::Not(_) => expression,
+    })
+}
+
+/// Translate `query` into a filter expression sent as the parameter `param`.
+///
+/// The query is normalized first, such that negations are pushed inward. Each term of the
+/// outermost conjunction which can not be expressed remains as residue. Terms created while
+/// normalizing are not part of the original query, so if such a term can not be expressed, the
+/// entire query remains as residue instead.
+fn filter<'a, T, G: Grammar>(
+    grammar: &G,
+    param: Cow<'a, str>,
+    query: &'a (dyn Query<T> + Sync),
+    convention: &HttpConvention,
+) -> Single<'a, HttpQuery<'a>, T> {
+    let conjuncts = match Node::of(query).normalize() {
+        Node::True => Vec::new(),
+        Node::And(nodes) => nodes,
+        node @ (Node::False | Node::Leaf { .. } | Node::Not(_) | Node::Or(_)) => vec![node],
+    };
+
+    let mut terms = Vec::new();
+    let mut residue = Vec::new();
+    let mut opaque = false;
+    for conjunct in conjuncts {
+        if let Some(term) = term(grammar, &conjunct, convention) {
+            terms.push(term);
+        } else if let Node::Leaf {
+            predicate: Predicate::Borrowed(predicate),
+            ..
+        } = conjunct
+        {
+            residue.push(predicate);
+        } else {
+            opaque = true;
+        }
+    }
+    if opaque {
+        residue = vec![query];
+    }
+
+    let params = if terms.is_empty() {
+        HttpQuery::new()
+    } else {
+        vec![(param, terms.join(G::AND).into())]
+    };
+    Single {
+        query: params,
+        residue,
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    clippy::unwrap_used,
+    reason = "Panics simply indicate failed tests."
+)]
+mod tests {
+    use super::*;
+    use crate::query::{
+        Queryable,
+        combinators::{And, Not, Or},
+        http::FieldMap,
+    };
+
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestBook {
+        title: String,
+        author: String,
+        year: u16,
+    }
+
+    /// Translate `query` using `dialect`, returning the parameters and the number of residue
+    /// parts.
+    fn translate(
+        dialect: &impl HttpDialect<TestBook>,
+        query: &(dyn Query<TestBook> + Sync),
+        convention: &HttpConvention,
+    ) -> (Vec<(String, String)>, usize) {
+        let Single { query, residue } = dialect.translate(query, convention);
+        let params = query
+            .into_iter()
+            .map(|(name, value)| (name.into_owned(), value.into()))
+            .collect();
+        (params, residue.len())
+    }
+
+    /// A single parameter named `name`.
+    fn param(name: &str, value: &str) -> Vec<(String, String)> {
+        vec![(name.to_owned(), value.to_owned())]
+    }
+
+    #[test]
+    fn params() {
+        let convention = HttpConvention::new();
+        let query = TestBook::year().eq(&1815) & TestBook::year().gt(&1800);
+
+        assert_eq!(
+            translate(&Params, &query, &convention),
+            (param("year", "1815"), 1)
+        );
+    }
+
+    #[test]
+    fn odata() {
+        let convention = HttpConvention::new();
+        let query = TestBook::year().gt(&1800)
+            & Or(
+                TestBook::author().eq("O'Brian"),
+                Not(TestBook::title().starts_with("E")),
+            )
+            & !(TestBook::year().lt(&1900) & TestBook::title().ne("Emma"));
+
+        assert_eq!(
+            translate(&OData, &query, &convention),
+            (
+                param(
+                    "$filter",
+                    "year gt 1800 and (author eq 'O''Brian' or not (startswith(title,'E'))) and \
+                     (year ge 1900 or title eq 'Emma')"
+                ),
+                0
+            )
+        );
+
+        let patterns = And(
+            TestBook::title().contains("mm").ignore_case(),
+            TestBook::year().is_in(&[1811, 1815]),
+        );
+        assert_eq!(
+            translate(&OData, &patterns, &convention),
+            (
+                param(
+                    "$filter",
+                    "contains(tolower(title),tolower('mm')) and (year eq 1811 or year eq 1815)"
+                ),
+                0
+            )
+        );
+    }
+
+    #[test]
+    fn rsql() {
+        let convention = HttpConvention::new();
+        let query = And(
+            And(
+                TestBook::year().between(&1800, &1900),
+                !(TestBook::year().ne(&1815) & TestBook::author().eq("Jane Austen")),
+            ),
+            TestBook::title().contains("mm"),
+        );
+
+        assert_eq!(
+            translate(&Rsql::default(), &query, &convention),
+            (
+                param(
+                    "filter",
+                    "year=ge=1800;year=le=1900;(year==1815,author!=\"Jane Austen\");title==*mm*"
+                ),
+                0
+            )
+        );
+    }
+
+    #[test]
+    fn residue() {
+        let convention = HttpConvention::new().fields(FieldMap::new().rename("author", "creator"));
+        let query = TestBook::author().eq("Jane Austen")
+            & TestBook::title().eq_ignore_case("emma")
+            & TestBook::year().le(&1815);
+
+        // Unsupported terms of the outermost conjunction remain as residue by themselves.
+        assert_eq!(
+            translate(&Rsql::new("q"), &query, &convention),
+            (param("q", "creator==\"Jane Austen\";year=le=1815"), 1)
+        );
+
+        // Terms created by normalizing do not outlive the translation, so the entire query
+        // remains as residue.
+        let negated = And(
+            TestBook::year().le(&1815),
+            Not(TestBook::title().starts_with("E")),
+        );
+        assert_eq!(
+            translate(&Rsql::default(), &negated, &convention),
+            (param("filter", "year=le=1815"), 1)
+        );
+
+        let wildcard = TestBook::title().eq("A*") | TestBook::title().eq("B");
+        assert_eq!(
+            translate(&Rsql::default(), &wildcard, &convention),
+            (Vec::new(), 1)
+        );
+    }
+}
@@ -0,0 +1,921 @@
+//! Rewriting queries before translation.
+//!
+//! Translations are rarely exact: for example, [`Not`](super::combinators::Not) leaves its entire
+//! subquery as residue in [`Query::to_http_single`], and [`Or`](super::combinators::Or) keeps
+//! only the parameters common to both operands. [`optimize`] rewrites a query into an equivalent
+//! form that translates better, such that connectors fetch smaller supersets of the desired
+//! entries:
+//!
+//! - Negations are pushed inward using De Morgan's laws, and comparisons are negated directly
+//!   (`!(year < 1800)` becomes `year >= 1800`). Ordering comparisons are only negated for
+//!   totally ordered types such as integers and strings, since floating-point NaN fails both.
+//! - [`True`](super::combinators::True) is folded away, as are the constants resulting from
+//!   empty or absorbed junctions.
+//! - Nested conjunctions and disjunctions are flattened.
+//! - Duplicate terms are removed.
+//!
+//! Optionally, the result can be converted into disjunctive normal form using
+//! [`Optimized::to_dnf`], which lets [`Query::to_http_multi`] translate more queries.
+//!
+//! To see what the rewrite achieved, print an [`Explanation`] using `{:#?}`.
+
+#[cfg(any(feature = "rest", feature = "postgres"))]
+use super::Single;
+#[cfg(feature = "postgres")]
+use super::SqlStatement;
+#[cfg(feature = "postgres")]
+use super::combinators::{all_to_sql_impl, any_to_sql_impl, sql_false};
+#[cfg(feature = "rest")]
+use super::{
+    HttpConvention, HttpQuery,
+    combinators::{
+        all_to_http_multi_impl, all_to_http_single_impl, any_to_http_impl, any_to_http_multi_impl,
+    },
+};
+use super::{Primitive, Query, Shape};
+use nameof::{name_of, name_of_type};
+use std::fmt::{Debug, Display, Error as FmtError, Formatter};
+use std::iter::zip;
+use std::ptr::addr_eq;
+use std::sync::Arc;
+
+/// The maximum number of terms produced by [`Optimized::to_dnf`]. Conversion to disjunctive normal
+/// form may grow queries exponentially, and is abandoned if the result would exceed this.
+pub const DNF_TERM_LIMIT: usize = 64;
+
+/// A predicate in a [`Node`] tree, either part of the original query or created while rewriting
+/// it.
+pub enum Predicate<'a, T> {
+    /// A part of the original query.
+    Borrowed(&'a (dyn Query<T> + Sync)),
+    /// A query created while rewriting, such as the negation of a comparison.
+    Shared(Arc<dyn Query<T> + Send + Sync + 'a>),
+}
+
+impl<T> Predicate<'_, T> {
+    /// The underlying query.
+    #[must_use]
+    #[inline]
+    pub fn get(&self) -> &(dyn Query<T> + Sync) {
+        match self {
+            Self::Borrowed(query) => *query,
+            Self::Shared(query) => &**query,
+        }
+    }
+}
+
+impl<T> Clone for Predicate<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        match self {
+            Self::Borrowed(query) => Self::Borrowed(*query),
+            Self::Shared(query) => Self::Shared(Arc::clone(query)),
+        }
+    }
+}
+
+impl<T> Debug for Predicate<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        self.get().fmt(f)
+    }
+}
+
+/// The logical structure of a query, as returned by [`Query::node`] and rewritten by [`optimize`].
+///
+/// A node is itself a query, translating similarly to the combinators it represents.
+pub enum Node<'a, T> {
+    /// Matches everything.
+    True,
+    /// Matches nothing.
+    False,
+    /// A predicate with no further logical structure.
+    Leaf {
+        /// The predicate.
+        predicate: Predicate<'a, T>,
+        /// The negation of the predicate, if it can be expressed without
+        /// [`Not`](super::combinators::Not).
+        negation: Option<Predicate<'a, T>>,
+    },
+    /// Matches what the operand does not.
+    Not(Box<Self>),
+    /// Matches what all operands match.
+    And(Vec<Self>),
+    /// Matches what any operand matches.
+    Or(Vec<Self>),
+}
+
+impl<'a, T> Node<'a, T> {
+    /// The structure of `query`, or a leaf if it has none.
+    #[must_use]
+    #[inline]
+    pub fn of(query: &'a (dyn Query<T> + Sync)) -> Self {
+        query.node().unwrap_or(Self::Leaf {
+            predicate: Predicate::Borrowed(query),
+            negation: None,
+        })
+    }
+
+    /// A leaf with a known negation.
+    #[must_use]
+    #[inline]
+    pub fn leaf(predicate: &'a (dyn Query<T> + Sync), negation: impl Query<T> + Send + 'a) -> Self {
+        Self::Leaf {
+            predicate: Predicate::Borrowed(predicate),
+            negation: Some(Predicate::Shared(Arc::new(negation))),
+        }
+    }
+
+    /// A leaf created while rewriting, with a known negation.
+    #[must_use]
+    #[inline]
+    pub fn shared(
+        predicate: impl Query<T> + Send + 'a,
+        negation: impl Query<T> + Send + 'a,
+    ) -> Self {
+        Self::Leaf {
+            predicate: Predicate::Shared(Arc::new(predicate)),
+            negation: Some(Predicate::Shared(Arc::new(negation))),
+        }
+    }
+
+    /// Negate this node, pushing the negation as far inward as possible.
+    #[must_use]
+    #[inline]
+    pub fn negate(self) -> Self {
+        match self {
+            Self::True => Self::False,
+            Self::False => Self::True,
+            Self::Leaf {
+                predicate,
+                negation: Some(negation),
+            } => Self::Leaf {
+                predicate: negation,
+                negation: Some(predicate),
+            },
+            leaf @ Self::Leaf { negation: None, .. } => Self::Not(Box::new(leaf)),
+            Self::Not(node) => *node,
+            Self::And(nodes) => Self::Or(nodes.into_iter().map(Self::negate).collect()),
+            Self::Or(nodes) => Self::And(nodes.into_iter().map(Self::negate).collect()),
+        }
+    }
+
+    /// Rewrite this node into negation normal form, folding constants, flattening nested
+    /// junctions and removing duplicate terms.
+    #[must_use]
+    #[inline]
+    pub fn normalize(self) -> Self {
+        match self {
+            Self::Not(operand) => match *operand {
+                leaf @ Self::Leaf { negation: None, .. } => Self::Not(Box::new(leaf)),
+                inner @ (Self::True
+                | Self::False
+                | Self::Leaf { .. }
+                | Self::Not(_)
+                | Self::And(_)
+                | Self::Or(_)) => inner.negate().normalize(),
+            },
+            Self::And(nodes) => Self::junction(nodes, true),
+            Self::Or(nodes) => Self::junction(nodes, false),
+            node @ (Self::True | Self::False | Self::Leaf { .. }) => node,
+        }
+    }
+
+    /// Normalize a conjunction (if `conjunctive`) or disjunction of `nodes`.
+    fn junction(nodes: Vec<Self>, conjunctive: bool) -> Self {
+        let (identity, absorbing) = if conjunctive {
+            (Self::True, Self::False)
+        } else {
+            (Self::False, Self::True)
+        };
+
+        let mut terms = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            match node.normalize() {
+                Self::True if conjunctive => {},
+                Self::False if !conjunctive => {},
+                Self::True | Self::False => return absorbing,
+                Self::And(nested) if conjunctive => terms.extend(nested),
+                Self::Or(nested) if !conjunctive => terms.extend(nested),
+                term @ (Self::Leaf { .. } | Self::Not(_) | Self::And(_) | Self::Or(_)) => {
+                    terms.push(term);
+                },
+            }
+        }
+
+        let mut unique: Vec<Self> = Vec::with_capacity(terms.len());
+        for term in terms {
+            if !unique.iter().any(|seen| seen.same(&term)) {
+                unique.push(term);
+            }
+        }
+
+        match unique.len() {
+            0 => identity,
+            1 => unique.pop().unwrap_or(identity),
+            _ if conjunctive => Self::And(unique),
+            _ => Self::Or(unique),
+        }
+    }
+
+    /// Whether this node is known to be the same term as `other`. Leaves describing a
+    /// [`Primitive`] are compared by their query syntax, which identifies them by their field,
+    /// operator and values. Other leaves are opaque, so they are only the same if they are the same
+    /// predicate.
+    fn same(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::True, Self::True) | (Self::False, Self::False) => true,
+            (Self::Leaf { predicate: lhs, .. }, Self::Leaf { predicate: rhs, .. }) => {
+                let (lhs, rhs) = (lhs.get(), rhs.get());
+                if lhs.primitive().is_some() && rhs.primitive().is_some() {
+                    format!("{:#}", Syntax(lhs)) == format!("{:#}", Syntax(rhs))
+                } else {
+                    // Distinct values of zero-sized types may share an address.
+                    addr_eq(lhs, rhs) && size_of_val(lhs) != 0
+                }
+            },
+            (Self::Not(lhs), Self::Not(rhs)) => lhs.same(rhs),
+            (Self::And(lhs), Self::And(rhs)) | (Self::Or(lhs), Self::Or(rhs)) => {
+                lhs.len() == rhs.len() && zip(lhs, rhs).all(|(lhs, rhs)| lhs.same(rhs))
+            },
+            (
+                Self::True
+                | Self::False
+                | Self::Leaf { .. }
+                | Self::Not(_)
+                | Self::And(_)
+                | Self::Or(_),
+                _,
+            ) => false,
+        }
+    }
+
+    /// The terms of this node in disjunctive normal form, each being a conjunction of literals.
+    /// Returns [`None`] if there would be more than `limit` terms.
+    fn disjuncts(&self, limit: usize) -> Option<Vec<Vec<Self>>> {
+        match self {
+            Self::True => Some(vec![Vec::new()]),
+            Self::False => Some(Vec::new()),
+            Self::Or(nodes) => {
+                let mut terms = Vec::new();
+                for node in nodes {
+                    terms.append(&mut node.disjuncts(limit)?);
+                    if terms.len() > limit {
+                        return None;
+                    }
+                }
+                Some(terms)
+            },
+            Self::And(nodes) => nodes.iter().try_fold(vec![Vec::new()], |terms, node| {
+                let parts = node.disjuncts(limit)?;
+                if terms.len().saturating_mul(parts.len()) > limit {
+                    return None;
+                }
+                Some(
+                    terms
+                        .iter()
+                        .flat_map(|term| {
+                            parts.iter().map(|part| {
+                                let mut term = term.clone();
+                                term.extend_from_slice(part);
+                                term
+                            })
+                        })
+                        .collect(),
+                )
+            }),
+            literal @ (Self::Leaf { .. } | Self::Not(_)) => Some(vec![vec![literal.clone()]]),
+        }
+    }
+}
+
+impl<T> Clone for Node<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        match self {
+            Self::True => Self::True,
+            Self::False => Self::False,
+            Self::Leaf {
+                predicate,
+                negation,
+            } => Self::Leaf {
+                predicate: predicate.clone(),
+                negation: negation.clone(),
+            },
+            Self::Not(node) => Self::Not(node.clone()),
+            Self::And(nodes) => Self::And(nodes.clone()),
+            Self::Or(nodes) => Self::Or(nodes.clone()),
+        }
+    }
+}
+
+impl<T> Query<T> for Node<'_, T> {
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        match self {
+            Self::True => true,
+            Self::False => false,
+            Self::Leaf { predicate, .. } => predicate.get().evaluate(data),
+            Self::Not(node) => !node.evaluate(data),
+            Self::And(nodes) => nodes.iter().all(|node| node.evaluate(data)),
+            Self::Or(nodes) => nodes.iter().any(|node| node.evaluate(data)),
+        }
+    }
+
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        Some(self.clone())
+    }
+
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        match self {
+            Self::Leaf { predicate, .. } => predicate.get().primitive(),
+            Self::True | Self::False | Self::Not(_) | Self::And(_) | Self::Or(_) => None,
+        }
+    }
+
+    /// If the operand of a negation has residue, the negation matches a subset of the desired
+    /// elements, so it translates to nothing and remains as residue.
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        match self {
+            Self::True => Single {
+                query: SqlStatement::new(),
+                residue: Vec::new(),
+            },
+            Self::False => Single {
+                query: sql_false(),
+                residue: Vec::new(),
+            },
+            Self::Leaf { predicate, .. } => predicate.get().to_sql_single(),
+            Self::Not(node) => {
+                let Single { mut query, residue } = node.to_sql_single();
+                if residue.is_empty() {
+                    query.query_text = format!("NOT ({})", query.condition());
+                    Single { query, residue }
+                } else {
+                    Single {
+                        query: SqlStatement::new(),
+                        residue: vec![self],
+                    }
+                }
+            },
+            Self::And(nodes) => all_to_sql_impl(nodes),
+            Self::Or(nodes) => {
+                let (query, exact) = any_to_sql_impl(nodes);
+                Single {
+                    query,
+                    residue: if exact { Vec::new() } else { vec![self] },
+                }
+            },
+        }
+    }
+
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        let Single { query, residue } = self.to_sql_single();
+        residue.is_empty().then(|| vec![query])
+    }
+
+    /// Translates like the corresponding combinators: [`True`](super::combinators::True),
+    /// [`Not`](super::combinators::Not), [`All`](super::combinators::All) and
+    /// [`Any`](super::combinators::Any). A constant false node translates to no parameters and
+    /// remains as residue.
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        match self {
+            Self::True => Single {
+                query: HttpQuery::new(),
+                residue: Vec::new(),
+            },
+            Self::Leaf { predicate, .. } => predicate.get().to_http_single(convention),
+            Self::False | Self::Not(_) => Single {
+                query: HttpQuery::new(),
+                residue: vec![self],
+            },
+            Self::And(nodes) => all_to_http_single_impl(nodes, convention),
+            Self::Or(nodes) => {
+                let (query, exact) = any_to_http_impl(nodes, convention);
+                Single {
+                    query,
+                    residue: if exact { Vec::new() } else { vec![self] },
+                }
+            },
+        }
+    }
+
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        match self {
+            Self::True => Some(vec![HttpQuery::new()]),
+            Self::False => Some(Vec::new()),
+            Self::Leaf { predicate, .. } => predicate.get().to_http_multi(convention),
+            Self::Not(_) => None,
+            Self::And(nodes) => all_to_http_multi_impl(nodes, convention),
+            Self::Or(nodes) => any_to_http_multi_impl(nodes, convention),
+        }
+    }
+}
+
+impl<T> Debug for Node<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::True if f.alternate() => write!(f, "true"),
+            Self::False if f.alternate() => write!(f, "false"),
+            Self::Leaf { predicate, .. } if f.alternate() => predicate.fmt(f),
+            Self::Not(node) if f.alternate() => write!(f, "!({node:#?})"),
+            Self::And(nodes) if f.alternate() => write_variadic(f, "all", nodes),
+            Self::Or(nodes) if f.alternate() => write_variadic(f, "any", nodes),
+            Self::True => write!(f, "True"),
+            Self::False => write!(f, "False"),
+            Self::Leaf {
+                predicate,
+                negation,
+            } => f
+                .debug_struct("Leaf")
+                .field(name_of!(predicate), predicate)
+                .field(name_of!(negation), negation)
+                .finish(),
+            Self::Not(node) => f.debug_tuple("Not").field(node).finish(),
+            Self::And(nodes) => f.debug_tuple("And").field(nodes).finish(),
+            Self::Or(nodes) => f.debug_tuple("Or").field(nodes).finish(),
+        }
+    }
+}
+
+/// Format `nodes` in the alternate syntax as a call to `name`, such as `all(a, b)`.
+///
+/// # Errors
+///
+/// Forwards errors from the formatter.
+fn write_variadic<T>(
+    f: &mut Formatter<'_>,
+    name: &str,
+    nodes: &[Node<'_, T>],
+) -> Result<(), FmtError> {
+    write!(f, "{name}(")?;
+    for (i, node) in nodes.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        node.fmt(f)?;
+    }
+    write!(f, ")")
+}
+
+/// Displays a query in its alternate [`Debug`] syntax, which is used to compare terms.
+struct Syntax<'b, Q: ?Sized>(&'b Q);
+
+impl<Q: Debug + ?Sized> Display for Syntax<'_, Q> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self(query) = self;
+        query.fmt(f)
+    }
+}
+
+/// A query rewritten by [`optimize`].
+///
+/// The shape of the original query is retained, while its predicate is replaced by an
+/// equivalent [`Node`] tree.
+pub struct Optimized<'a, T> {
+    /// The original query.
+    original: &'a (dyn Query<T> + Sync),
+    /// The rewritten predicate.
+    node: Node<'a, T>,
+}
+
+/// Rewrite `query` into an equivalent query that translates at least as well. See the
+/// [module-level documentation](self) for details.
+#[must_use]
+#[inline]
+pub fn optimize<T>(query: &(dyn Query<T> + Sync)) -> Optimized<'_, T> {
+    Optimized {
+        original: query,
+        node: Node::of(query).normalize(),
+    }
+}
+
+impl<'a, T> Optimized<'a, T> {
+    /// The query before optimization.
+    #[must_use]
+    #[inline]
+    pub fn original(&self) -> &'a (dyn Query<T> + Sync) {
+        self.original
+    }
+
+    /// The rewritten predicate.
+    #[must_use]
+    #[inline]
+    pub const fn as_node(&self) -> &Node<'a, T> {
+        &self.node
+    }
+
+    /// Convert the predicate into disjunctive normal form, a disjunction of conjunctions of
+    /// literals. This is left as is if the result would have more than [`DNF_TERM_LIMIT`] terms.
+    #[must_use]
+    #[inline]
+    pub fn to_dnf(self) -> Self {
+        let Some(terms) = self.node.disjuncts(DNF_TERM_LIMIT) else {
+            return self;
+        };
+        let node = Node::Or(terms.into_iter().map(Node::And).collect()).normalize();
+        Self { node, ..self }
+    }
+
+    /// Compare the translations of the query before and after optimization.
+    #[must_use]
+    #[inline]
+    pub const fn explain(&self) -> Explanation<'_, 'a, T> {
+        Explanation {
+            optimized: self,
+            #[cfg(feature = "rest")]
+            convention: HttpConvention::new(),
+        }
+    }
+}
+
+impl<T> Query<T> for Optimized<'_, T> {
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        self.node.evaluate(data)
+    }
+
+    #[inline]
+    fn shape(&self) -> Shape<T> {
+        self.original.shape()
+    }
+
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        Some(self.node.clone())
+    }
+
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        self.node.primitive()
+    }
+
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        self.node.to_sql_single()
+    }
+
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        self.node.to_sql_multi()
+    }
+
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        self.node.to_http_single(convention)
+    }
+
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        self.node.to_http_multi(convention)
+    }
+}
+
+impl<T> Debug for Optimized<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { original, node } = self;
+        if f.alternate() {
+            node.fmt(f)
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(original in Self), original)
+                .field(name_of!(node in Self), node)
+                .finish()
+        }
+    }
+}
+
+/// The translations of a query before and after optimization, created by
+/// [`Optimized::explain`] and meant to be printed using `{:#?}` when tuning the optimizer.
+pub struct Explanation<'o, 'a, T> {
+    /// The optimized query.
+    optimized: &'o Optimized<'a, T>,
+    /// The convention used for HTTP translations.
+    #[cfg(feature = "rest")]
+    convention: HttpConvention,
+}
+
+impl<T> Explanation<'_, '_, T> {
+    /// Set the convention used for HTTP translations.
+    #[cfg(feature = "rest")]
+    #[must_use]
+    #[inline]
+    pub fn convention(self, convention: HttpConvention) -> Self {
+        Self { convention, ..self }
+    }
+
+    /// Write the HTTP translation of `query`.
+    ///
+    /// # Errors
+    ///
+    /// Forwards errors from the formatter.
+    #[cfg(feature = "rest")]
+    fn write_http(
+        &self,
+        f: &mut Formatter<'_>,
+        query: &(dyn Query<T> + Sync),
+    ) -> Result<(), FmtError> {
+        let Single {
+            query: params,
+            residue,
+        } = query.to_http_single(&self.convention);
+        let params = params
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>();
+        writeln!(
+            f,
+            "    http: {} residue: [{}]",
+            params.join("&"),
+            residue_syntax(&residue)
+        )
+    }
+
+    /// Write the SQL translation of `query`.
+    ///
+    /// # Errors
+    ///
+    /// Forwards errors from the formatter.
+    #[cfg(feature = "postgres")]
+    #[expect(clippy::unused_self, reason = "Consistency with `write_http`.")]
+    fn write_sql(
+        &self,
+        f: &mut Formatter<'_>,
+        query: &(dyn Query<T> + Sync),
+    ) -> Result<(), FmtError> {
+        let Single {
+            query: statement,
+            residue,
+        } = query.to_sql_single();
+        writeln!(
+            f,
+            "    sql: {} residue: [{}]",
+            statement.condition(),
+            residue_syntax(&residue)
+        )
+    }
+}
+
+impl<T> Debug for Explanation<'_, '_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Optimized { original, node } = self.optimized;
+        let queries: [(&str, &(dyn Query<T> + Sync)); 2] = [("before", *original), ("after", node)];
+
+        for (stage, query) in queries {
+            writeln!(f, "{stage}: {query:#?}")?;
+            #[cfg(feature = "rest")]
+            self.write_http(f, query)?;
+            #[cfg(feature = "postgres")]
+            self.write_sql(f, query)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Format residue in the alternate syntax, separated by commas.
+#[cfg(any(feature = "rest", feature = "postgres"))]
+fn residue_syntax<T>(residue: &[&(dyn Query<T> + Sync)]) -> String {
+    residue
+        .iter()
+        .map(|query| format!("{:#}", Syntax(*query)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    reason = "Panics simply indicate failed tests."
+)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "rest")]
+    use crate::query::http::RangeParams;
+    use crate::query::{
+        Queryable,
+        combinators::{And, Any, Not, Or, True, Xor},
+    };
+
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestBook {
+        title: String,
+        year: u16,
+    }
+
+    impl TestBook {
+        fn new(title: &str, year: u16) -> Self {
+            Self {
+                title: title.to_owned(),
+                year,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestReading {
+        value: f64,
+    }
+
+    /// An opaque predicate, which its syntax does not identify.
+    struct TestCustom(fn(&TestBook) -> bool);
+
+    impl Debug for TestCustom {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+            f.write_str("custom")
+        }
+    }
+
+    impl Query<TestBook> for TestCustom {
+        fn evaluate(&self, data: &TestBook) -> bool {
+            (self.0)(data)
+        }
+
+        #[cfg(feature = "rest")]
+        fn to_http_single(&self, _: &HttpConvention) -> Single<'_, HttpQuery<'_>, TestBook> {
+            Single {
+                query: HttpQuery::new(),
+                residue: vec![self],
+            }
+        }
+
+        #[cfg(feature = "rest")]
+        fn to_http_multi(&self, _: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+            None
+        }
+
+        #[cfg(feature = "postgres")]
+        fn to_sql_single(&self) -> Single<'_, SqlStatement, TestBook> {
+            Single {
+                query: SqlStatement::new(),
+                residue: vec![self],
+            }
+        }
+
+        #[cfg(feature = "postgres")]
+        fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+            None
+        }
+    }
+
+    #[test]
+    fn de_morgan() {
+        let query = Not(And(
+            TestBook::title().eq("Emma"),
+            Not(Or(
+                TestBook::year().lt(&1800),
+                TestBook::year().between(&1810, &1820),
+            )),
+        ));
+
+        let optimized = optimize(&query);
+
+        assert_eq!(
+            format!("{optimized:#?}"),
+            r#"any(title != "Emma", year < 1800, all(year >= 1810, year <= 1820))"#
+        );
+    }
+
+    #[test]
+    fn fold_flatten_dedupe() {
+        let emma = || TestBook::title().eq("Emma");
+        let recent = || TestBook::year().gt(&1800);
+
+        let flattened = And(True, And(emma(), And(recent(), emma())));
+        let absorbed = Or(recent(), Not(True));
+        let empty = Any::<True>(vec![]);
+        let tautology = Or(emma(), Not(Any::<True>(vec![])));
+
+        assert_eq!(
+            format!("{:#?}", optimize(&flattened)),
+            r#"all(title = "Emma", year > 1800)"#
+        );
+        assert_eq!(format!("{:#?}", optimize(&absorbed)), "year > 1800");
+        assert_eq!(format!("{:#?}", optimize::<TestBook>(&empty)), "false");
+        assert_eq!(format!("{:#?}", optimize(&tautology)), "true");
+    }
+
+    #[test]
+    fn partial_order() {
+        let above = Not(TestReading::value().gt(&1.0));
+        let outside = Not(TestReading::value().between(&0.0, &1.0));
+        let nan = TestReading { value: f64::NAN };
+
+        assert_eq!(format!("{:#?}", optimize(&above)), "!(value > 1.0)");
+        assert_eq!(
+            format!("{:#?}", optimize(&outside)),
+            "any(!(value >= 0.0), !(value <= 1.0))"
+        );
+        assert!(optimize(&above).evaluate(&nan));
+        assert!(optimize(&outside).evaluate(&nan));
+    }
+
+    #[test]
+    fn opaque_terms() {
+        let old = TestCustom(|book| book.year < 1800);
+        let emma = TestCustom(|book| book.title == "Emma");
+        let query = Or(old, emma);
+        let repeated = TestCustom(|book| book.year < 1800);
+
+        assert!(optimize(&query).evaluate(&TestBook::new("Emma", 1815)));
+        assert_eq!(
+            format!(
+                "{:#?}",
+                Node::Or(vec![Node::of(&repeated), Node::of(&repeated)]).normalize()
+            ),
+            "custom"
+        );
+    }
+
+    #[test]
+    fn dnf() {
+        let query = And(
+            Or(
+                TestBook::title().eq("Emma"),
+                TestBook::title().eq("Persuasion"),
+            ),
+            TestBook::year().gt(&1800),
+        );
+
+        let optimized = optimize(&query).to_dnf();
+
+        assert_eq!(
+            format!("{optimized:#?}"),
+            r#"any(all(title = "Emma", year > 1800), all(title = "Persuasion", year > 1800))"#
+        );
+    }
+
+    #[test]
+    fn preserves_evaluation() {
+        let books = [
+            TestBook::new("Emma", 1815),
+            TestBook::new("Evelina", 1778),
+            TestBook::new("Persuasion", 1817),
+        ];
+        let query = Xor(
+            Not(TestBook::title().eq("Emma")),
+            Or(
+                TestBook::year().le(&1800),
+                Not(TestBook::title().starts_with("P")),
+            ),
+        );
+
+        let optimized = optimize(&query);
+        let dnf = optimize(&query).to_dnf();
+
+        for book in &books {
+            assert_eq!(optimized.evaluate(book), query.evaluate(book));
+            assert_eq!(dnf.evaluate(book), query.evaluate(book));
+        }
+    }
+
+    #[cfg(feature = "rest")]
+    #[test]
+    fn http_smaller_residue() {
+        let convention = HttpConvention::new().range(RangeParams::suffixed());
+        let query = Not(Or(TestBook::title().ne("Emma"), TestBook::year().lt(&1800)));
+
+        let before = query.to_http_single(&convention);
+        let optimized = optimize(&query);
+        let after = optimized.to_http_single(&convention);
+
+        assert!(before.query.is_empty());
+        assert_eq!(before.residue.len(), 1);
+        assert_eq!(
+            after.query,
+            [
+                ("title".into(), "Emma".into()),
+                ("year_gte".into(), "1800".into()),
+            ]
+        );
+        assert!(after.residue.is_empty());
+    }
+
+    #[test]
+    fn explain() {
+        let query = Not(TestBook::year().lt(&1800));
+
+        let explanation = format!("{:#?}", optimize(&query).explain());
+
+        assert!(explanation.starts_with("before: !(year < 1800)\n"));
+        assert!(explanation.contains("after: year >= 1800\n"));
+    }
+}
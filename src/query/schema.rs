@@ -0,0 +1,267 @@
+//! Descriptions of queryable fields, for discovering them at runtime.
+//!
+//! `#[derive(Queryable)]` implements [`Schema`], listing a [`FieldInfo`] for every field that can
+//! be queried. These can be serialized using [`serde`], such that for example a user interface can
+//! offer only the fields and predicates that apply, or a validator can reject queries up front.
+
+use super::dynamic::DynValue;
+use serde::Serialize;
+use std::any::type_name;
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+/// A type whose queryable fields can be listed at runtime, implemented by `#[derive(Queryable)]`.
+pub trait Schema {
+    /// Descriptions of the queryable fields, in declaration order. Fields marked
+    /// `#[queryable(nested)]` are followed by the fields of the nested type, named using dotted
+    /// names such as `publisher.name`.
+    fn fields() -> Vec<FieldInfo>;
+}
+
+/// A description of a queryable field.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "The capabilities are independent and serialized as flags."
+)]
+pub struct FieldInfo {
+    /// The name of the field, as used for translation and by
+    /// [`DynQuery`](super::dynamic::DynQuery).
+    pub name: Cow<'static, str>,
+    /// The name of the Rust type of the field, as given by [`type_name`].
+    pub type_name: &'static str,
+    /// Whether the field supports ordering comparisons, such as [`Field::gt`].
+    ///
+    /// [`Field::gt`]: super::Field::gt
+    pub comparable: bool,
+    /// Whether the field supports string predicates, such as [`Field::starts_with`].
+    ///
+    /// [`Field::starts_with`]: super::Field::starts_with
+    pub string: bool,
+    /// Whether the field supports null checks, such as [`Field::is_none`].
+    ///
+    /// [`Field::is_none`]: super::Field::is_none
+    pub optional: bool,
+    /// Whether the field can be queried by [`DynQuery`](super::dynamic::DynQuery), meaning its
+    /// type implements [`DynValue`]. The other capabilities describe [`Field`](super::Field)
+    /// and may include fields that can not be queried dynamically.
+    pub dynamic: bool,
+}
+
+impl FieldInfo {
+    /// Describe the field named `name` of type `U`, without any capabilities. Used by
+    /// `#[derive(Queryable)]`.
+    #[doc(hidden)]
+    #[must_use]
+    #[inline]
+    pub fn new<U: ?Sized>(name: &'static str) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            type_name: type_name::<U>(),
+            comparable: false,
+            string: false,
+            optional: false,
+            dynamic: false,
+        }
+    }
+
+    /// Describe this field as nested within the field named `parent`. Used by
+    /// `#[derive(Queryable)]`.
+    #[doc(hidden)]
+    #[must_use]
+    #[inline]
+    pub fn nested(self, parent: &str) -> Self {
+        Self {
+            name: Cow::Owned(format!("{parent}.{}", self.name)),
+            ..self
+        }
+    }
+}
+
+/// Determines the capabilities of fields of type `U`. Used by `#[derive(Queryable)]`; see
+/// [`Unsupported`].
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Probe<U: ?Sized>(PhantomData<U>);
+
+impl<U: ?Sized> Probe<U> {
+    /// Create a probe.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<U: ?Sized> Default for Probe<U> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Detects types supporting ordering comparisons.
+#[doc(hidden)]
+pub trait Comparable {
+    /// Returns `true`.
+    #[inline]
+    fn comparable(&self) -> bool {
+        true
+    }
+}
+
+impl<U: PartialOrd + ?Sized> Comparable for Probe<U> {}
+
+/// Detects types supporting string predicates.
+#[doc(hidden)]
+pub trait Textual {
+    /// Returns `true`.
+    #[inline]
+    fn string(&self) -> bool {
+        true
+    }
+}
+
+impl<U: AsRef<str> + ?Sized> Textual for Probe<U> {}
+
+/// Detects types supporting null checks.
+#[doc(hidden)]
+pub trait Optional {
+    /// Returns `true`.
+    #[inline]
+    fn optional(&self) -> bool {
+        true
+    }
+}
+
+impl<U> Optional for Probe<Option<U>> {}
+
+/// Detects types supporting [`DynQuery`](super::dynamic::DynQuery).
+#[doc(hidden)]
+pub trait Dynamic {
+    /// Returns `true`.
+    #[inline]
+    fn dynamic(&self) -> bool {
+        true
+    }
+}
+
+impl<U: DynValue> Dynamic for Probe<U> {}
+
+/// Fallback for types lacking a capability. Since it is implemented on a reference, method
+/// resolution prefers [`Comparable`], [`Textual`], [`Optional`] and [`Dynamic`] when applicable.
+#[doc(hidden)]
+pub trait Unsupported {
+    /// Returns `false`.
+    #[inline]
+    fn comparable(&self) -> bool {
+        false
+    }
+
+    /// Returns `false`.
+    #[inline]
+    fn string(&self) -> bool {
+        false
+    }
+
+    /// Returns `false`.
+    #[inline]
+    fn optional(&self) -> bool {
+        false
+    }
+
+    /// Returns `false`.
+    #[inline]
+    fn dynamic(&self) -> bool {
+        false
+    }
+}
+
+impl<U: ?Sized> Unsupported for &Probe<U> {}
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    clippy::unwrap_used,
+    reason = "Panics simply indicate failed tests."
+)]
+mod tests {
+    use super::*;
+    use crate::query::Queryable;
+    use crate::query::dynamic::{DynQuery, ResolveError};
+    use serde_json::{json, to_value};
+
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestPublisher {
+        name: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestBook {
+        title: String,
+        year: Option<u16>,
+        tags: Vec<String>,
+        #[queryable(nested)]
+        publisher: TestPublisher,
+        #[queryable(skip)]
+        _isbn: String,
+    }
+
+    #[test]
+    fn fields() {
+        let fields = TestBook::fields();
+        let names: Vec<_> = fields.iter().map(|field| &*field.name).collect();
+        let capabilities = |name: &str| {
+            let field = fields.iter().find(|field| field.name == name).unwrap();
+            (
+                field.comparable,
+                field.string,
+                field.optional,
+                field.dynamic,
+            )
+        };
+
+        assert_eq!(
+            names,
+            ["title", "year", "tags", "publisher", "publisher.name"]
+        );
+        assert_eq!(capabilities("title"), (true, true, false, true));
+        assert_eq!(capabilities("year"), (true, false, true, false));
+        assert_eq!(capabilities("tags"), (true, false, false, false));
+        assert_eq!(capabilities("publisher"), (false, false, false, false));
+        assert_eq!(capabilities("publisher.name"), (true, true, false, true));
+        assert_eq!(fields[1].type_name, "core::option::Option<u16>");
+    }
+
+    #[test]
+    fn dynamic_agrees_with_resolve() {
+        for field in TestBook::fields() {
+            let query = DynQuery::Eq {
+                field: field.name.to_string(),
+                value: json!("Emma"),
+            };
+            let unsupported = matches!(
+                query.resolve::<TestBook>(),
+                Err(ResolveError::UnsupportedField(_))
+            );
+
+            assert_eq!(field.dynamic, !unsupported, "{}", field.name);
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        assert_eq!(
+            to_value(TestPublisher::fields()).unwrap(),
+            json!([{
+                "name": "name",
+                "type_name": "alloc::string::String",
+                "comparable": true,
+                "string": true,
+                "optional": false,
+                "dynamic": true,
+            }])
+        );
+    }
+}
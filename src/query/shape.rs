@@ -0,0 +1,285 @@
+//! Ordering, limits and offsets of query results.
+//!
+//! The shape of a query is specified using the [`OrderBy`](super::combinators::OrderBy),
+//! [`Limit`](super::combinators::Limit) and [`Offset`](super::combinators::Offset) combinators,
+//! and retrieved using [`Query::shape`]. Connectors push it down to the source where possible, and
+//! otherwise apply it locally using [`Shape::apply`].
+
+#[cfg(any(feature = "rest", feature = "postgres"))]
+use super::Single;
+#[cfg(feature = "postgres")]
+use super::SqlStatement;
+#[cfg(feature = "rest")]
+use super::{HttpConvention, HttpQuery};
+use super::{Node, Primitive, Query};
+use nameof::{name_of, name_of_type};
+use std::cmp::Ordering;
+use std::fmt::{Debug, Error as FmtError, Formatter};
+use std::sync::Arc;
+
+/// A function comparing two values in ascending order.
+type Comparator<T> = dyn Fn(&T, &T) -> Ordering + Send + Sync;
+
+/// A key to order results by, created by [`Field::asc`](super::Field::asc) or
+/// [`Field::desc`](super::Field::desc).
+pub struct SortKey<T> {
+    /// Field name.
+    name: Arc<str>,
+    /// Compares two values by the field, in ascending order.
+    compare: Arc<Comparator<T>>,
+    /// Whether the order is reversed.
+    descending: bool,
+}
+
+impl<T> SortKey<T> {
+    /// Constructs a key from a field name and a function comparing values in ascending order.
+    #[inline]
+    pub fn new(
+        name: Arc<str>,
+        compare: impl Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+        descending: bool,
+    ) -> Self {
+        Self {
+            name,
+            compare: Arc::new(compare),
+            descending,
+        }
+    }
+
+    /// The name of the field ordered by.
+    #[must_use]
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the order is descending rather than ascending.
+    #[must_use]
+    #[inline]
+    pub const fn is_descending(&self) -> bool {
+        self.descending
+    }
+
+    /// Compare two values by this key.
+    #[inline]
+    pub fn compare(&self, a: &T, b: &T) -> Ordering {
+        let ordering = (self.compare)(a, b);
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+impl<T> Clone for SortKey<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            name: Arc::clone(&self.name),
+            compare: Arc::clone(&self.compare),
+            descending: self.descending,
+        }
+    }
+}
+
+impl<T> Debug for SortKey<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self {
+            name,
+            compare: _,
+            descending,
+        } = self;
+        if f.alternate() {
+            let order = if *descending { "desc" } else { "asc" };
+            write!(f, "{name}.{order}()")
+        } else {
+            f.debug_struct(name_of_type!(Self))
+                .field(name_of!(name in Self), name)
+                .field(name_of!(descending in Self), descending)
+                .finish_non_exhaustive()
+        }
+    }
+}
+
+/// The ordering, limit and offset requested for the results of a query.
+///
+/// Results are first ordered, then the first [`offset`](Self::offset) results are skipped, and
+/// finally at most [`limit`](Self::limit) results are kept.
+pub struct Shape<T> {
+    /// The keys to order by, the first being most significant. If empty, the order is unspecified.
+    pub order: Vec<SortKey<T>>,
+    /// The maximum number of results.
+    pub limit: Option<usize>,
+    /// The number of results to skip.
+    pub offset: usize,
+}
+
+impl<T> Shape<T> {
+    /// Constructs a shape with unspecified order and no limit or offset.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            order: Vec::new(),
+            limit: None,
+            offset: 0,
+        }
+    }
+
+    /// Whether this shape leaves results as they are.
+    #[must_use]
+    #[inline]
+    pub const fn is_unshaped(&self) -> bool {
+        self.order.is_empty() && self.limit.is_none() && self.offset == 0
+    }
+
+    /// Compare two values by all keys.
+    #[inline]
+    pub fn compare(&self, a: &T, b: &T) -> Ordering {
+        self.order
+            .iter()
+            .map(|key| key.compare(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    /// Order `entries`, and unless the source has already done so (`windowed`), apply the offset
+    /// and limit.
+    ///
+    /// Ordering is always applied since the source might order values differently, for example
+    /// using a different collation for strings. The sort is stable, so this is cheap if the
+    /// entries are already ordered.
+    #[inline]
+    pub fn apply(&self, entries: &mut Vec<T>, windowed: bool) {
+        if !self.order.is_empty() {
+            entries.sort_by(|a, b| self.compare(a, b));
+        }
+        if !windowed {
+            drop(entries.drain(..self.offset.min(entries.len())));
+            if let Some(limit) = self.limit {
+                entries.truncate(limit);
+            }
+        }
+    }
+
+    /// The shape to request from each of several sources, such that merging their ordered
+    /// results and then applying this shape produces the correct results.
+    #[must_use]
+    #[inline]
+    pub fn for_merge(&self) -> Self {
+        Self {
+            order: self.order.clone(),
+            limit: self.limit.map(|limit| limit.saturating_add(self.offset)),
+            offset: 0,
+        }
+    }
+}
+
+impl<T> Default for Shape<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Shape<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            order: self.order.clone(),
+            limit: self.limit,
+            offset: self.offset,
+        }
+    }
+}
+
+impl<T> Debug for Shape<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self {
+            order,
+            limit,
+            offset,
+        } = self;
+        f.debug_struct(name_of_type!(Self))
+            .field(name_of!(order in Self), order)
+            .field(name_of!(limit in Self), limit)
+            .field(name_of!(offset in Self), offset)
+            .finish()
+    }
+}
+
+/// A query with its shape replaced, used when a query needs to be fetched from a source with a
+/// different shape than requested.
+pub(crate) struct Reshaped<'a, T> {
+    /// The query.
+    query: &'a (dyn Query<T> + Sync),
+    /// The replacing shape.
+    shape: Shape<T>,
+}
+
+impl<'a, T> Reshaped<'a, T> {
+    /// Replace the shape of a query.
+    pub(crate) const fn new(query: &'a (dyn Query<T> + Sync), shape: Shape<T>) -> Self {
+        Self { query, shape }
+    }
+}
+
+impl<T> Query<T> for Reshaped<'_, T> {
+    #[inline]
+    fn evaluate(&self, data: &T) -> bool {
+        self.query.evaluate(data)
+    }
+
+    #[inline]
+    fn shape(&self) -> Shape<T> {
+        self.shape.clone()
+    }
+
+    #[inline]
+    fn node(&self) -> Option<Node<'_, T>> {
+        self.query.node()
+    }
+
+    #[inline]
+    fn primitive(&self) -> Option<Primitive<'_>> {
+        self.query.primitive()
+    }
+
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_single(&self) -> Single<'_, SqlStatement, T> {
+        self.query.to_sql_single()
+    }
+
+    #[cfg(feature = "postgres")]
+    #[inline]
+    fn to_sql_multi(&self) -> Option<Vec<SqlStatement>> {
+        self.query.to_sql_multi()
+    }
+
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_single(&self, convention: &HttpConvention) -> Single<'_, HttpQuery<'_>, T> {
+        self.query.to_http_single(convention)
+    }
+
+    #[cfg(feature = "rest")]
+    #[inline]
+    fn to_http_multi(&self, convention: &HttpConvention) -> Option<Vec<HttpQuery<'_>>> {
+        self.query.to_http_multi(convention)
+    }
+}
+
+impl<T> Debug for Reshaped<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self { query, shape } = self;
+        f.debug_struct(name_of_type!(Self))
+            .field(name_of!(query in Self), query)
+            .field(name_of!(shape in Self), shape)
+            .finish()
+    }
+}
@@ -0,0 +1,757 @@
+//! Parsing queries from text.
+//!
+//! The syntax is that of the alternate [`Debug`](std::fmt::Debug) format (`{:#?}`) of queries, such
+//! that printed filters can be parsed back. Parsing produces a [`DynQuery`], which is then
+//! resolved against a [`Queryable`] type using [`parse`]. Only filters round-trip: the modifiers
+//! `order_by(..)`, `limit(..)` and `offset(..)` have no counterpart in [`DynQuery`] and are
+//! rejected. For example:
+//!
+//! ```text
+//! author = "George Orwell" & (title = "1984" | year < 1940)
+//! !(title.starts_with("the").ignore_case()) ^ isbn.is_in(["0451524934", "0452284236"])
+//! any(year >= 1800, true, all())
+//! ```
+//!
+//! Comparisons are written as `field op value`, where `op` is one of `=`, `!=`, `>`, `<`, `>=` and
+//! `<=`, and values are strings, numbers or booleans. Strings are double-quoted and may contain
+//! the escape sequences used by `Debug`. Queries are combined using `!`, `&`, `^` and `|`, in
+//! order of decreasing precedence, or using `all(...)`, `any(...)` and `one(...)`. Queries may be
+//! nested at most 128 levels deep.
+
+use super::Query;
+use super::dynamic::{DynQuery, Queryable, ResolveError};
+use serde_json::{Number, Value, from_str};
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::{CharIndices, FromStr};
+use thiserror::Error;
+
+/// Parse a query on `T`. See the [module-level documentation](self) for the syntax.
+///
+/// # Errors
+///
+/// Fails if the input is not a valid query, or if the query can not be resolved against `T`.
+#[inline]
+pub fn parse<T: Queryable>(input: &str) -> Result<Box<dyn Query<T> + Send + Sync>, ParseError> {
+    Ok(input.parse::<DynQuery>()?.resolve()?)
+}
+
+impl FromStr for DynQuery {
+    type Err = ParseError;
+
+    /// Parse a query without resolving it. See the [module-level documentation](self) for the
+    /// syntax.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input is not a valid query. Never fails with [`ParseError::Resolve`].
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            position: 0,
+            depth: 0,
+        };
+        let query = parser.query()?;
+        parser.expect(&Kind::End, "end of input")?;
+        Ok(query)
+    }
+}
+
+/// Errors that may occur when parsing a query.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The input does not follow the syntax.
+    #[error("Expected {expected} at {}..{}.", span.start, span.end)]
+    Syntax {
+        /// The byte range of the offending input.
+        span: Range<usize>,
+        /// A description of what was expected.
+        expected: &'static str,
+    },
+    /// A string contains an invalid escape sequence.
+    #[error("Invalid escape sequence at {}..{}.", .0.start, .0.end)]
+    Escape(Range<usize>),
+    /// A number can not be represented.
+    #[error("Invalid number at {}..{}.", .0.start, .0.end)]
+    Number(Range<usize>),
+    /// The query could not be resolved.
+    #[error(transparent)]
+    Resolve(#[from] ResolveError),
+}
+
+impl ParseError {
+    /// The byte range of the input that caused the error, if any.
+    #[must_use]
+    #[inline]
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            Self::Syntax { span, .. } | Self::Escape(span) | Self::Number(span) => {
+                Some(span.clone())
+            },
+            Self::Resolve(_) => None,
+        }
+    }
+}
+
+/// A token and the byte range it was read from.
+#[derive(Debug)]
+struct Token {
+    /// The token.
+    kind: Kind,
+    /// The byte range of the token.
+    span: Range<usize>,
+}
+
+/// The kinds of tokens.
+#[derive(Debug, PartialEq)]
+enum Kind {
+    /// A field name, method name or keyword.
+    Ident(String),
+    /// A string literal, unescaped.
+    Str(String),
+    /// A number literal.
+    Number(Number),
+    /// An operator or punctuation.
+    Symbol(&'static str),
+    /// The end of the input.
+    End,
+}
+
+/// Symbols, ordered such that longer symbols are matched before their prefixes.
+const SYMBOLS: [&str; 16] = [
+    "!=", ">=", "<=", "=", ">", "<", "!", "&", "|", "^", "(", ")", "[", "]", ",", ".",
+];
+
+/// Split `input` into tokens, ending with [`Kind::End`].
+///
+/// # Errors
+///
+/// Fails on unknown characters, unterminated strings and invalid escape sequences or numbers.
+fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let mut chars = input.char_indices().peekable();
+    let mut tokens = Vec::new();
+
+    while let Some((start, c)) = chars.next() {
+        let kind = if c.is_whitespace() {
+            continue;
+        } else if c == '"' {
+            Kind::Str(string(input, &mut chars)?)
+        } else if c == '-' || c.is_ascii_digit() {
+            let text = take_while(c, &mut chars, |c| {
+                c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.')
+            });
+            let span = start..start + text.len();
+            Kind::Number(from_str(&text).map_err(|_err| ParseError::Number(span))?)
+        } else if c == '_' || c.is_alphabetic() {
+            Kind::Ident(take_while(c, &mut chars, |c| {
+                c == '_' || c.is_alphanumeric()
+            }))
+        } else if let Some(symbol) = symbol(c, &mut chars) {
+            Kind::Symbol(symbol)
+        } else {
+            return Err(ParseError::Syntax {
+                span: start..start + c.len_utf8(),
+                expected: "a query",
+            });
+        };
+
+        let end = chars.peek().map_or(input.len(), |&(i, _)| i);
+        tokens.push(Token {
+            kind,
+            span: start..end,
+        });
+    }
+
+    tokens.push(Token {
+        kind: Kind::End,
+        span: input.len()..input.len(),
+    });
+    Ok(tokens)
+}
+
+/// Read a symbol starting with `first`, consuming the rest of it.
+fn symbol(first: char, chars: &mut Peekable<CharIndices<'_>>) -> Option<&'static str> {
+    SYMBOLS.into_iter().find(|symbol| {
+        let mut expected = symbol.chars();
+        expected.next() == Some(first)
+            && expected
+                .next()
+                .is_none_or(|second| chars.next_if(|&(_, c)| c == second).is_some())
+    })
+}
+
+/// Consume characters while `predicate` holds, returning them preceded by `first`.
+fn take_while(
+    first: char,
+    chars: &mut Peekable<CharIndices<'_>>,
+    predicate: impl Fn(char) -> bool,
+) -> String {
+    let mut text = String::from(first);
+    while let Some((_, c)) = chars.next_if(|&(_, c)| predicate(c)) {
+        text.push(c);
+    }
+    text
+}
+
+/// Read a string literal following its opening quote.
+///
+/// # Errors
+///
+/// Fails if the string is unterminated or contains an invalid escape sequence.
+fn string(input: &str, chars: &mut Peekable<CharIndices<'_>>) -> Result<String, ParseError> {
+    let unterminated = ParseError::Syntax {
+        span: input.len()..input.len(),
+        expected: "`\"`",
+    };
+    let mut value = String::new();
+
+    loop {
+        let Some((i, c)) = chars.next() else {
+            return Err(unterminated);
+        };
+        match c {
+            '"' => return Ok(value),
+            '\\' => {
+                let Some((_, escape)) = chars.next() else {
+                    return Err(unterminated);
+                };
+                let end = chars.peek().map_or(input.len(), |&(j, _)| j);
+                value.push(match escape {
+                    '"' => '"',
+                    '\'' => '\'',
+                    '\\' => '\\',
+                    '0' => '\0',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'u' => unicode(input, chars, i)?,
+                    _ => return Err(ParseError::Escape(i..end)),
+                });
+            },
+            _ => value.push(c),
+        }
+    }
+}
+
+/// Read the `{...}` part of a `\u{...}` escape sequence starting at `start`.
+///
+/// # Errors
+///
+/// Fails if the escape sequence is malformed or not a valid character.
+fn unicode(
+    input: &str,
+    chars: &mut Peekable<CharIndices<'_>>,
+    start: usize,
+) -> Result<char, ParseError> {
+    let opened = chars.next_if(|&(_, c)| c == '{').is_some();
+    let mut digits = String::new();
+    while let Some((_, c)) = chars.next_if(|&(_, c)| c.is_ascii_hexdigit()) {
+        digits.push(c);
+    }
+    let closed = chars.next_if(|&(_, c)| c == '}').is_some();
+    let end = chars.peek().map_or(input.len(), |&(i, _)| i);
+
+    u32::from_str_radix(&digits, 16)
+        .ok()
+        .filter(|_| opened && closed)
+        .and_then(char::from_u32)
+        .ok_or(ParseError::Escape(start..end))
+}
+
+/// A recursive descent parser over tokens.
+struct Parser {
+    /// The tokens, ending with [`Kind::End`].
+    tokens: Vec<Token>,
+    /// The index of the next token.
+    position: usize,
+    /// The number of queries currently being parsed within each other.
+    depth: usize,
+}
+
+/// The maximum nesting depth of queries, limiting recursion such that untrusted input can not
+/// overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+impl Parser {
+    /// The next token.
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position]
+    }
+
+    /// Whether the token after the next one is `kind`.
+    fn second_is(&self, kind: &Kind) -> bool {
+        self.tokens
+            .get(self.position + 1)
+            .is_some_and(|token| token.kind == *kind)
+    }
+
+    /// Move past the next token, unless it is [`Kind::End`].
+    fn advance(&mut self) {
+        if self.peek().kind != Kind::End {
+            self.position += 1;
+        }
+    }
+
+    /// Consume the next token if it is `kind`.
+    fn eat(&mut self, kind: &Kind) -> bool {
+        let matches = self.peek().kind == *kind;
+        if matches {
+            self.advance();
+        }
+        matches
+    }
+
+    /// Consume the next token, which should be `kind`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the next token is not `kind`.
+    fn expect(&mut self, kind: &Kind, expected: &'static str) -> Result<(), ParseError> {
+        if self.eat(kind) {
+            Ok(())
+        } else {
+            Err(self.error(expected))
+        }
+    }
+
+    /// An error at the next token.
+    fn error(&self, expected: &'static str) -> ParseError {
+        ParseError::Syntax {
+            span: self.peek().span.clone(),
+            expected,
+        }
+    }
+
+    /// Parse a disjunction: `query | query | ...`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input does not follow the syntax.
+    fn query(&mut self) -> Result<DynQuery, ParseError> {
+        self.junction("|", Self::exclusive, DynQuery::Any)
+    }
+
+    /// Parse an exclusive disjunction: `query ^ query ^ ...`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input does not follow the syntax.
+    fn exclusive(&mut self) -> Result<DynQuery, ParseError> {
+        let mut lhs = self.conjunction()?;
+        while self.eat(&Kind::Symbol("^")) {
+            lhs = DynQuery::Xor(Box::new(lhs), Box::new(self.conjunction()?));
+        }
+        Ok(lhs)
+    }
+
+    /// Parse a conjunction: `query & query & ...`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input does not follow the syntax.
+    fn conjunction(&mut self) -> Result<DynQuery, ParseError> {
+        self.junction("&", Self::unary, DynQuery::All)
+    }
+
+    /// Parse operands of `symbol` using `operand`, joining them using `join` if there are several.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input does not follow the syntax.
+    fn junction(
+        &mut self,
+        symbol: &'static str,
+        operand: fn(&mut Self) -> Result<DynQuery, ParseError>,
+        join: fn(Vec<DynQuery>) -> DynQuery,
+    ) -> Result<DynQuery, ParseError> {
+        let mut operands = vec![operand(self)?];
+        while self.eat(&Kind::Symbol(symbol)) {
+            operands.push(operand(self)?);
+        }
+        Ok(if operands.len() == 1 {
+            operands.remove(0)
+        } else {
+            join(operands)
+        })
+    }
+
+    /// Parse a possibly negated primary query.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input does not follow the syntax, or if queries are nested more than
+    /// [`MAX_DEPTH`] levels deep.
+    fn unary(&mut self) -> Result<DynQuery, ParseError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error("a less deeply nested query"));
+        }
+        self.depth += 1;
+        let query = self.nested();
+        self.depth -= 1;
+        query
+    }
+
+    /// Parse a possibly negated primary query, one level deeper than the enclosing query.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input does not follow the syntax.
+    fn nested(&mut self) -> Result<DynQuery, ParseError> {
+        if self.eat(&Kind::Symbol("!")) {
+            return Ok(DynQuery::Not(Box::new(self.unary()?)));
+        }
+        if self.eat(&Kind::Symbol("(")) {
+            let query = self.query()?;
+            self.expect(&Kind::Symbol(")"), "`)`")?;
+            return Ok(query);
+        }
+
+        let Kind::Ident(ident) = &self.peek().kind else {
+            return Err(self.error("a query"));
+        };
+        let variadic: Option<fn(_) -> _> = match ident.as_str() {
+            "all" => Some(DynQuery::All),
+            "any" => Some(DynQuery::Any),
+            "one" => Some(DynQuery::One),
+            "true" => {
+                self.advance();
+                return Ok(DynQuery::True);
+            },
+            _ => None,
+        };
+        match variadic {
+            Some(variadic) if self.second_is(&Kind::Symbol("(")) => {
+                self.advance();
+                self.advance();
+                Ok(variadic(self.list(")", Self::query)?))
+            },
+            _ => self.predicate(),
+        }
+    }
+
+    /// Parse a predicate on a field: a comparison or a method call.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input does not follow the syntax.
+    fn predicate(&mut self) -> Result<DynQuery, ParseError> {
+        let mut field = self.ident("a field")?;
+        while self.eat(&Kind::Symbol(".")) {
+            let span = self.peek().span.clone();
+            let ident = self.ident("a field or method")?;
+            if self.eat(&Kind::Symbol("(")) {
+                return self.method(field, &ident, span);
+            }
+            field.push('.');
+            field.push_str(&ident);
+        }
+
+        let Kind::Symbol(operator @ ("=" | "!=" | ">" | "<" | ">=" | "<=")) = self.peek().kind
+        else {
+            return Err(self.error("a comparison operator or method"));
+        };
+        self.advance();
+        let value = self.value()?;
+        Ok(match operator {
+            "=" => DynQuery::Eq { field, value },
+            "!=" => DynQuery::Ne { field, value },
+            ">" => DynQuery::Gt { field, value },
+            "<" => DynQuery::Lt { field, value },
+            ">=" => DynQuery::Ge { field, value },
+            _ => DynQuery::Le { field, value },
+        })
+    }
+
+    /// Parse the arguments of the method `method` on `field`, following the opening parenthesis.
+    /// `span` is the span of the method name.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the method does not exist or the input does not follow the syntax.
+    fn method(
+        &mut self,
+        field: String,
+        method: &str,
+        span: Range<usize>,
+    ) -> Result<DynQuery, ParseError> {
+        if method == "is_in" {
+            self.expect(&Kind::Symbol("["), "`[`")?;
+            let values = self.list("]", Self::value)?;
+            self.expect(&Kind::Symbol(")"), "`)`")?;
+            return Ok(DynQuery::In { field, values });
+        }
+
+        if !matches!(
+            method,
+            "starts_with" | "contains" | "ends_with" | "eq_ignore_case"
+        ) {
+            return Err(ParseError::Syntax {
+                span,
+                expected: "a known method",
+            });
+        }
+        let value = self.string()?;
+        self.expect(&Kind::Symbol(")"), "`)`")?;
+        Ok(match method {
+            "eq_ignore_case" => DynQuery::EqIgnoreCase { field, value },
+            "starts_with" => DynQuery::StartsWith {
+                field,
+                value,
+                ignore_case: self.ignore_case()?,
+            },
+            "contains" => DynQuery::Contains {
+                field,
+                value,
+                ignore_case: self.ignore_case()?,
+            },
+            _ => DynQuery::EndsWith {
+                field,
+                value,
+                ignore_case: self.ignore_case()?,
+            },
+        })
+    }
+
+    /// Parse an optional `.ignore_case()` suffix.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the suffix is malformed.
+    fn ignore_case(&mut self) -> Result<bool, ParseError> {
+        if !self.eat(&Kind::Symbol(".")) {
+            return Ok(false);
+        }
+        self.expect(&Kind::Ident("ignore_case".to_owned()), "`ignore_case`")?;
+        self.expect(&Kind::Symbol("("), "`(`")?;
+        self.expect(&Kind::Symbol(")"), "`)`")?;
+        Ok(true)
+    }
+
+    /// Parse comma-separated items using `item` until `close`, consuming it.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the input does not follow the syntax.
+    fn list<I>(
+        &mut self,
+        close: &'static str,
+        item: fn(&mut Self) -> Result<I, ParseError>,
+    ) -> Result<Vec<I>, ParseError> {
+        let mut items = Vec::new();
+        while !self.eat(&Kind::Symbol(close)) {
+            if !items.is_empty() {
+                self.expect(&Kind::Symbol(","), "`,`")?;
+            }
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Parse an identifier.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the next token is not an identifier.
+    fn ident(&mut self, expected: &'static str) -> Result<String, ParseError> {
+        let Kind::Ident(ident) = &self.peek().kind else {
+            return Err(self.error(expected));
+        };
+        let ident = ident.clone();
+        self.advance();
+        Ok(ident)
+    }
+
+    /// Parse a string literal.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the next token is not a string.
+    fn string(&mut self) -> Result<String, ParseError> {
+        let Kind::Str(value) = &self.peek().kind else {
+            return Err(self.error("a string"));
+        };
+        let value = value.clone();
+        self.advance();
+        Ok(value)
+    }
+
+    /// Parse a value: a string, number or boolean.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the next token is not a value.
+    fn value(&mut self) -> Result<Value, ParseError> {
+        let value = match &self.peek().kind {
+            Kind::Str(value) => Value::String(value.clone()),
+            Kind::Number(value) => Value::Number(value.clone()),
+            Kind::Ident(ident) if ident == "true" => Value::Bool(true),
+            Kind::Ident(ident) if ident == "false" => Value::Bool(false),
+            Kind::Ident(_) | Kind::Symbol(_) | Kind::End => return Err(self.error("a value")),
+        };
+        self.advance();
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    clippy::unwrap_used,
+    reason = "Panics simply indicate failed tests."
+)]
+mod tests {
+    use super::*;
+    use crate::query::{
+        Queryable,
+        combinators::{And, Not, Xor},
+    };
+    use serde_json::json;
+
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestBook {
+        title: String,
+        author: String,
+        year: u16,
+    }
+
+    impl TestBook {
+        fn new(title: &str, author: &str, year: u16) -> Self {
+            Self {
+                title: title.to_owned(),
+                author: author.to_owned(),
+                year,
+            }
+        }
+    }
+
+    fn dyn_query(input: &str) -> DynQuery {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            dyn_query(r#"!title = "a" | year > 1 & year < 2 ^ true"#),
+            DynQuery::Any(vec![
+                DynQuery::Not(Box::new(DynQuery::Eq {
+                    field: "title".to_owned(),
+                    value: json!("a"),
+                })),
+                DynQuery::Xor(
+                    Box::new(DynQuery::All(vec![
+                        DynQuery::Gt {
+                            field: "year".to_owned(),
+                            value: json!(1),
+                        },
+                        DynQuery::Lt {
+                            field: "year".to_owned(),
+                            value: json!(2),
+                        },
+                    ])),
+                    Box::new(DynQuery::True),
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn methods_and_values() {
+        assert_eq!(
+            dyn_query(
+                r#"all(
+                    author.name.starts_with("G\u{e9}\"").ignore_case(),
+                    title.eq_ignore_case("x"),
+                    year.is_in([-1, 2.5e3, false]),
+                    any()
+                )"#
+            ),
+            DynQuery::All(vec![
+                DynQuery::StartsWith {
+                    field: "author.name".to_owned(),
+                    value: "G\u{e9}\"".to_owned(),
+                    ignore_case: true,
+                },
+                DynQuery::EqIgnoreCase {
+                    field: "title".to_owned(),
+                    value: "x".to_owned(),
+                },
+                DynQuery::In {
+                    field: "year".to_owned(),
+                    values: vec![json!(-1), json!(2500.0), json!(false)],
+                },
+                DynQuery::Any(Vec::new()),
+            ])
+        );
+    }
+
+    #[test]
+    fn debug_round_trip() {
+        let books = [
+            TestBook::new("1984", "George Orwell", 1949),
+            TestBook::new("Animal Farm", "George Orwell", 1945),
+            TestBook::new("Emma", "Jane Austen", 1815),
+        ];
+        let years = [1815, 1945];
+        let query = Xor(
+            And(
+                TestBook::author().eq("George Orwell"),
+                Not(TestBook::title().contains("farm").ignore_case()),
+            ),
+            TestBook::year().is_in(&years),
+        );
+        let text = format!("{query:#?}");
+
+        let parsed = parse::<TestBook>(&text).unwrap();
+
+        for book in &books {
+            assert_eq!(parsed.evaluate(book), query.evaluate(book));
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let span = |input: &str| input.parse::<DynQuery>().unwrap_err().span();
+
+        assert_eq!(span(r#"title = "a" &"#), Some(13..13));
+        assert_eq!(span("title ~ 1"), Some(6..7));
+        assert_eq!(span("title = 1 1"), Some(10..11));
+        assert_eq!(span(r#"title = "\q""#), Some(9..11));
+        assert_eq!(span("year = 1-2"), Some(7..10));
+        assert_eq!(span(r#"title.foo("a")"#), Some(6..9));
+        assert_eq!(span("(true"), Some(5..5));
+        assert_eq!(
+            span(&format!("{}true", "!".repeat(200_000))),
+            Some(128..129)
+        );
+        assert_eq!(
+            span(&format!("{}true", "(".repeat(200_000))),
+            Some(128..129)
+        );
+        assert_eq!(
+            span(&format!("{}true", "any(".repeat(200_000))),
+            Some(512..515)
+        );
+        // `true` is the 128th level.
+        drop(
+            format!("{}true{}", "!(".repeat(63), ")".repeat(63))
+                .parse::<DynQuery>()
+                .unwrap(),
+        );
+        for modified in [
+            "order_by(year > 1800, year.desc())",
+            "limit(year > 1800, 5)",
+            "offset(year > 1800, 10)",
+        ] {
+            assert!(matches!(
+                modified.parse::<DynQuery>(),
+                Err(ParseError::Syntax { .. })
+            ));
+        }
+        assert!(matches!(
+            parse::<TestBook>("isbn = 1"),
+            Err(ParseError::Resolve(ResolveError::UnknownField(field))) if field == "isbn"
+        ));
+    }
+}
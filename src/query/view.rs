@@ -0,0 +1,219 @@
+//! Introspection of queries, allowing translation outside of this crate.
+//!
+//! The built-in translations are methods of [`Query`], which connectors outside of this crate can
+//! not extend. Instead, they can walk the structure of a query and translate it themselves:
+//!
+//! - [`Node::of`] exposes the logical structure of a query, that is, its conjunctions,
+//!   disjunctions and negations. Comparisons spanning several operators, like
+//!   [`Between`](super::combinators::Between), are expanded.
+//! - At the leaves, [`Query::primitive`] describes comparisons between a field and values as a
+//!   [`Primitive`]: the field name, the [`Operator`] and the [`Operand`]s.
+//!
+//! Leaves without a primitive are opaque, and can only be evaluated locally using
+//! [`Query::evaluate`]. A translator would typically leave them as residue, as the built-in
+//! translations do with [`Single::residue`](super::Single::residue).
+//!
+//! [`Node::of`]: super::Node::of
+//! [`Query`]: super::Query
+//! [`Query::primitive`]: super::Query::primitive
+//! [`Query::evaluate`]: super::Query::evaluate
+
+use serde::{Serialize, Serializer, ser::Error as _};
+use serde_json::{Error as JsonError, Value, to_value};
+use std::fmt::{Debug, Display};
+
+/// A comparison between a field and values, as returned by
+/// [`Query::primitive`](super::Query::primitive).
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Primitive<'a> {
+    /// The name of the field, as given by [`Field`](super::Field).
+    pub field: &'a str,
+    /// The comparison.
+    pub operator: Operator,
+    /// The values compared to. See [`Operator`] for how many there are.
+    pub operands: Vec<Box<dyn Operand + 'a>>,
+}
+
+impl<'a> Primitive<'a> {
+    /// Describe a comparison of `field` to `operands`.
+    #[must_use]
+    #[inline]
+    pub fn new(field: &'a str, operator: Operator, operands: Vec<Box<dyn Operand + 'a>>) -> Self {
+        Self {
+            field,
+            operator,
+            operands,
+        }
+    }
+}
+
+/// The comparisons described by a [`Primitive`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Operator {
+    /// The field is equal to the single operand.
+    Eq,
+    /// The field is not equal to the single operand.
+    Ne,
+    /// The field is greater than the single operand.
+    Gt,
+    /// The field is lesser than the single operand.
+    Lt,
+    /// The field is greater than or equal to the single operand.
+    Ge,
+    /// The field is lesser than or equal to the single operand.
+    Le,
+    /// The field is between the two operands, inclusive. The lower bound comes first.
+    Between,
+    /// The field is equal to any of the operands, of which there may be any number.
+    In,
+    /// The string field starts with the single operand.
+    StartsWith {
+        /// Whether to ignore case when matching.
+        ignore_case: bool,
+    },
+    /// The string field contains the single operand.
+    Contains {
+        /// Whether to ignore case when matching.
+        ignore_case: bool,
+    },
+    /// The string field ends with the single operand.
+    EndsWith {
+        /// Whether to ignore case when matching.
+        ignore_case: bool,
+    },
+    /// The string field is equal to the single operand, ignoring case.
+    EqIgnoreCase,
+    /// The optional field is [`None`]. There are no operands.
+    IsNone,
+    /// The optional field is [`Some`]. There are no operands.
+    IsSome,
+}
+
+/// A value in a [`Primitive`].
+///
+/// Its [`Display`] and [`Serialize`] implementations are the formats used in HTTP queries, as
+/// chosen by [`ValueFormat`](super::http::ValueFormat), and its [`Debug`] implementation the one
+/// used by the alternate format of queries.
+pub trait Operand: Debug + Display + Sync {
+    /// The value as serialized by its [`Serialize`] implementation.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the value fails to serialize.
+    fn to_json(&self) -> Result<Value, JsonError>;
+}
+
+impl<V> Operand for V
+where
+    V: Debug + Display + Serialize + Sync + ?Sized,
+{
+    #[inline]
+    fn to_json(&self) -> Result<Value, JsonError> {
+        to_value(self)
+    }
+}
+
+impl Serialize for dyn Operand + '_ {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json()
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    clippy::unwrap_used,
+    reason = "Panics simply indicate failed tests."
+)]
+mod tests {
+    use super::*;
+    use crate::query::{
+        Node, Query, Queryable,
+        combinators::{All, Not, One},
+    };
+
+    #[derive(Clone, Debug, PartialEq, Queryable)]
+    struct TestBook {
+        title: String,
+        year: u16,
+    }
+
+    /// A translator for a made-up language, leaving opaque predicates as residue.
+    fn translate<'a>(
+        node: &'a Node<'a, TestBook>,
+        residue: &mut Vec<&'a (dyn Query<TestBook> + Sync)>,
+    ) -> String {
+        let mut join = |nodes: &'a [Node<'a, TestBook>], separator| {
+            let terms = nodes.iter().map(|operand| translate(operand, residue));
+            format!("[{}]", terms.collect::<Vec<_>>().join(separator))
+        };
+
+        match node {
+            Node::True => "TRUE".to_owned(),
+            Node::False => "FALSE".to_owned(),
+            Node::Not(operand) => format!("NOT {}", translate(operand, residue)),
+            Node::And(nodes) => join(nodes, " AND "),
+            Node::Or(nodes) => join(nodes, " OR "),
+            Node::Leaf { predicate, .. } => {
+                let Some(primitive) = predicate.get().primitive() else {
+                    residue.push(predicate.get());
+                    return "TRUE".to_owned();
+                };
+                let operands = primitive.operands.iter().map(ToString::to_string);
+                format!(
+                    "{} {:?} {}",
+                    primitive.field,
+                    primitive.operator,
+                    operands.collect::<Vec<_>>().join(" ")
+                )
+            },
+        }
+    }
+
+    #[test]
+    fn primitive() {
+        let years = [1811, 1815];
+        let query = TestBook::year().is_in(&years);
+        let primitive = query.primitive().unwrap();
+
+        assert_eq!(primitive.field, "year");
+        assert_eq!(primitive.operator, Operator::In);
+        assert_eq!(format!("{:?}", primitive.operands), "[1811, 1815]");
+        assert_eq!(
+            TestBook::title()
+                .contains("mm")
+                .ignore_case()
+                .primitive()
+                .unwrap()
+                .operator,
+            Operator::Contains { ignore_case: true }
+        );
+        assert!(Not(TestBook::year().eq(&1815)).primitive().is_none());
+    }
+
+    #[test]
+    fn external_translation() {
+        let query: All<Box<dyn Query<TestBook> + Sync>> = All(vec![
+            Box::new(TestBook::title().starts_with("E")),
+            Box::new(Not(TestBook::year().between(&1800, &1900))),
+            Box::new(One(vec![TestBook::year().lt(&0)])),
+        ]);
+        let node = Node::of(&query);
+        let mut residue = Vec::new();
+
+        let translated = translate(&node, &mut residue);
+
+        assert_eq!(
+            translated,
+            "[title StartsWith { ignore_case: false } E AND NOT [year Ge 1800 AND year Le 1900] \
+             AND TRUE]"
+        );
+        assert_eq!(residue.len(), 1);
+        assert_eq!(format!("{:#?}", residue[0]), "one(year < 0)");
+    }
+}
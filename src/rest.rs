@@ -2,21 +2,47 @@
 
 use crate::{
     Query,
-    connector::{Sink, Source},
+    connector::{Sink, Source, fetch_owned_eager},
     encode::{Codec, Decode, Encode},
     errors::{ConnectionError, DecodeError, FetchError, FetchOneError, SendError},
-    query::{HttpQuery, Single},
+    query::{HttpConvention, HttpQuery, Single, http::dialect::HttpDialect},
+    retry::{Failure, Policy},
 };
 use async_trait::async_trait;
-use futures::{StreamExt as _, TryStreamExt as _, future::ready, stream::BoxStream};
-use reqwest::{Body, Client, Method, Response, Url};
+use futures::{
+    StreamExt as _, TryStreamExt as _,
+    future::ready,
+    stream::{BoxStream, iter as from_iter, try_unfold},
+};
+use httpdate::parse_http_date;
+use reqwest::{Body, Client, Method, Request, Response, StatusCode, Url, header::RETRY_AFTER};
 use std::any::Any;
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use std::{io::Error as IoError, marker::PhantomData};
 
 /// The [`Builder`], used to construct REST connectors more flexibly.
 mod builder;
 pub use builder::*;
 
+/// [`Authentication`], included with every request.
+mod auth;
+pub use auth::*;
+
+/// [`TokenProvider`]s, such as the OAuth 2.0 [`ClientCredentials`] flow.
+mod token;
+pub use token::*;
+
+/// Client-side rate limiting using a [`RateLimiter`].
+mod throttle;
+pub use throttle::RateLimiter;
+
+/// [`Pagination`] of results.
+mod pagination;
+use pagination::{ForeignLink, PageRequest, Target};
+pub use pagination::{PageStrategy, Pagination};
+
 /// A source to work with REST APIs.
 ///
 /// This makes no assumption about the format used to communicate with the API, but delegates this
@@ -32,8 +58,20 @@ pub struct ReadOnly<T, D> {
     method: Method,
     /// The client used to execute requests.
     client: Client,
+    /// The credentials included with every request.
+    auth: Authentication,
+    /// How failed requests are retried.
+    retry: Policy,
+    /// Limits the rate of requests, if set.
+    limiter: Option<RateLimiter>,
     /// The decoder used to deserialize received data.
     decoder: D,
+    /// The conventions used to translate queries into parameters.
+    convention: HttpConvention,
+    /// The dialect used to translate queries.
+    dialect: Arc<dyn HttpDialect<T>>,
+    /// How results are split into pages.
+    pagination: Pagination,
     /// Satisfies missing fields using `T`.
     // TODO: This may be overly restrictive when considering variance. Improve using unstable
     // `phantom_variance_markers` (#135806)?
@@ -52,6 +90,12 @@ pub struct WriteOnly<T, E> {
     method: Method,
     /// The client used to execute requests.
     client: Client,
+    /// The credentials included with every request.
+    auth: Authentication,
+    /// How failed requests are retried.
+    retry: Policy,
+    /// Limits the rate of requests, if set.
+    limiter: Option<RateLimiter>,
     /// The encoder used to serialize data to be sent.
     encoder: E,
     /// Satisfies missing fields using `T`.
@@ -79,21 +123,63 @@ pub struct ReadWrite<T, E, D, C> {
     sink_method: Method,
     /// The client used to execute requests.
     client: Client,
+    /// The credentials included with every request.
+    auth: Authentication,
+    /// How failed requests are retried.
+    retry: Policy,
+    /// Limits the rate of requests, if set.
+    limiter: Option<RateLimiter>,
     /// The codec used to serialize and deserialize data.
     codec: Codec<T, E, D, C>,
+    /// The conventions used to translate queries into parameters.
+    convention: HttpConvention,
+    /// The dialect used to translate queries.
+    dialect: Arc<dyn HttpDialect<T>>,
+    /// How results are split into pages.
+    pagination: Pagination,
     /// Satisfies missing fields using `T`.
     // TODO: This may be overly restrictive when considering variance. Improve using unstable
     // `phantom_variance_markers` (#135806)?
     _phantom: PhantomData<T>,
 }
 
+/// What is needed to execute requests: the client, credentials, retry policy and rate limiter.
+type Transport<'a> = (
+    &'a Client,
+    &'a Authentication,
+    &'a Policy,
+    Option<&'a RateLimiter>,
+);
+
+impl<T, D> ReadOnly<T, D> {
+    /// The [`Transport`] used for all requests.
+    const fn transport(&self) -> Transport<'_> {
+        (&self.client, &self.auth, &self.retry, self.limiter.as_ref())
+    }
+}
+
+impl<T, E> WriteOnly<T, E> {
+    /// The [`Transport`] used for all requests.
+    const fn transport(&self) -> Transport<'_> {
+        (&self.client, &self.auth, &self.retry, self.limiter.as_ref())
+    }
+}
+
+impl<T, E, D, C> ReadWrite<T, E, D, C> {
+    /// The [`Transport`] used for all requests.
+    const fn transport(&self) -> Transport<'_> {
+        (&self.client, &self.auth, &self.retry, self.limiter.as_ref())
+    }
+}
+
 /// Helper to use for [`Source`] implementation.
 ///
 /// # Errors
 ///
-/// Fails if an error occurs during connection or if the query fails to serialize.
+/// Fails if an error occurs during connection, if the server responds with an error or if the query
+/// fails to serialize.
 async fn fetch_impl(
-    client: &Client,
+    (client, auth, retry, limiter): Transport<'_>,
     url: Url,
     method: Method,
     query: HttpQuery<'_>,
@@ -101,22 +187,26 @@ async fn fetch_impl(
     // `RequestBuilder::build` also fails is the URL cannot be parsed. Although
     // `<Url as IntoUrl>::into_url` can fail, it has already been validated that this will not
     // happen here. Hence, any error here stems from the query.
-    let request = client
-        .request(method, url)
+    let request = auth
+        .apply(client, method, url)
         .query(&query)
         .build()
-        .map_err(|err| FetchError::InvalidQuery(Box::new(err)))?;
-    client.execute(request).await.map_err(Into::into)
+        .map_err(|err| FetchError::InvalidQuery(Box::new(err.without_url())))?;
+    // Fetching has no side effects, regardless of the method used.
+    execute((client, auth, retry, limiter), request, true)
+        .await
+        .map_err(Into::into)
 }
 
 #[expect(clippy::missing_panics_doc, reason = "See implementation.")]
-/// Helper to use for [`Sink`] implementation.
+/// Helper to use for [`Sink`] implementation. Requests are only retried if `method` is idempotent,
+/// unless the policy allows otherwise.
 ///
 /// # Errors
 ///
-/// Fails if an error occurs during connection.
+/// Fails if an error occurs during connection or if the server responds with an error.
 async fn send_impl<B>(
-    client: &Client,
+    (client, auth, retry, limiter): Transport<'_>,
     url: Url,
     method: Method,
     body: B,
@@ -124,6 +214,7 @@ async fn send_impl<B>(
 where
     B: Into<Body>,
 {
+    let idempotent = method.is_idempotent();
     #[allow(
         clippy::unwrap_used,
         reason = "
@@ -132,8 +223,185 @@ where
             construction that this will not happen here.
         "
     )]
-    let request = client.request(method, url).body(body).build().unwrap();
-    client.execute(request).await.map_err(Into::into)
+    let request = auth.apply(client, method, url).body(body).build().unwrap();
+    execute((client, auth, retry, limiter), request, idempotent).await
+}
+
+#[expect(clippy::missing_panics_doc, reason = "See implementation.")]
+/// Execute `request`, retrying it as specified by `retry`. Requests that are not `idempotent` are
+/// only retried if the policy allows it.
+///
+/// # Errors
+///
+/// Fails if the last attempt fails, either during connection or with an error response.
+async fn execute(
+    (client, auth, retry, limiter): Transport<'_>,
+    request: Request,
+    idempotent: bool,
+) -> Result<Response, ConnectionError> {
+    retry
+        .run(|| {
+            let attempt = request
+                .try_clone()
+                .expect("Bodies are buffered, so requests can always be cloned.");
+            async move {
+                if let Some(limiter) = limiter {
+                    limiter.acquire().await;
+                }
+                let response = auth.execute(client, attempt).await.map_err(|err| {
+                    let retryable = retry.retries(&err, idempotent);
+                    Failure::new(err, retryable)
+                })?;
+                let retry_after = retry_after(&response);
+                check_status(response).map_err(|err| Failure {
+                    retryable: retry.retries(&err, idempotent),
+                    error: err,
+                    retry_after,
+                })
+            }
+        })
+        .await
+}
+
+/// The delay requested using the `Retry-After` header of `response`, either in seconds or as a
+/// date. The header is only honoured for the codes 429 (Too Many Requests) and 503 (Service
+/// Unavailable).
+fn retry_after(response: &Response) -> Option<Duration> {
+    if !matches!(
+        response.status(),
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+    ) {
+        return None;
+    }
+
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    value.parse().map(Duration::from_secs).ok().or_else(|| {
+        let date = parse_http_date(value).ok()?;
+        Some(date.duration_since(SystemTime::now()).unwrap_or_default())
+    })
+}
+
+/// Helper to use for [`Source`] implementations when paginating, lazily fetching the pages of the
+/// results of `query` and decoding them using `decoder`.
+fn fetch_pages<'a, T, D>(
+    transport: Transport<'a>,
+    target: (&'a Url, &'a Method),
+    query: HttpQuery<'a>,
+    decoder: &'a D,
+    pagination: &'a Pagination,
+) -> BoxStream<'a, Result<T, FetchError>>
+where
+    T: Send + 'a,
+    D: Decode<T> + Sync,
+{
+    try_unfold(Some(PageRequest::default()), move |request| {
+        let page_query = query.clone();
+        async move {
+            let Some(request) = request else {
+                return Ok::<_, FetchError>(None);
+            };
+            let page =
+                fetch_page(transport, target, page_query, decoder, pagination, &request).await?;
+            Ok(page.map(|(entries, next)| (from_iter(entries.into_iter().map(Ok)), next)))
+        }
+    })
+    .try_flatten()
+    .boxed()
+}
+
+/// Helper to use for the [`Source::fetch_owned`] implementations of paginated sources.
+///
+/// Like [`fetch_pages`], pages are fetched lazily, but the stream owns `source` and `query`.
+/// `parts` borrows what is needed to fetch a page from the source: its transport, URL and method,
+/// decoder, pagination, dialect and convention.
+///
+/// The residue would borrow the query, so the query is translated for every page, and entries are
+/// filtered by evaluating the entire query instead.
+fn fetch_pages_owned<'a, T, S, D, Q>(
+    source: S,
+    query: Q,
+    parts: for<'s> fn(&'s S) -> PageParts<'s, T, D>,
+) -> BoxStream<'a, Result<T, FetchError>>
+where
+    T: Send + 'a,
+    S: Send + 'a,
+    D: Decode<T> + Sync + 'a,
+    Q: Query<T> + Send + 'a,
+{
+    try_unfold(
+        (source, query, Some(PageRequest::default())),
+        move |(source, query, request)| async move {
+            let Some(request) = request else {
+                return Ok::<_, FetchError>(None);
+            };
+            let (transport, target, decoder, pagination, dialect, convention) = parts(&source);
+            let Single {
+                query: page_query, ..
+            } = dialect.translate(&query, convention);
+            let Some((entries, next)) =
+                fetch_page(transport, target, page_query, decoder, pagination, &request).await?
+            else {
+                return Ok(None);
+            };
+            let matching = entries
+                .into_iter()
+                .filter(|entry| query.evaluate(entry))
+                .map(Ok)
+                .collect::<Vec<_>>();
+            Ok(Some((from_iter(matching), (source, query, next))))
+        },
+    )
+    .try_flatten()
+    .boxed()
+}
+
+/// What [`fetch_pages_owned`] borrows from a source to fetch a page.
+type PageParts<'s, T, D> = (
+    Transport<'s>,
+    (&'s Url, &'s Method),
+    &'s D,
+    &'s Pagination,
+    &'s dyn HttpDialect<T>,
+    &'s HttpConvention,
+);
+
+/// Fetch the page of the results of `query` described by `request`, returning its entries and the
+/// request for the following page, or [`None`] if there are no more pages.
+///
+/// # Errors
+///
+/// Fails if the page can not be fetched or decoded, or if it is linked at a different origin.
+async fn fetch_page<T, D>(
+    transport: Transport<'_>,
+    (url, method): (&Url, &Method),
+    mut query: HttpQuery<'_>,
+    decoder: &D,
+    pagination: &Pagination,
+    request: &PageRequest,
+) -> Result<Option<(Vec<T>, Option<PageRequest>)>, FetchError>
+where
+    D: Decode<T> + Sync,
+{
+    let response = match pagination.target(request) {
+        None => return Ok(None),
+        Some(Target::Query(params)) => {
+            query.extend(
+                params
+                    .into_iter()
+                    .map(|(name, value)| (Cow::Owned(name.into()), value.into())),
+            );
+            fetch_impl(transport, url.clone(), method.clone(), query).await?
+        },
+        // The link already includes the query.
+        Some(Target::Link(link)) if link.origin() == url.origin() => {
+            fetch_impl(transport, link, method.clone(), HttpQuery::new()).await?
+        },
+        Some(Target::Link(link)) => {
+            let origin = link.origin().ascii_serialization();
+            return Err(ConnectionError::Process(Box::new(ForeignLink(origin))).into());
+        },
+    };
+    pagination.page(request, response, decoder).await.map(Some)
 }
 
 // TODO: Add support for query translation strategies. Currently uses only `to_http_single`, and
@@ -159,16 +427,40 @@ where
     where
         T: 's,
     {
-        let Single { query, residue } = query.to_http_single();
+        // Shaping requires all results, so there is no benefit to streaming.
+        if !query.shape().is_unshaped() {
+            let entries = self.fetch_all(query).await?;
+            return Ok(from_iter(entries.into_iter().map(Ok)).boxed());
+        }
 
-        let bytes = fetch_impl(&self.client, self.url.clone(), self.method.clone(), query)
-            .await?
-            .bytes_stream()
-            .map_err(|err| {
-                // HTTP errors should be raised by `fetch_impl`, and already have been returned.
-                debug_assert!(err.status().is_none());
-                ConnectionError::Io(IoError::other(err))
-            });
+        let Single { query, residue } = self.dialect.translate(query, &self.convention);
+
+        if self.pagination.is_paginated() {
+            let pages = fetch_pages(
+                self.transport(),
+                (&self.url, &self.method),
+                query,
+                &self.decoder,
+                &self.pagination,
+            );
+            return Ok(pages
+                .try_filter(move |entry| ready(residue.iter().all(|part| part.evaluate(entry))))
+                .boxed());
+        }
+
+        let bytes = fetch_impl(
+            self.transport(),
+            self.url.clone(),
+            self.method.clone(),
+            query,
+        )
+        .await?
+        .bytes_stream()
+        .map_err(|err| {
+            // HTTP errors should be raised by `fetch_impl`, and already have been returned.
+            debug_assert!(err.status().is_none());
+            ConnectionError::Io(IoError::other(err.without_url()))
+        });
 
         let apply_residue = move |res| {
             ready(match res {
@@ -185,19 +477,76 @@ where
             .map_err(Into::into)
     }
 
+    /// If the source is paginated, pages are fetched lazily as with [`fetch`](Source::fetch).
+    /// Otherwise, or if the query is shaped, all entries are fetched when the stream is first
+    /// polled.
+    #[inline]
+    fn fetch_owned<'a, Q>(self, query: Q) -> BoxStream<'a, Result<T, FetchError>>
+    where
+        Self: Sized + Send + 'a,
+        Q: Query<T> + Send + 'a,
+        T: 'a,
+    {
+        // Shaping requires all results, and the entries decoded from a single response borrow the
+        // decoder.
+        if !self.pagination.is_paginated() || !query.shape().is_unshaped() {
+            return fetch_owned_eager(self, query);
+        }
+        fetch_pages_owned(self, query, |source| {
+            (
+                source.transport(),
+                (&source.url, &source.method),
+                &source.decoder,
+                &source.pagination,
+                &*source.dialect,
+                &source.convention,
+            )
+        })
+    }
+
     #[inline]
     async fn fetch_all(&mut self, query: &(dyn Query<T> + Sync)) -> Result<Vec<T>, FetchError> {
-        let Single { query, residue } = query.to_http_single();
+        let shape = query.shape();
+        let Single { mut query, residue } = self.dialect.translate(query, &self.convention);
+        // The limit and offset can only be applied by the source if it does all filtering, and
+        // would conflict with pagination.
+        let windowed = !self.pagination.is_paginated()
+            && residue.is_empty()
+            && self
+                .convention
+                .shape
+                .translate(&shape, &self.convention.fields, &mut query);
 
-        let bytes = fetch_impl(&self.client, self.url.clone(), self.method.clone(), query)
-            .await?
-            .bytes()
+        if self.pagination.is_paginated() {
+            let mut entries: Vec<_> = fetch_pages(
+                self.transport(),
+                (&self.url, &self.method),
+                query,
+                &self.decoder,
+                &self.pagination,
+            )
+            .try_filter(|entry| ready(residue.iter().all(|part| part.evaluate(entry))))
+            .try_collect()
             .await?;
+            shape.apply(&mut entries, windowed);
+            return Ok(entries);
+        }
+
+        let bytes = fetch_impl(
+            self.transport(),
+            self.url.clone(),
+            self.method.clone(),
+            query,
+        )
+        .await?
+        .bytes()
+        .await?;
 
         self.decoder
             .decode_all(&bytes)
             .map(|mut entries| {
                 entries.retain(|entry| residue.iter().all(|part| part.evaluate(entry)));
+                shape.apply(&mut entries, windowed);
                 entries
             })
             .map_err(|err| DecodeError(Box::new(err)).into())
@@ -205,16 +554,38 @@ where
 
     #[inline]
     async fn fetch_one(&mut self, query: &(dyn Query<T> + Sync)) -> Result<T, FetchOneError> {
-        let Single { query, residue } = query.to_http_single();
+        if self.pagination.is_paginated() {
+            return self
+                .fetch(query)
+                .await?
+                .try_next()
+                .await?
+                .ok_or(FetchOneError::NoSuchEntry);
+        }
+        if !query.shape().is_unshaped() {
+            return self
+                .fetch_all(query)
+                .await?
+                .into_iter()
+                .next()
+                .ok_or(FetchOneError::NoSuchEntry);
+        }
+
+        let Single { query, residue } = self.dialect.translate(query, &self.convention);
 
-        let bytes = fetch_impl(&self.client, self.url.clone(), self.method.clone(), query)
-            .await?
-            .bytes_stream()
-            .map_err(|err| {
-                // HTTP errors should be raised by `fetch_impl`, and already have been returned.
-                debug_assert!(err.status().is_none());
-                ConnectionError::Io(IoError::other(err))
-            });
+        let bytes = fetch_impl(
+            self.transport(),
+            self.url.clone(),
+            self.method.clone(),
+            query,
+        )
+        .await?
+        .bytes_stream()
+        .map_err(|err| {
+            // HTTP errors should be raised by `fetch_impl`, and already have been returned.
+            debug_assert!(err.status().is_none());
+            ConnectionError::Io(IoError::other(err.without_url()))
+        });
 
         // TODO: Fix this messy code.
 
@@ -253,10 +624,29 @@ where
     where
         T: 's,
     {
-        let Single { query, residue } = query.to_http_single();
+        // Shaping requires all results, so there is no benefit to streaming.
+        if !query.shape().is_unshaped() {
+            let entries = self.fetch_all(query).await?;
+            return Ok(from_iter(entries.into_iter().map(Ok)).boxed());
+        }
+
+        let Single { query, residue } = self.dialect.translate(query, &self.convention);
+
+        if self.pagination.is_paginated() {
+            let pages = fetch_pages(
+                self.transport(),
+                (&self.source_url, &self.source_method),
+                query,
+                &self.codec,
+                &self.pagination,
+            );
+            return Ok(pages
+                .try_filter(move |entry| ready(residue.iter().all(|part| part.evaluate(entry))))
+                .boxed());
+        }
 
         let bytes = fetch_impl(
-            &self.client,
+            self.transport(),
             self.source_url.clone(),
             self.source_method.clone(),
             query,
@@ -266,7 +656,7 @@ where
         .map_err(|err| {
             // HTTP errors should be raised by `fetch_impl`, and already have been returned.
             debug_assert!(err.status().is_none());
-            ConnectionError::Io(IoError::other(err))
+            ConnectionError::Io(IoError::other(err.without_url()))
         });
 
         let apply_residue = move |res| {
@@ -284,12 +674,63 @@ where
             .map_err(Into::into)
     }
 
+    /// If the source is paginated, pages are fetched lazily as with [`fetch`](Source::fetch).
+    /// Otherwise, or if the query is shaped, all entries are fetched when the stream is first
+    /// polled.
+    #[inline]
+    fn fetch_owned<'a, Q>(self, query: Q) -> BoxStream<'a, Result<T, FetchError>>
+    where
+        Self: Sized + Send + 'a,
+        Q: Query<T> + Send + 'a,
+        T: 'a,
+    {
+        // Shaping requires all results, and the entries decoded from a single response borrow the
+        // decoder.
+        if !self.pagination.is_paginated() || !query.shape().is_unshaped() {
+            return fetch_owned_eager(self, query);
+        }
+        fetch_pages_owned(self, query, |source| {
+            (
+                source.transport(),
+                (&source.source_url, &source.source_method),
+                &source.codec,
+                &source.pagination,
+                &*source.dialect,
+                &source.convention,
+            )
+        })
+    }
+
     #[inline]
     async fn fetch_all(&mut self, query: &(dyn Query<T> + Sync)) -> Result<Vec<T>, FetchError> {
-        let Single { query, residue } = query.to_http_single();
+        let shape = query.shape();
+        let Single { mut query, residue } = self.dialect.translate(query, &self.convention);
+        // The limit and offset can only be applied by the source if it does all filtering, and
+        // would conflict with pagination.
+        let windowed = !self.pagination.is_paginated()
+            && residue.is_empty()
+            && self
+                .convention
+                .shape
+                .translate(&shape, &self.convention.fields, &mut query);
+
+        if self.pagination.is_paginated() {
+            let mut entries: Vec<_> = fetch_pages(
+                self.transport(),
+                (&self.source_url, &self.source_method),
+                query,
+                &self.codec,
+                &self.pagination,
+            )
+            .try_filter(|entry| ready(residue.iter().all(|part| part.evaluate(entry))))
+            .try_collect()
+            .await?;
+            shape.apply(&mut entries, windowed);
+            return Ok(entries);
+        }
 
         let bytes = fetch_impl(
-            &self.client,
+            self.transport(),
             self.source_url.clone(),
             self.source_method.clone(),
             query,
@@ -302,6 +743,7 @@ where
             .decode_all(&bytes)
             .map(|mut entries| {
                 entries.retain(|entry| residue.iter().all(|part| part.evaluate(entry)));
+                shape.apply(&mut entries, windowed);
                 entries
             })
             .map_err(|err| DecodeError(Box::new(err)).into())
@@ -309,10 +751,27 @@ where
 
     #[inline]
     async fn fetch_one(&mut self, query: &(dyn Query<T> + Sync)) -> Result<T, FetchOneError> {
-        let Single { query, residue } = query.to_http_single();
+        if self.pagination.is_paginated() {
+            return self
+                .fetch(query)
+                .await?
+                .try_next()
+                .await?
+                .ok_or(FetchOneError::NoSuchEntry);
+        }
+        if !query.shape().is_unshaped() {
+            return self
+                .fetch_all(query)
+                .await?
+                .into_iter()
+                .next()
+                .ok_or(FetchOneError::NoSuchEntry);
+        }
+
+        let Single { query, residue } = self.dialect.translate(query, &self.convention);
 
         let bytes = fetch_impl(
-            &self.client,
+            self.transport(),
             self.source_url.clone(),
             self.source_method.clone(),
             query,
@@ -322,7 +781,7 @@ where
         .map_err(|err| {
             // HTTP errors should be raised by `fetch_impl`, and already have been returned.
             debug_assert!(err.status().is_none());
-            ConnectionError::Io(IoError::other(err))
+            ConnectionError::Io(IoError::other(err.without_url()))
         });
 
         // TODO: Fix this messy code.
@@ -355,7 +814,7 @@ where
             .encode_all(entries)
             .map_err(SendError::Encode)?;
         send_impl(
-            &self.client,
+            self.transport(),
             self.url.clone(),
             self.method.clone(),
             Vec::from(body),
@@ -369,7 +828,7 @@ where
     async fn send_one(&mut self, entry: &T) -> Result<(), SendError> {
         let body = self.encoder.encode_one(entry).map_err(SendError::Encode)?;
         send_impl(
-            &self.client,
+            self.transport(),
             self.url.clone(),
             self.method.clone(),
             Vec::from(body),
@@ -392,7 +851,7 @@ where
     async fn send_all(&mut self, entries: &[T]) -> Result<(), SendError> {
         let body = self.codec.encode_all(entries).map_err(SendError::Encode)?;
         send_impl(
-            &self.client,
+            self.transport(),
             self.sink_url.clone(),
             self.sink_method.clone(),
             Vec::from(body),
@@ -406,7 +865,7 @@ where
     async fn send_one(&mut self, entry: &T) -> Result<(), SendError> {
         let body = self.codec.encode_one(entry).map_err(SendError::Encode)?;
         send_impl(
-            &self.client,
+            self.transport(),
             self.sink_url.clone(),
             self.sink_method.clone(),
             Vec::from(body),
@@ -416,3 +875,204 @@ where
         .map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    clippy::unwrap_used,
+    reason = "Panics simply indicate failed tests."
+)]
+mod tests {
+    use super::*;
+    use crate::{encode::json::Json, query::combinators::True};
+    use serde::{Deserialize, Serialize};
+    use std::io::{BufRead as _, BufReader, Read as _, Write as _};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread::spawn;
+    use std::time::Instant;
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct TestBook {
+        title: String,
+    }
+
+    /// A stand-in server responding to requests with `failures` in order, given as a status line
+    /// and headers, and then succeeding. Returns the base URL and the number of requests received.
+    fn server(failures: &[&'static str]) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let received = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&received);
+        let failures = failures.to_vec();
+
+        drop(spawn(move || {
+            for stream in listener.incoming() {
+                let index = counter.fetch_add(1, Ordering::SeqCst);
+                respond(stream.unwrap(), failures.get(index).copied());
+            }
+        }));
+        (url, received)
+    }
+
+    /// Respond to a single request with `failure`, or successfully if there is none.
+    fn respond(mut stream: TcpStream, failure: Option<&str>) {
+        let mut reader = BufReader::new(&mut stream);
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap() == 0 || line.trim_end().is_empty() {
+                break;
+            }
+            if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length: ") {
+                length = value.trim_end().parse().unwrap();
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        let (head, content) =
+            failure.map_or(("200 OK", r#"[{"title":"Emma"}]"#), |head| (head, ""));
+        write!(
+            stream,
+            "HTTP/1.1 {head}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{content}",
+            content.len()
+        )
+        .unwrap();
+    }
+
+    fn policy() -> Policy {
+        Policy::new()
+            .max_attempts(3)
+            .initial_delay(Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn retried() {
+        let (url, received) = server(&[
+            "503 Service Unavailable\r\nRetry-After: 0",
+            "429 Too Many Requests\r\nRetry-After: Sun, 06 Nov 1994 08:49:37 GMT",
+        ]);
+        let mut source = Builder::<TestBook>::new()
+            .source_url(format!("{url}/books"))
+            .unwrap()
+            .decoder(Json)
+            .retry(policy())
+            .build();
+
+        let books = source.fetch_all(&True).await.unwrap();
+
+        assert_eq!(
+            books,
+            [TestBook {
+                title: "Emma".to_owned()
+            }]
+        );
+        assert_eq!(received.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn not_retried() {
+        let (url, received) = server(&["404 Not Found", "404 Not Found"]);
+        let mut source = Builder::<TestBook>::new()
+            .source_url(format!("{url}/books"))
+            .unwrap()
+            .decoder(Json)
+            .retry(policy())
+            .build();
+
+        let err = source.fetch_all(&True).await.unwrap_err();
+
+        assert!(matches!(
+            err,
+            FetchError::Connection(ConnectionError::Http { code: 404, .. })
+        ));
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+
+        let (delayed_url, delayed) = server(&["503 Service Unavailable\r\nRetry-After: 3600"]);
+        let mut delayed_source = Builder::<TestBook>::new()
+            .source_url(format!("{delayed_url}/books"))
+            .unwrap()
+            .decoder(Json)
+            .retry(policy())
+            .build();
+
+        assert!(matches!(
+            delayed_source.fetch_all(&True).await,
+            Err(FetchError::Connection(ConnectionError::Http {
+                code: 503,
+                ..
+            }))
+        ));
+        assert_eq!(delayed.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn key_not_leaked() {
+        let (url, _) = server(&["404 Not Found"]);
+        let unreachable = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+
+        for base in [url, unreachable] {
+            let mut source = Builder::<TestBook>::new()
+                .source_url(format!("{base}/books"))
+                .unwrap()
+                .decoder(Json)
+                .api_key_param("api_key", "secret")
+                .build();
+
+            let err = source.fetch_all(&True).await.unwrap_err();
+            assert!(!format!("{err:?}").contains("secret"));
+        }
+    }
+
+    #[tokio::test]
+    async fn rate_limited() {
+        let (url, received) = server(&[]);
+        let limiter = RateLimiter::new(1, Duration::from_millis(50));
+        let mut sources = [limiter.clone(), limiter].map(|limiter| {
+            Builder::<TestBook>::new()
+                .source_url(format!("{url}/books"))
+                .unwrap()
+                .decoder(Json)
+                .rate_limit(limiter)
+                .build()
+        });
+
+        let start = Instant::now();
+        for source in &mut sources {
+            for _ in 0..2 {
+                drop(source.fetch_all(&True).await.unwrap());
+            }
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(150));
+        assert_eq!(received.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn idempotent_sink() {
+        let book = TestBook {
+            title: "Emma".to_owned(),
+        };
+        for (method, non_idempotent, attempts) in [
+            (Method::PUT, false, 2),
+            (Method::POST, false, 1),
+            (Method::POST, true, 2),
+        ] {
+            let (url, received) = server(&["502 Bad Gateway"]);
+            let mut sink = Builder::<TestBook>::new()
+                .sink_url(format!("{url}/books"))
+                .unwrap()
+                .sink_method(method)
+                .encoder(Json)
+                .retry(policy().non_idempotent(non_idempotent))
+                .build();
+
+            assert_eq!(sink.send_one(&book).await.is_ok(), attempts == 2);
+            assert_eq!(received.load(Ordering::SeqCst), attempts);
+        }
+    }
+}
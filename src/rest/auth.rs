@@ -0,0 +1,297 @@
+use super::TokenProvider;
+use crate::errors::ConnectionError;
+use reqwest::{
+    Client, Method, Request, RequestBuilder, Response, StatusCode, Url,
+    header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue},
+};
+use std::fmt::{Debug, Error as FmtError, Formatter};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Credentials and other data included with every request, but not part of the queries.
+///
+/// Secrets, that is API keys, tokens and passwords, are redacted from the [`Debug`] output.
+/// Headers added using [`header`](Self::header) are not considered secret. Since API keys sent as
+/// query parameters are part of the URL, connectors remove the URL from their errors.
+#[derive(Clone, Debug, Default)]
+pub struct Authentication {
+    /// Headers, with secret values marked as [sensitive](HeaderValue::set_sensitive).
+    headers: HeaderMap,
+    /// Query parameters.
+    params: Vec<(Box<str>, Secret)>,
+    /// The username and password used for basic authentication.
+    basic: Option<(Box<str>, Option<Secret>)>,
+    /// Provides bearer tokens, such as [`ClientCredentials`](super::ClientCredentials).
+    provider: Option<Arc<dyn TokenProvider>>,
+}
+
+/// A string redacted from [`Debug`] output.
+#[derive(Clone)]
+pub(super) struct Secret(pub(super) Box<str>);
+
+impl Debug for Secret {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        f.write_str("Sensitive")
+    }
+}
+
+/// Error that is raised when a header name or value is invalid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Error)]
+#[error("The header name or value was invalid.")]
+pub struct InvalidHeader;
+
+impl Authentication {
+    /// Constructs authentication including nothing with requests.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends an API key in the header `name`, such as `X-Api-Key`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`InvalidHeader`] if the name or key is not valid in a header.
+    #[inline]
+    pub fn api_key_header(self, name: &str, key: &str) -> Result<Self, InvalidHeader> {
+        self.insert(name, key, true)
+    }
+
+    /// Sends an API key in the query parameter `name`, such as `api_key`.
+    #[must_use]
+    #[inline]
+    pub fn api_key_param(mut self, name: impl Into<Box<str>>, key: impl Into<Box<str>>) -> Self {
+        self.params.push((name.into(), Secret(key.into())));
+        self
+    }
+
+    /// Sends a static bearer token in the `Authorization` header.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`InvalidHeader`] if the token is not valid in a header.
+    #[inline]
+    pub fn bearer_token(self, token: &str) -> Result<Self, InvalidHeader> {
+        self.insert(AUTHORIZATION.as_str(), &format!("Bearer {token}"), true)
+    }
+
+    /// Uses basic authentication with `username` and optionally `password`.
+    #[must_use]
+    #[inline]
+    pub fn basic_auth(
+        self,
+        username: impl Into<Box<str>>,
+        password: Option<impl Into<Box<str>>>,
+    ) -> Self {
+        Self {
+            basic: Some((
+                username.into(),
+                password.map(|password| Secret(password.into())),
+            )),
+            ..self
+        }
+    }
+
+    /// Sends bearer tokens from `provider`, such as
+    /// [`ClientCredentials`](super::ClientCredentials). If a request is rejected with code 401,
+    /// the token is invalidated and the request retried once with a new one.
+    #[must_use]
+    #[inline]
+    pub fn token_provider(self, provider: impl TokenProvider + 'static) -> Self {
+        Self {
+            provider: Some(Arc::new(provider)),
+            ..self
+        }
+    }
+
+    /// Sends the header `name` with `value`, replacing any previous value. The value is not
+    /// considered secret, and is shown in [`Debug`] output.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`InvalidHeader`] if the name or value is not valid in a header.
+    #[inline]
+    pub fn header(self, name: &str, value: &str) -> Result<Self, InvalidHeader> {
+        self.insert(name, value, false)
+    }
+
+    /// Sets the header `name` to `value`, marking it as sensitive if `secret`.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`InvalidHeader`] if the name or value is not valid in a header.
+    #[expect(
+        clippy::map_err_ignore,
+        reason = "The errors of `http` expose no useful information."
+    )]
+    fn insert(mut self, name: &str, value: &str, secret: bool) -> Result<Self, InvalidHeader> {
+        let header = HeaderName::try_from(name).map_err(|_| InvalidHeader)?;
+        let mut header_value = HeaderValue::try_from(value).map_err(|_| InvalidHeader)?;
+        header_value.set_sensitive(secret);
+        drop(self.headers.insert(header, header_value));
+        Ok(self)
+    }
+
+    /// Build a request to `url` including the credentials. API keys sent as query parameters are
+    /// only added if `url` does not already include them, such as in links to following pages.
+    pub(super) fn apply(&self, client: &Client, method: Method, mut url: Url) -> RequestBuilder {
+        let missing: Vec<_> = self
+            .params
+            .iter()
+            .filter(|(name, _)| url.query_pairs().all(|(present, _)| *present != **name))
+            .map(|(name, Secret(key))| (&**name, &**key))
+            .collect();
+        if !missing.is_empty() {
+            let _ = url.query_pairs_mut().extend_pairs(missing);
+        }
+
+        let mut request = client.request(method, url).headers(self.headers.clone());
+        if let Some((username, password)) = &self.basic {
+            request =
+                request.basic_auth(username, password.as_ref().map(|Secret(password)| password));
+        }
+        request
+    }
+
+    /// Execute `request`, which has been built using [`apply`](Self::apply), adding a token from
+    /// the provider if there is one. The status of the response is not checked.
+    ///
+    /// # Errors
+    ///
+    /// Fails if an error occurs during connection, or if no token can be provided.
+    pub(super) async fn execute(
+        &self,
+        client: &Client,
+        mut request: Request,
+    ) -> Result<Response, ConnectionError> {
+        let Some(provider) = &self.provider else {
+            return Ok(client.execute(request).await?);
+        };
+
+        let retry = request.try_clone();
+        let token = provider.token().await?;
+        authorize(&mut request, &token)?;
+        let response = client.execute(request).await?;
+
+        match retry {
+            Some(mut retry) if response.status() == StatusCode::UNAUTHORIZED => {
+                provider.invalidate(&token).await;
+                authorize(&mut retry, &provider.token().await?)?;
+                Ok(client.execute(retry).await?)
+            },
+            Some(_) | None => Ok(response),
+        }
+    }
+}
+
+/// Add `token` to `request` as a bearer token.
+///
+/// # Errors
+///
+/// Fails if the token is not valid in a header.
+fn authorize(request: &mut Request, token: &str) -> Result<(), ConnectionError> {
+    let mut value = HeaderValue::try_from(format!("Bearer {token}"))
+        .map_err(|err| ConnectionError::Process(Box::new(err)))?;
+    value.set_sensitive(true);
+    drop(request.headers_mut().insert(AUTHORIZATION, value));
+    Ok(())
+}
+
+/// Fail with [`ConnectionError::Http`] if the server responded with an error.
+///
+/// # Errors
+///
+/// Fails if the status of `response` is a client or server error.
+pub(super) fn check_status(response: Response) -> Result<Response, ConnectionError> {
+    match response.error_for_status_ref() {
+        Ok(_) => Ok(response),
+        Err(err) => Err(ConnectionError::Http {
+            code: response.status().as_u16(),
+            // The URL may contain secrets.
+            source: Box::new(err.without_url()),
+        }),
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    clippy::unwrap_used,
+    reason = "Panics simply indicate failed tests."
+)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply() {
+        let auth = Authentication::new()
+            .api_key_header("X-Api-Key", "key")
+            .unwrap()
+            .api_key_param("api_key", "param key")
+            .header("Accept", "application/json")
+            .unwrap();
+        let request = auth
+            .apply(
+                &Client::new(),
+                Method::GET,
+                Url::parse("http://localhost/books?year=1815").unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(request.headers()["x-api-key"], "key");
+        assert_eq!(request.headers()["accept"], "application/json");
+        assert_eq!(request.url().query(), Some("year=1815&api_key=param+key"));
+
+        let linked = auth
+            .apply(
+                &Client::new(),
+                Method::GET,
+                Url::parse("http://localhost/books?api_key=param+key&p=2").unwrap(),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(linked.url().query(), Some("api_key=param+key&p=2"));
+
+        let basic = Authentication::new().basic_auth("user", Some("password"));
+        let basic_request = basic
+            .apply(
+                &Client::new(),
+                Method::GET,
+                Url::parse("http://localhost/books").unwrap(),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(
+            basic_request.headers()[AUTHORIZATION],
+            "Basic dXNlcjpwYXNzd29yZA=="
+        );
+
+        assert_eq!(
+            Authentication::new().header("Invalid Name", "value").err(),
+            Some(InvalidHeader)
+        );
+    }
+
+    #[test]
+    fn redacted() {
+        let auth = Authentication::new()
+            .api_key_header("X-Api-Key", "header key")
+            .unwrap()
+            .api_key_param("api_key", "param key")
+            .bearer_token("token")
+            .unwrap()
+            .basic_auth("user", Some("password"))
+            .header("Accept", "application/json")
+            .unwrap();
+        let debug = format!("{auth:?}");
+
+        for secret in ["header key", "param key", "token", "password"] {
+            assert!(!debug.contains(secret), "{secret} leaked in {debug}");
+        }
+        assert!(debug.contains("user"));
+        assert!(debug.contains("application/json"));
+    }
+}
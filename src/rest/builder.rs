@@ -1,9 +1,21 @@
 use crate::{
     encode::Codec,
-    rest::{ReadOnly, ReadWrite, WriteOnly},
+    query::{
+        HttpConvention,
+        http::{
+            FieldMap,
+            dialect::{HttpDialect, Params},
+        },
+    },
+    rest::{
+        Authentication, InvalidHeader, Pagination, RateLimiter, ReadOnly, ReadWrite, TokenProvider,
+        WriteOnly,
+    },
+    retry::Policy,
 };
 use reqwest::{Client, IntoUrl, Method, Url};
 use std::marker::PhantomData;
+use std::sync::Arc;
 use thiserror::Error;
 
 /// A builder used to construct a [`ReadOnly`], [`WriteOnly`] or [`ReadWrite`] REST connector.
@@ -17,7 +29,10 @@ use thiserror::Error;
 ///   either both an [encoder](Self::encoder) and a [decoder](Self::decoder), or a single
 ///   [codec](Self::codec). It also optionally allows setting a [client](Self::client).
 ///
-/// If none of these cases match, there is no output type and no `build` method exists.
+/// If none of these cases match, there is no output type and no `build` method exists. All of them
+/// optionally allow setting [authentication](Self::authentication), such as an
+/// [API key](Self::api_key_header) or a [bearer token](Self::bearer_token), which is included with
+/// every request.
 ///
 /// The builder uses the typestate pattern to accomplish this. The downside is that the method
 /// documentations can be quite messy with the type signatures. It is advised to consult the guide
@@ -73,6 +88,18 @@ pub struct Builder<
     // INVARIANT: `!(combined.is_some() && encoder.is_some())`.
     // INVARIANT: `!(combined.is_some() && decoder.is_some())`.
     combined: Option<C>,
+    /// The conventions used to translate queries into parameters when fetching data.
+    convention: HttpConvention,
+    /// The dialect used to translate queries when fetching data. Defaults to [`Params`].
+    dialect: Option<Arc<dyn HttpDialect<T>>>,
+    /// The credentials included with every request. Defaults to [`Authentication::new`].
+    auth: Option<Authentication>,
+    /// How results are split into pages when fetching data.
+    pagination: Pagination,
+    /// How failed requests are retried.
+    retry: Policy,
+    /// Limits the rate of requests, if set.
+    limiter: Option<RateLimiter>,
     /// Satisfies missing fields using `T`.
     // TODO: This may be overly restrictive when considering variance. Improve using unstable
     // `phantom_variance_markers` (#135806)?
@@ -93,6 +120,12 @@ impl<T> Builder<T> {
             encoder: None,
             decoder: None,
             combined: None,
+            convention: HttpConvention::new(),
+            dialect: None,
+            auth: None,
+            pagination: Pagination::new(),
+            retry: Policy::new(),
+            limiter: None,
             _phantom: PhantomData,
         }
     }
@@ -290,7 +323,7 @@ impl<
         InvalidUrl,
     > {
         Ok(Builder {
-            source_url: Some(url.into_url().map_err(|_| InvalidUrl)?),
+            sink_url: Some(url.into_url().map_err(|_| InvalidUrl)?),
             ..self
         })
     }
@@ -576,6 +609,206 @@ impl<
     }
 }
 
+impl<
+    T,
+    E,
+    D,
+    C,
+    const SOURCE_URL: bool,
+    const SOURCE_METHOD: bool,
+    const SINK_URL: bool,
+    const SINK_METHOD: bool,
+    const CLIENT: bool,
+    const ENCODER: bool,
+    const DECODER: bool,
+    const COMBINED: bool,
+>
+    Builder<
+        T,
+        E,
+        D,
+        C,
+        SOURCE_URL,
+        SOURCE_METHOD,
+        SINK_URL,
+        SINK_METHOD,
+        CLIENT,
+        ENCODER,
+        DECODER,
+        COMBINED,
+    >
+{
+    /// Specifies the conventions used to translate queries into parameters when fetching data.
+    /// Defaults to [`HttpConvention::new`], supporting only equality.
+    ///
+    /// Unlike most other options, this is not tracked by the builder's type and may be set
+    /// repeatedly, the last value taking effect.
+    #[must_use]
+    #[inline]
+    pub fn convention(self, convention: HttpConvention) -> Self {
+        Self { convention, ..self }
+    }
+
+    /// Specifies how fields and their values are named by the source, as part of the
+    /// [`convention`](Self::convention). Defaults to [`FieldMap::new`], passing all fields
+    /// through unchanged.
+    ///
+    /// Since the field map is part of the convention, it is replaced by setting the convention
+    /// afterwards.
+    #[must_use]
+    #[inline]
+    pub fn field_map(self, fields: FieldMap) -> Self {
+        Self {
+            convention: self.convention.fields(fields),
+            ..self
+        }
+    }
+
+    /// Specifies the dialect used to translate queries when fetching data, such as
+    /// [`OData`](crate::query::http::dialect::OData). Defaults to [`Params`], sending one
+    /// parameter per comparison as specified by the [`convention`](Self::convention).
+    ///
+    /// Unlike most other options, this is not tracked by the builder's type and may be set
+    /// repeatedly, the last value taking effect.
+    #[must_use]
+    #[inline]
+    pub fn dialect(self, dialect: impl HttpDialect<T> + 'static) -> Self {
+        Self {
+            dialect: Some(Arc::new(dialect)),
+            ..self
+        }
+    }
+
+    /// Specifies how results are split into pages when fetching data. Defaults to
+    /// [`Pagination::new`], making a single request.
+    ///
+    /// Unlike most other options, this is not tracked by the builder's type and may be set
+    /// repeatedly, the last value taking effect.
+    #[must_use]
+    #[inline]
+    pub fn pagination(self, pagination: Pagination) -> Self {
+        Self { pagination, ..self }
+    }
+
+    /// Specifies how requests failing with transient errors are retried. Defaults to
+    /// [`Policy::new`], making a single attempt. Requests sending data are only retried if their
+    /// method is idempotent, such as `PUT`, unless the policy
+    /// [allows otherwise](Policy::non_idempotent).
+    ///
+    /// Unlike most other options, this is not tracked by the builder's type and may be set
+    /// repeatedly, the last value taking effect.
+    #[must_use]
+    #[inline]
+    pub fn retry(self, retry: Policy) -> Self {
+        Self { retry, ..self }
+    }
+
+    /// Limits the rate of requests, waiting when the budget is exhausted. By default, the rate is
+    /// not limited. Pass clones of the same [`RateLimiter`] to connectors hitting the same host to
+    /// share the budget between them.
+    ///
+    /// Unlike most other options, this is not tracked by the builder's type and may be set
+    /// repeatedly, the last value taking effect.
+    #[must_use]
+    #[inline]
+    pub fn rate_limit(self, limiter: RateLimiter) -> Self {
+        Self {
+            limiter: Some(limiter),
+            ..self
+        }
+    }
+
+    /// Specifies the credentials and other data included with every request. Defaults to
+    /// [`Authentication::new`], including nothing.
+    ///
+    /// Unlike most other options, this is not tracked by the builder's type and may be set
+    /// repeatedly, the last value taking effect. The methods below add to the current
+    /// authentication instead.
+    #[must_use]
+    #[inline]
+    pub fn authentication(self, auth: Authentication) -> Self {
+        Self {
+            auth: Some(auth),
+            ..self
+        }
+    }
+
+    /// Sends an API key in the header `name`. See [`Authentication::api_key_header`].
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`InvalidHeader`] if the name or key is not valid in a header.
+    #[inline]
+    pub fn api_key_header(self, name: &str, key: &str) -> Result<Self, InvalidHeader> {
+        Ok(Self {
+            auth: Some(self.auth.unwrap_or_default().api_key_header(name, key)?),
+            ..self
+        })
+    }
+
+    /// Sends an API key in the query parameter `name`. See [`Authentication::api_key_param`].
+    #[must_use]
+    #[inline]
+    pub fn api_key_param(self, name: impl Into<Box<str>>, key: impl Into<Box<str>>) -> Self {
+        Self {
+            auth: Some(self.auth.unwrap_or_default().api_key_param(name, key)),
+            ..self
+        }
+    }
+
+    /// Sends a static bearer token. See [`Authentication::bearer_token`].
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`InvalidHeader`] if the token is not valid in a header.
+    #[inline]
+    pub fn bearer_token(self, token: &str) -> Result<Self, InvalidHeader> {
+        Ok(Self {
+            auth: Some(self.auth.unwrap_or_default().bearer_token(token)?),
+            ..self
+        })
+    }
+
+    /// Uses basic authentication. See [`Authentication::basic_auth`].
+    #[must_use]
+    #[inline]
+    pub fn basic_auth(
+        self,
+        username: impl Into<Box<str>>,
+        password: Option<impl Into<Box<str>>>,
+    ) -> Self {
+        Self {
+            auth: Some(self.auth.unwrap_or_default().basic_auth(username, password)),
+            ..self
+        }
+    }
+
+    /// Sends bearer tokens from `provider`, such as
+    /// [`ClientCredentials`](crate::rest::ClientCredentials). See
+    /// [`Authentication::token_provider`].
+    #[must_use]
+    #[inline]
+    pub fn token_provider(self, provider: impl TokenProvider + 'static) -> Self {
+        Self {
+            auth: Some(self.auth.unwrap_or_default().token_provider(provider)),
+            ..self
+        }
+    }
+
+    /// Sends the header `name` with `value`. See [`Authentication::header`].
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`InvalidHeader`] if the name or value is not valid in a header.
+    #[inline]
+    pub fn header(self, name: &str, value: &str) -> Result<Self, InvalidHeader> {
+        Ok(Self {
+            auth: Some(self.auth.unwrap_or_default().header(name, value)?),
+            ..self
+        })
+    }
+}
+
 /// A trait indicating that a builder is ready to be built into its output type.
 ///
 /// Depending on the builder, this trait may only be available under certain conditions. That is,
@@ -602,6 +835,12 @@ impl<T, D, const SOURCE_METHOD: bool, const CLIENT: bool> Build
             source_method,
             client,
             decoder: Some(decoder),
+            convention,
+            dialect,
+            auth,
+            pagination,
+            retry,
+            limiter,
             ..
         } = self
         else {
@@ -612,7 +851,13 @@ impl<T, D, const SOURCE_METHOD: bool, const CLIENT: bool> Build
             url,
             method: source_method.unwrap_or(Method::GET),
             client: client.unwrap_or_default(),
+            auth: auth.unwrap_or_default(),
+            retry,
+            limiter,
             decoder,
+            convention,
+            dialect: dialect.unwrap_or_else(|| Arc::new(Params)),
+            pagination,
             _phantom: PhantomData,
         }
     }
@@ -632,6 +877,9 @@ impl<T, E, const SINK_METHOD: bool, const CLIENT: bool> Build
             sink_method,
             client,
             encoder: Some(encoder),
+            auth,
+            retry,
+            limiter,
             ..
         } = self
         else {
@@ -640,8 +888,11 @@ impl<T, E, const SINK_METHOD: bool, const CLIENT: bool> Build
 
         Self::Output {
             url,
-            method: sink_method.unwrap_or(Method::GET),
+            method: sink_method.unwrap_or(Method::PUT),
             client: client.unwrap_or_default(),
+            auth: auth.unwrap_or_default(),
+            retry,
+            limiter,
             encoder,
             _phantom: PhantomData,
         }
@@ -666,6 +917,12 @@ impl<T, E, D, const SOURCE_METHOD: bool, const SINK_METHOD: bool, const CLIENT:
             encoder: Some(encoder),
             decoder: Some(decoder),
             combined: None,
+            convention,
+            dialect,
+            auth,
+            pagination,
+            retry,
+            limiter,
             ..
         } = self
         else {
@@ -678,7 +935,13 @@ impl<T, E, D, const SOURCE_METHOD: bool, const SINK_METHOD: bool, const CLIENT:
             sink_url,
             sink_method: sink_method.unwrap_or(Method::PUT),
             client: client.unwrap_or_default(),
+            auth: auth.unwrap_or_default(),
+            retry,
+            limiter,
             codec: Codec::separate(encoder, decoder),
+            convention,
+            dialect: dialect.unwrap_or_else(|| Arc::new(Params)),
+            pagination,
             _phantom: PhantomData,
         }
     }
@@ -702,6 +965,12 @@ impl<T, C, const SOURCE_METHOD: bool, const SINK_METHOD: bool, const CLIENT: boo
             encoder: None,
             decoder: None,
             combined: Some(combined),
+            convention,
+            dialect,
+            auth,
+            pagination,
+            retry,
+            limiter,
             ..
         } = self
         else {
@@ -714,7 +983,13 @@ impl<T, C, const SOURCE_METHOD: bool, const SINK_METHOD: bool, const CLIENT: boo
             sink_url,
             sink_method: sink_method.unwrap_or(Method::PUT),
             client: client.unwrap_or_default(),
+            auth: auth.unwrap_or_default(),
+            retry,
+            limiter,
             codec: Codec::combined(combined),
+            convention,
+            dialect: dialect.unwrap_or_else(|| Arc::new(Params)),
+            pagination,
             _phantom: PhantomData,
         }
     }
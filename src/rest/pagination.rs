@@ -0,0 +1,649 @@
+use crate::{
+    encode::Decode,
+    errors::{DecodeError, FetchError},
+};
+use reqwest::{Response, Url, header::LINK};
+use serde_json::{Value, from_slice, to_vec};
+use thiserror::Error;
+
+/// How a source splits results into pages, and how many of them to fetch.
+///
+/// By default, results are not paginated and a single request is made. Otherwise, pages are
+/// fetched in order until the source indicates there are no more, or until
+/// [`max_pages`](Self::max_pages) have been fetched. A page without entries is always the last.
+///
+/// ```
+/// # use broker::rest::{PageStrategy, Pagination};
+/// // `?page=1&per_page=50`, `?page=2&per_page=50`, ...
+/// let pages = Pagination::pages("page", "per_page", 50).max_pages(10);
+///
+/// // `?cursor=...`, with entries under `data` and the next cursor under `meta.next`.
+/// let mut cursor = Pagination::cursor("cursor", "/meta/next");
+/// if let PageStrategy::Cursor { items, .. } = &mut cursor.strategy {
+///     *items = Some("/data".into());
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Pagination {
+    /// How pages are requested.
+    pub strategy: PageStrategy,
+    /// The maximum number of pages fetched, if any.
+    pub max_pages: Option<usize>,
+}
+
+/// How pages are requested. See [`Pagination`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PageStrategy {
+    /// Results are not paginated.
+    #[default]
+    None,
+    /// Pages are selected by number and size, such as `page=2&per_page=50`. A page with fewer than
+    /// `size` entries is the last.
+    Pages {
+        /// The name of the parameter specifying the page number.
+        page: Box<str>,
+        /// The name of the parameter specifying the page size.
+        size_param: Box<str>,
+        /// The page size.
+        size: usize,
+        /// The number of the first page, usually 0 or 1.
+        first: usize,
+    },
+    /// Pages are selected by offset and limit, such as `offset=100&limit=50`. A page with fewer
+    /// than `limit` entries is the last.
+    Offset {
+        /// The name of the parameter specifying the number of entries to skip.
+        offset: Box<str>,
+        /// The name of the parameter specifying the page size.
+        limit_param: Box<str>,
+        /// The page size.
+        limit: usize,
+    },
+    /// Pages are selected by an opaque cursor taken from the previous page, such as `cursor=abc`.
+    /// The body must be JSON. A page without a cursor, or with a null or empty one, is the last.
+    Cursor {
+        /// The name of the parameter specifying the cursor.
+        param: Box<str>,
+        /// The location of the cursor in the body, as a [JSON Pointer] such as `/meta/next`.
+        ///
+        /// [JSON Pointer]: https://www.rfc-editor.org/rfc/rfc6901
+        pointer: Box<str>,
+        /// The location of the entries in the body, as a JSON Pointer. If [`None`], the entire
+        /// body is decoded.
+        items: Option<Box<str>>,
+    },
+    /// The next page is linked by a `Link` header with `rel="next"`, as specified by
+    /// [RFC 8288]. A page without such a link is the last. Links are requested as given, already
+    /// including the query, and only if they have the same origin as the source; otherwise
+    /// fetching fails, such that credentials are not sent elsewhere.
+    ///
+    /// [RFC 8288]: https://www.rfc-editor.org/rfc/rfc8288
+    Link,
+}
+
+impl Pagination {
+    /// Constructs a configuration without pagination.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            strategy: PageStrategy::None,
+            max_pages: None,
+        }
+    }
+
+    /// Pages selected by the parameters `page`, counting from 1, and `size_param`, set to `size`.
+    #[must_use]
+    #[inline]
+    pub fn pages(page: impl Into<Box<str>>, size_param: impl Into<Box<str>>, size: usize) -> Self {
+        Self {
+            strategy: PageStrategy::Pages {
+                page: page.into(),
+                size_param: size_param.into(),
+                size,
+                first: 1,
+            },
+            max_pages: None,
+        }
+    }
+
+    /// Pages selected by the parameters `offset` and `limit_param`, set to `limit`.
+    #[must_use]
+    #[inline]
+    pub fn offset(
+        offset: impl Into<Box<str>>,
+        limit_param: impl Into<Box<str>>,
+        limit: usize,
+    ) -> Self {
+        Self {
+            strategy: PageStrategy::Offset {
+                offset: offset.into(),
+                limit_param: limit_param.into(),
+                limit,
+            },
+            max_pages: None,
+        }
+    }
+
+    /// Pages selected by the parameter `param`, set to the cursor found at `pointer` in the
+    /// previous page. The entire body is decoded; see [`PageStrategy::Cursor`] to decode only a
+    /// part of it.
+    #[must_use]
+    #[inline]
+    pub fn cursor(param: impl Into<Box<str>>, pointer: impl Into<Box<str>>) -> Self {
+        Self {
+            strategy: PageStrategy::Cursor {
+                param: param.into(),
+                pointer: pointer.into(),
+                items: None,
+            },
+            max_pages: None,
+        }
+    }
+
+    /// Pages linked by `Link` headers.
+    #[must_use]
+    #[inline]
+    pub const fn link() -> Self {
+        Self {
+            strategy: PageStrategy::Link,
+            max_pages: None,
+        }
+    }
+
+    /// Sets the maximum number of pages fetched.
+    #[must_use]
+    #[inline]
+    pub fn max_pages(self, max_pages: usize) -> Self {
+        Self {
+            max_pages: Some(max_pages),
+            ..self
+        }
+    }
+
+    /// Whether results are paginated.
+    #[must_use]
+    #[inline]
+    pub const fn is_paginated(&self) -> bool {
+        !matches!(self.strategy, PageStrategy::None)
+    }
+
+    /// Where to request the page following `request`. Returns [`None`] if the page is past
+    /// [`max_pages`](Self::max_pages).
+    pub(super) fn target(&self, request: &PageRequest) -> Option<Target> {
+        if self.max_pages.is_some_and(|max| request.index >= max) {
+            return None;
+        }
+
+        let params = match (&self.strategy, &request.position) {
+            (_, Position::Link(link)) => return Some(Target::Link(link.clone())),
+            (
+                PageStrategy::Pages {
+                    page,
+                    size_param,
+                    size,
+                    first,
+                },
+                Position::Start,
+            ) => vec![
+                (page.clone(), (first + request.index).to_string()),
+                (size_param.clone(), size.to_string()),
+            ],
+            (
+                PageStrategy::Offset {
+                    offset,
+                    limit_param,
+                    limit,
+                },
+                Position::Start,
+            ) => vec![
+                (offset.clone(), (limit * request.index).to_string()),
+                (limit_param.clone(), limit.to_string()),
+            ],
+            (PageStrategy::Cursor { param, .. }, Position::Cursor(cursor)) => {
+                vec![(param.clone(), cursor.clone())]
+            },
+            (
+                PageStrategy::None
+                | PageStrategy::Pages { .. }
+                | PageStrategy::Offset { .. }
+                | PageStrategy::Cursor { .. }
+                | PageStrategy::Link,
+                Position::Start | Position::Cursor(_),
+            ) => Vec::new(),
+        };
+        Some(Target::Query(params))
+    }
+
+    /// Decode the page requested by `request`, returning its entries and the request for the next
+    /// page, if any.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the body can not be received or decoded.
+    pub(super) async fn page<T, D>(
+        &self,
+        request: &PageRequest,
+        response: Response,
+        decoder: &D,
+    ) -> Result<(Vec<T>, Option<PageRequest>), FetchError>
+    where
+        D: Decode<T> + Sync,
+    {
+        let link = response
+            .headers()
+            .get_all(LINK)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(|value| next_link(value, response.url()));
+        let bytes = response.bytes().await?;
+
+        let (entries, position) = if let PageStrategy::Cursor { pointer, items, .. } =
+            &self.strategy
+        {
+            let body: Value = from_slice(&bytes).map_err(|err| DecodeError(Box::new(err)))?;
+            let entries = match items {
+                Some(items) => {
+                    let items = body.pointer(items).unwrap_or(&Value::Null);
+                    decoder.decode_all(&to_vec(items).map_err(|err| DecodeError(Box::new(err)))?)
+                },
+                None => decoder.decode_all(&bytes),
+            }?;
+            let cursor = match body.pointer(pointer) {
+                Some(Value::String(cursor)) if !cursor.is_empty() => Some(cursor.clone()),
+                Some(Value::Number(cursor)) => Some(cursor.to_string()),
+                Some(
+                    Value::String(_)
+                    | Value::Null
+                    | Value::Bool(_)
+                    | Value::Array(_)
+                    | Value::Object(_),
+                )
+                | None => None,
+            };
+            (entries, cursor.map(Position::Cursor))
+        } else {
+            let entries = decoder.decode_all(&bytes)?;
+            let position = match &self.strategy {
+                PageStrategy::Pages { size, .. } | PageStrategy::Offset { limit: size, .. } => {
+                    (entries.len() >= *size).then_some(Position::Start)
+                },
+                PageStrategy::Link => link.map(Position::Link),
+                PageStrategy::None | PageStrategy::Cursor { .. } => None,
+            };
+            (entries, position)
+        };
+
+        let next = position
+            .filter(|_| !entries.is_empty())
+            .map(|position| PageRequest {
+                index: request.index + 1,
+                position,
+            });
+        Ok((entries, next))
+    }
+}
+
+/// A parameter added to the query to select a page.
+pub(super) type Param = (Box<str>, String);
+
+/// Where to request a page.
+#[derive(Debug)]
+pub(super) enum Target {
+    /// The URL of the source, adding the parameters to the translated query.
+    Query(Vec<Param>),
+    /// A URL linked by the previous page, requested as given.
+    Link(Url),
+}
+
+/// Error raised when the next page is linked at a different origin than the source. Such links
+/// are not followed, since the credentials would be sent along.
+#[derive(Debug, Error)]
+#[error("The next page is linked at the different origin {0}.")]
+pub(super) struct ForeignLink(pub(super) String);
+
+/// A request for a page.
+#[derive(Clone, Debug, Default)]
+pub(super) struct PageRequest {
+    /// The number of pages before this one.
+    index: usize,
+    /// How the page is located.
+    position: Position,
+}
+
+/// How a page is located, besides its index.
+#[derive(Clone, Debug, Default)]
+enum Position {
+    /// Using the index alone.
+    #[default]
+    Start,
+    /// Using a cursor taken from the previous page.
+    Cursor(String),
+    /// Using a URL linked by the previous page.
+    Link(Url),
+}
+
+/// The target of the link in a `Link` header with relation type `next`, resolved against `base`.
+fn next_link(header: &str, base: &Url) -> Option<Url> {
+    let mut rest = header;
+    while let Some((_, link)) = rest.split_once('<') {
+        let (target, after) = link.split_once('>')?;
+        let (params, remaining) = after.split_at(after.find('<').unwrap_or(after.len()));
+        rest = remaining;
+
+        let next = params.split([';', ',']).any(|param| {
+            param.split_once('=').is_some_and(|(name, value)| {
+                name.trim().eq_ignore_ascii_case("rel")
+                    && value
+                        .trim()
+                        .trim_matches('"')
+                        .split_ascii_whitespace()
+                        .any(|relation| relation.eq_ignore_ascii_case("next"))
+            })
+        });
+        if next {
+            return base.join(target).ok();
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    clippy::unwrap_used,
+    reason = "Panics simply indicate failed tests."
+)]
+mod tests {
+    use super::*;
+    use crate::{
+        connector::Source as _,
+        encode::json::Json,
+        errors::ConnectionError,
+        query::{Queryable, combinators::True},
+        rest::{Build as _, Builder},
+    };
+    use futures::TryStreamExt as _;
+    use serde::Deserialize;
+    use std::io::{BufRead as _, BufReader, Write as _};
+    use std::net::TcpListener;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+    use std::thread::spawn;
+
+    #[derive(Debug, PartialEq, Eq, Deserialize, Queryable)]
+    struct TestBook {
+        title: String,
+    }
+
+    const TITLES: [&str; 5] = [
+        "Emma",
+        "Persuasion",
+        "Mansfield Park",
+        "Northanger Abbey",
+        "Lady Susan",
+    ];
+
+    /// A JSON array of the books with titles in `range`.
+    fn books(range: impl IntoIterator<Item = usize>) -> String {
+        let books: Vec<_> = range
+            .into_iter()
+            .filter_map(|index| TITLES.get(index))
+            .map(|title| format!(r#"{{"title":"{title}"}}"#))
+            .collect();
+        format!("[{}]", books.join(","))
+    }
+
+    /// A stand-in server responding to `GET` requests with the headers and body produced by
+    /// `respond` given the path and query. Returns the URL of `/books` and the number of requests
+    /// made.
+    fn server(respond: fn(&str) -> (String, String)) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/books", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+
+        drop(spawn(move || {
+            for (index, stream) in listener.incoming().enumerate() {
+                let mut stream = stream.unwrap();
+                let mut lines = BufReader::new(&mut stream).lines();
+                let request_line = lines.next().unwrap().unwrap();
+                while !lines.next().unwrap().unwrap().is_empty() {}
+
+                let target = request_line.split(' ').nth(1).unwrap();
+                let (headers, body) = respond(target);
+                counter.store(index + 1, Ordering::SeqCst);
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n\
+                     {body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        }));
+        (url, requests)
+    }
+
+    /// The value of the parameter `name` in `target`.
+    fn param(target: &str, name: &str) -> Option<usize> {
+        let (_, query) = target.split_once('?')?;
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+            .map(|value| value.parse().unwrap())
+    }
+
+    async fn fetch_all(url: String, pagination: Pagination) -> Vec<String> {
+        let mut source = Builder::<TestBook>::new()
+            .source_url(url)
+            .unwrap()
+            .decoder(Json)
+            .pagination(pagination)
+            .build();
+        let books = source.fetch_all(&True).await.unwrap();
+        books.into_iter().map(|book| book.title).collect()
+    }
+
+    #[tokio::test]
+    async fn pages() {
+        let respond = |target: &str| {
+            let (page, size) = (
+                param(target, "page").unwrap(),
+                param(target, "size").unwrap(),
+            );
+            (String::new(), books((page - 1) * size..page * size))
+        };
+
+        let (url, requests) = server(respond);
+        let fetched = fetch_all(url, Pagination::pages("page", "size", 2)).await;
+        assert_eq!(fetched, TITLES);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        let (capped_url, capped_requests) = server(respond);
+        let capped = fetch_all(
+            capped_url,
+            Pagination::pages("page", "size", 2).max_pages(2),
+        )
+        .await;
+        assert_eq!(capped, TITLES[..4]);
+        assert_eq!(capped_requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn offset() {
+        let (url, requests) = server(|target| {
+            let (offset, limit) = (
+                param(target, "skip").unwrap(),
+                param(target, "take").unwrap(),
+            );
+            (String::new(), books(offset..offset + limit))
+        });
+
+        let fetched = fetch_all(url, Pagination::offset("skip", "take", 5)).await;
+
+        // A full last page is followed by an empty one.
+        assert_eq!(fetched, TITLES);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn cursor() {
+        let (url, requests) = server(|target| {
+            let start = param(target, "cursor").unwrap_or(0);
+            let next = if start < 3 {
+                format!(r#""{}""#, start + 3)
+            } else {
+                "null".to_owned()
+            };
+            let body = format!(
+                r#"{{"data":{},"meta":{{"next":{next}}}}}"#,
+                books(start..start + 3)
+            );
+            (String::new(), body)
+        });
+        let mut pagination = Pagination::cursor("cursor", "/meta/next");
+        if let PageStrategy::Cursor { items, .. } = &mut pagination.strategy {
+            *items = Some("/data".into());
+        }
+
+        let fetched = fetch_all(url, pagination).await;
+
+        assert_eq!(fetched, TITLES);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn link() {
+        let (url, requests) = server(|target| {
+            let page = param(target, "p").unwrap_or(0);
+            let link = if page < 2 {
+                format!(
+                    "Link: </books?p={}>; rel=\"next\", </books?p=0>; rel=first\r\n",
+                    page + 1
+                )
+            } else {
+                String::new()
+            };
+            (link, books(page * 2..page * 2 + 2))
+        });
+
+        let mut source = Builder::<TestBook>::new()
+            .source_url(url)
+            .unwrap()
+            .decoder(Json)
+            .pagination(Pagination::link())
+            .build();
+        let mut stream = source.fetch(&True).await.unwrap();
+
+        // Pages are fetched lazily.
+        let first = stream.try_next().await.unwrap().unwrap();
+        assert_eq!(first.title, "Emma");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        let rest: Vec<_> = stream.try_collect().await.unwrap();
+        assert_eq!(rest.len(), 4);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn owned() {
+        let (url, requests) = server(|target| {
+            let page = param(target, "page").unwrap();
+            (String::new(), books((page - 1) * 2..page * 2))
+        });
+        let source = Builder::<TestBook>::new()
+            .source_url(url)
+            .unwrap()
+            .decoder(Json)
+            .pagination(Pagination::pages("page", "size", 2))
+            .build();
+        let mut stream = source.fetch_owned(TestBook::title().ne("Persuasion"));
+
+        // Pages are fetched lazily, and entries not matching the query are skipped.
+        let first = stream.try_next().await.unwrap().unwrap();
+        assert_eq!(first.title, "Emma");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        let rest: Vec<_> = stream.try_collect().await.unwrap();
+        assert_eq!(
+            rest.into_iter().map(|book| book.title).collect::<Vec<_>>(),
+            TITLES[2..]
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn link_query() {
+        let (url, requests) = server(|target| {
+            if target.contains("p=1") {
+                // Echo the query of the followed link.
+                (String::new(), format!(r#"[{{"title":"{target}"}}]"#))
+            } else {
+                let link = "Link: </books?title=Emma&api_key=key&p=1>; rel=\"next\"\r\n";
+                (link.to_owned(), books(0..1))
+            }
+        });
+        let mut source = Builder::<TestBook>::new()
+            .source_url(url)
+            .unwrap()
+            .decoder(Json)
+            .api_key_param("api_key", "key")
+            .pagination(Pagination::link())
+            .build();
+
+        let fetched = source
+            .fetch_all(&TestBook::title().eq("Emma"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            fetched,
+            ["Emma", "/books?title=Emma&api_key=key&p=1"].map(|title| TestBook {
+                title: title.to_owned()
+            })
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn foreign_link() {
+        let (url, requests) = server(|_| {
+            let link = "Link: <http://example.invalid/books?p=1>; rel=\"next\"\r\n";
+            (link.to_owned(), books(0..1))
+        });
+        let mut source = Builder::<TestBook>::new()
+            .source_url(url)
+            .unwrap()
+            .decoder(Json)
+            .api_key_param("api_key", "key")
+            .pagination(Pagination::link())
+            .build();
+
+        let err = source.fetch_all(&True).await.unwrap_err();
+
+        assert!(matches!(
+            err,
+            FetchError::Connection(ConnectionError::Process(_))
+        ));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn link_header() {
+        let base = Url::parse("http://localhost/books?p=1").unwrap();
+
+        assert_eq!(
+            next_link(r#"<?p=0>; rel="prev first", <?p=2>; rel="next""#, &base).unwrap(),
+            Url::parse("http://localhost/books?p=2").unwrap()
+        );
+        assert_eq!(
+            next_link("<http://example.com/a>;REL=NEXT", &base).unwrap(),
+            Url::parse("http://example.com/a").unwrap()
+        );
+        assert!(next_link(r#"<?p=0>; rel="prev""#, &base).is_none());
+    }
+}
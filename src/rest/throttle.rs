@@ -0,0 +1,121 @@
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::time::{Instant, sleep_until};
+
+/// A token bucket limiting the rate of requests.
+///
+/// The bucket holds up to [`burst`](Self::burst) tokens and is refilled at a constant rate. Every
+/// request, including retries and every page, takes a token. When the bucket is empty, requests
+/// wait for a token rather than fail, and are let through in the order they arrived.
+///
+/// Clones share the same bucket. To limit the requests of several connectors hitting the same
+/// host, give each of them a clone of the same limiter.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    /// The interval at which tokens are added.
+    interval: Duration,
+    /// The maximum number of tokens.
+    burst: u32,
+    /// The bucket, shared by clones.
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+/// The state of a [`RateLimiter`].
+#[derive(Debug)]
+struct Bucket {
+    /// When the next request is due if requests were evenly spaced. Requests may be sent up to
+    /// `burst - 1` intervals before they are due.
+    next: Instant,
+}
+
+impl RateLimiter {
+    /// Allows `requests` per `period` on average, such as 5 requests per second. Up to `requests`
+    /// may be sent at once after a pause; see [`burst`](Self::burst).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests` is zero.
+    #[must_use]
+    #[inline]
+    pub fn new(requests: u32, period: Duration) -> Self {
+        assert!(requests > 0, "At least one request must be allowed.");
+        Self {
+            interval: period / requests,
+            burst: requests,
+            bucket: Arc::new(Mutex::new(Bucket {
+                next: Instant::now(),
+            })),
+        }
+    }
+
+    /// Allows `requests` per second on average. See [`new`](Self::new).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests` is zero.
+    #[must_use]
+    #[inline]
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    /// Sets the maximum number of requests sent at once after a pause. Zero is treated as one,
+    /// spacing all requests evenly.
+    #[must_use]
+    #[inline]
+    pub fn burst(self, burst: u32) -> Self {
+        Self { burst, ..self }
+    }
+
+    /// Wait for a token.
+    pub(super) async fn acquire(&self) {
+        let allowance = self.interval * self.burst.saturating_sub(1);
+        let due = {
+            let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+            let due = bucket.next.max(Instant::now());
+            bucket.next = due + self.interval;
+            due
+        };
+        // The burst allows requests to be sent early.
+        if let Some(at) = due.checked_sub(allowance) {
+            sleep_until(at).await;
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    clippy::unwrap_used,
+    reason = "Panics simply indicate failed tests."
+)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn limited() {
+        let limiter = RateLimiter::new(2, Duration::from_millis(100));
+
+        let start = Instant::now();
+        for _ in 0..2 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        let shared = limiter.clone();
+        shared.acquire().await;
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn evenly_spaced() {
+        let limiter = RateLimiter::per_second(100).burst(0);
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
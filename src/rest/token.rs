@@ -0,0 +1,346 @@
+use super::{
+    InvalidUrl,
+    auth::{Secret, check_status},
+};
+use crate::errors::ConnectionError;
+use async_trait::async_trait;
+use reqwest::{Client, IntoUrl, Url};
+use serde::Deserialize;
+use serde_json::from_slice;
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Provides access tokens, sent as bearer tokens with every request.
+///
+/// Connectors request a token before every request, so providers should cache them. If a request
+/// is rejected with [`ConnectionError::Http`] and code 401, the connector
+/// [invalidates](Self::invalidate) the token and retries the request once with a new one.
+#[async_trait]
+pub trait TokenProvider: Debug + Send + Sync {
+    /// A token that is currently valid, fetching a new one if needed.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a new token is needed but can not be fetched.
+    async fn token(&self) -> Result<Box<str>, ConnectionError>;
+
+    /// Discard `token`, which was rejected, such that the next call to [`token`](Self::token)
+    /// fetches a new one. A different token, such as one refreshed concurrently, is kept.
+    async fn invalidate(&self, token: &str);
+}
+
+/// The OAuth 2.0 client credentials flow, as specified by [RFC 6749 §4.4].
+///
+/// Tokens are requested from the token endpoint using basic authentication with the client ID and
+/// secret. They are cached until [`refresh_margin`](Self::refresh_margin) before they expire, after
+/// which a new token is fetched. Tokens without a stated lifetime are kept until rejected.
+///
+/// [RFC 6749 §4.4]: https://www.rfc-editor.org/rfc/rfc6749#section-4.4
+#[derive(Debug)]
+pub struct ClientCredentials {
+    /// The URL of the token endpoint.
+    token_url: Url,
+    /// The client identifier.
+    client_id: Box<str>,
+    /// The client secret.
+    client_secret: Secret,
+    /// The scope requested, if any.
+    scope: Option<Box<str>>,
+    /// How long before expiry tokens are refreshed.
+    refresh_margin: Duration,
+    /// The client used to request tokens.
+    client: Client,
+    /// The current token, if any. Locked while refreshing, such that concurrent requests wait for
+    /// the same token.
+    cache: Mutex<Option<CachedToken>>,
+}
+
+/// A token fetched by [`ClientCredentials`].
+#[derive(Debug)]
+struct CachedToken {
+    /// The access token.
+    token: Secret,
+    /// When the token should be refreshed, if ever.
+    refresh_at: Option<Instant>,
+}
+
+/// A successful response from a token endpoint.
+#[derive(Deserialize)]
+struct TokenResponse {
+    /// The access token.
+    access_token: String,
+    /// The lifetime of the token in seconds, if stated.
+    expires_in: Option<u64>,
+}
+
+impl ClientCredentials {
+    /// The default value of [`refresh_margin`](Self::refresh_margin).
+    pub const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(30);
+
+    /// Request tokens from `token_url`, identifying as `client_id` using `client_secret`.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`InvalidUrl`] if the URL fails to parse.
+    #[expect(
+        clippy::map_err_ignore,
+        reason = "`reqwest::Error` exposes no useful information about the error."
+    )]
+    #[inline]
+    pub fn new<U: IntoUrl>(
+        token_url: U,
+        client_id: impl Into<Box<str>>,
+        client_secret: impl Into<Box<str>>,
+    ) -> Result<Self, InvalidUrl> {
+        Ok(Self {
+            token_url: token_url.into_url().map_err(|_| InvalidUrl)?,
+            client_id: client_id.into(),
+            client_secret: Secret(client_secret.into()),
+            scope: None,
+            refresh_margin: Self::DEFAULT_REFRESH_MARGIN,
+            client: Client::new(),
+            cache: Mutex::new(None),
+        })
+    }
+
+    /// Requests tokens for `scope`, a space-separated list of scopes.
+    #[must_use]
+    #[inline]
+    pub fn scope(self, scope: impl Into<Box<str>>) -> Self {
+        Self {
+            scope: Some(scope.into()),
+            ..self
+        }
+    }
+
+    /// Sets how long before expiry tokens are refreshed. Defaults to
+    /// [`DEFAULT_REFRESH_MARGIN`](Self::DEFAULT_REFRESH_MARGIN).
+    #[must_use]
+    #[inline]
+    pub fn refresh_margin(self, refresh_margin: Duration) -> Self {
+        Self {
+            refresh_margin,
+            ..self
+        }
+    }
+
+    /// Sets the [`Client`] used to request tokens.
+    #[must_use]
+    #[inline]
+    pub fn client(self, client: Client) -> Self {
+        Self { client, ..self }
+    }
+
+    /// Request a new token from the token endpoint.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the request fails, or if the response is not a token.
+    async fn fetch(&self) -> Result<CachedToken, ConnectionError> {
+        let mut form = vec![("grant_type", "client_credentials")];
+        if let Some(scope) = &self.scope {
+            form.push(("scope", scope));
+        }
+
+        let requested = Instant::now();
+        let response = self
+            .client
+            .post(self.token_url.clone())
+            .basic_auth(&self.client_id, Some(&self.client_secret.0))
+            .form(&form)
+            .send()
+            .await?;
+        let bytes = check_status(response)?.bytes().await?;
+        let TokenResponse {
+            access_token,
+            expires_in,
+        } = from_slice(&bytes).map_err(|err| ConnectionError::Process(Box::new(err)))?;
+
+        Ok(CachedToken {
+            token: Secret(access_token.into()),
+            refresh_at: expires_in
+                .and_then(|seconds| requested.checked_add(Duration::from_secs(seconds)))
+                .map(|expiry| expiry.checked_sub(self.refresh_margin).unwrap_or(requested)),
+        })
+    }
+}
+
+#[async_trait]
+impl TokenProvider for ClientCredentials {
+    #[expect(
+        clippy::significant_drop_tightening,
+        reason = "The lock is held while refreshing, such that concurrent requests wait for it."
+    )]
+    #[inline]
+    async fn token(&self) -> Result<Box<str>, ConnectionError> {
+        let mut cache = self.cache.lock().await;
+        if let Some(cached) = &*cache
+            && cached
+                .refresh_at
+                .is_none_or(|refresh_at| Instant::now() < refresh_at)
+        {
+            return Ok(cached.token.0.clone());
+        }
+
+        let fetched = self.fetch().await?;
+        let token = fetched.token.0.clone();
+        *cache = Some(fetched);
+        Ok(token)
+    }
+
+    #[inline]
+    async fn invalidate(&self, token: &str) {
+        let mut cache = self.cache.lock().await;
+        if cache
+            .as_ref()
+            .is_some_and(|cached| *cached.token.0 == *token)
+        {
+            *cache = None;
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    clippy::unwrap_used,
+    reason = "Panics simply indicate failed tests."
+)]
+mod tests {
+    use super::*;
+    use crate::{
+        connector::Source as _,
+        encode::json::Json,
+        query::combinators::True,
+        rest::{Build as _, Builder},
+    };
+    use std::io::{BufRead as _, BufReader, Read as _, Write as _};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+    use std::thread::spawn;
+
+    #[derive(Debug, PartialEq, Eq, serde::Deserialize)]
+    struct TestBook {
+        title: String,
+    }
+
+    /// A stand-in server issuing tokens numbered from 1 at `/token`, which expire after
+    /// `expires_in` seconds. `/books` rejects the first token. Returns the base URL and the number
+    /// of tokens issued.
+    fn server(expires_in: u64) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let issued = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&issued);
+
+        drop(spawn(move || {
+            for stream in listener.incoming() {
+                respond(stream.unwrap(), &counter, expires_in);
+            }
+        }));
+        (url, issued)
+    }
+
+    /// Respond to a single request.
+    fn respond(mut stream: TcpStream, issued: &AtomicUsize, expires_in: u64) {
+        let mut reader = BufReader::new(&mut stream);
+        let mut head = Vec::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap() == 0 || line.trim_end().is_empty() {
+                break;
+            }
+            head.push(line.trim_end().to_ascii_lowercase());
+        }
+        let length = head
+            .iter()
+            .find_map(|line| line.strip_prefix("content-length: "))
+            .map_or(0, |length| length.parse().unwrap());
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        let (status, content) = if head[0].starts_with("post /token") {
+            assert!(
+                head.iter()
+                    .any(|line| line.starts_with("authorization: basic "))
+            );
+            assert_eq!(body, b"grant_type=client_credentials&scope=books");
+            let number = issued.fetch_add(1, Ordering::SeqCst) + 1;
+            (
+                "200 OK",
+                format!(r#"{{"access_token":"token-{number}","expires_in":{expires_in}}}"#),
+            )
+        } else if head
+            .iter()
+            .any(|line| line == "authorization: bearer token-1")
+        {
+            ("401 Unauthorized", String::new())
+        } else {
+            ("200 OK", r#"[{"title":"Emma"}]"#.to_owned())
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{content}",
+            content.len()
+        )
+        .unwrap();
+    }
+
+    fn provider(url: &str) -> ClientCredentials {
+        ClientCredentials::new(format!("{url}/token"), "client", "s3cr3t")
+            .unwrap()
+            .scope("books")
+    }
+
+    #[tokio::test]
+    async fn cached() {
+        let (url, issued) = server(3600);
+        let provider = provider(&url);
+
+        assert_eq!(&*provider.token().await.unwrap(), "token-1");
+        assert_eq!(&*provider.token().await.unwrap(), "token-1");
+        assert_eq!(issued.load(Ordering::SeqCst), 1);
+
+        provider.invalidate("token-0").await;
+        assert_eq!(&*provider.token().await.unwrap(), "token-1");
+        provider.invalidate("token-1").await;
+        assert_eq!(&*provider.token().await.unwrap(), "token-2");
+        assert!(!format!("{provider:?}").contains("s3cr3t"));
+    }
+
+    #[tokio::test]
+    async fn refreshed_before_expiry() {
+        let (url, issued) = server(10);
+        let provider = provider(&url).refresh_margin(Duration::from_secs(20));
+
+        assert_eq!(&*provider.token().await.unwrap(), "token-1");
+        assert_eq!(&*provider.token().await.unwrap(), "token-2");
+        assert_eq!(issued.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn retried_when_unauthorized() {
+        let (url, issued) = server(3600);
+        let mut source = Builder::<TestBook>::new()
+            .source_url(format!("{url}/books"))
+            .unwrap()
+            .decoder(Json)
+            .token_provider(provider(&url))
+            .build();
+
+        let books = source.fetch_all(&True).await.unwrap();
+
+        assert_eq!(
+            books,
+            [TestBook {
+                title: "Emma".to_owned()
+            }]
+        );
+        assert_eq!(issued.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,358 @@
+//! Retrying failed requests with exponential backoff.
+
+use crate::errors::ConnectionError;
+use std::hash::{BuildHasher as _, RandomState};
+use std::time::Duration;
+#[cfg(any(feature = "rest", feature = "postgres"))]
+use tokio::time::sleep;
+
+/// How connectors retry requests failing with transient errors.
+///
+/// After a failed attempt, the connector waits before trying again. The delay starts at
+/// [`initial_delay`](Self::initial_delay) and doubles with every retry, up to
+/// [`max_delay`](Self::max_delay). With [`jitter`](Self::jitter), a random delay of between half
+/// and all of that is used instead, such that clients failing at the same time spread out their
+/// retries.
+///
+/// Requests that are not idempotent, such as most `POST` requests, are never retried unless
+/// [allowed](Self::non_idempotent), since the server may have processed them before failing.
+#[derive(Clone, Copy, Debug)]
+pub struct Policy {
+    /// The maximum number of attempts, including the first.
+    #[cfg_attr(
+        not(any(feature = "rest", feature = "postgres")),
+        expect(dead_code, reason = "Only read by connectors.")
+    )]
+    max_attempts: u32,
+    /// The delay before the first retry.
+    initial_delay: Duration,
+    /// The maximum delay between attempts.
+    max_delay: Duration,
+    /// Whether delays are randomized.
+    jitter: bool,
+    /// Decides which errors are retried.
+    retryable: fn(&ConnectionError) -> bool,
+    /// Whether requests that are not idempotent are retried.
+    non_idempotent: bool,
+}
+
+#[cfg(any(feature = "rest", feature = "postgres"))]
+/// A failed attempt, as reported to [`Policy::run`].
+#[derive(Debug)]
+pub(crate) struct Failure<E> {
+    /// The error the attempt failed with.
+    pub(crate) error: E,
+    /// Whether the attempt may be retried, as decided by [`Policy::retries`].
+    pub(crate) retryable: bool,
+    /// The delay requested by the server before retrying, if any.
+    pub(crate) retry_after: Option<Duration>,
+}
+
+impl Policy {
+    /// The default value of [`initial_delay`](Self::initial_delay).
+    pub const DEFAULT_INITIAL_DELAY: Duration = Duration::from_millis(100);
+    /// The default value of [`max_delay`](Self::max_delay).
+    pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(10);
+
+    /// Constructs a policy making a single attempt, that is, never retrying. Retries are enabled
+    /// by setting [`max_attempts`](Self::max_attempts).
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_delay: Self::DEFAULT_INITIAL_DELAY,
+            max_delay: Self::DEFAULT_MAX_DELAY,
+            jitter: true,
+            retryable: transient,
+            non_idempotent: false,
+        }
+    }
+
+    /// Sets the maximum number of attempts, including the first. Zero is treated as one.
+    #[must_use]
+    #[inline]
+    pub const fn max_attempts(self, max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..self
+        }
+    }
+
+    /// Sets the delay before the first retry. Defaults to
+    /// [`DEFAULT_INITIAL_DELAY`](Self::DEFAULT_INITIAL_DELAY).
+    #[must_use]
+    #[inline]
+    pub const fn initial_delay(self, initial_delay: Duration) -> Self {
+        Self {
+            initial_delay,
+            ..self
+        }
+    }
+
+    /// Sets the maximum delay between attempts. Defaults to
+    /// [`DEFAULT_MAX_DELAY`](Self::DEFAULT_MAX_DELAY).
+    ///
+    /// If the server asks for a longer delay using `Retry-After`, the request fails instead.
+    #[must_use]
+    #[inline]
+    pub const fn max_delay(self, max_delay: Duration) -> Self {
+        Self { max_delay, ..self }
+    }
+
+    /// Sets whether delays are randomized. Defaults to `true`.
+    #[must_use]
+    #[inline]
+    pub const fn jitter(self, jitter: bool) -> Self {
+        Self { jitter, ..self }
+    }
+
+    /// Sets which errors are retried. Defaults to [`transient`].
+    #[must_use]
+    #[inline]
+    pub const fn retryable(self, retryable: fn(&ConnectionError) -> bool) -> Self {
+        Self { retryable, ..self }
+    }
+
+    /// Sets whether requests that are not idempotent are retried. Defaults to `false`.
+    ///
+    /// Only enable this if the server deduplicates requests, or if duplicates are harmless.
+    #[must_use]
+    #[inline]
+    pub const fn non_idempotent(self, non_idempotent: bool) -> Self {
+        Self {
+            non_idempotent,
+            ..self
+        }
+    }
+
+    /// The delay before retry number `retry`, counting from 1, ignoring any delay requested by the
+    /// server. With [`jitter`](Self::jitter), this is random.
+    #[must_use]
+    #[inline]
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exponential = self
+            .initial_delay
+            .saturating_mul(2_u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+        if self.jitter {
+            exponential.mul_f64(random().mul_add(0.5, 0.5))
+        } else {
+            exponential
+        }
+    }
+
+    /// Whether a request failing with `error` may be retried. Requests that are not `idempotent`
+    /// are only retried if [allowed](Self::non_idempotent).
+    #[must_use]
+    #[inline]
+    pub fn retries(&self, error: &ConnectionError, idempotent: bool) -> bool {
+        (idempotent || self.non_idempotent) && (self.retryable)(error)
+    }
+
+    /// Run `attempt` until it succeeds, fails with an error that may not be retried, or the
+    /// maximum number of attempts is reached, waiting between attempts.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the error of the last attempt.
+    #[cfg(any(feature = "rest", feature = "postgres"))]
+    pub(crate) async fn run<R, E, F, Fut>(&self, mut attempt: F) -> Result<R, E>
+    where
+        F: FnMut() -> Fut + Send,
+        Fut: Future<Output = Result<R, Failure<E>>> + Send,
+    {
+        let mut attempts = 1;
+        loop {
+            let Failure {
+                error,
+                retryable,
+                retry_after,
+            } = match attempt().await {
+                Ok(output) => return Ok(output),
+                Err(failure) => failure,
+            };
+            if !retryable || attempts >= self.max_attempts {
+                return Err(error);
+            }
+
+            let delay = match retry_after {
+                Some(delay) if delay > self.max_delay => return Err(error),
+                Some(delay) => delay,
+                None => self.backoff(attempts),
+            };
+            drop(error);
+            sleep(delay).await;
+            attempts += 1;
+        }
+    }
+}
+
+impl Default for Policy {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(any(feature = "rest", feature = "postgres"))]
+impl<E> Failure<E> {
+    /// A failure without a delay requested by the server.
+    pub(crate) const fn new(error: E, retryable: bool) -> Self {
+        Self {
+            error,
+            retryable,
+            retry_after: None,
+        }
+    }
+}
+
+/// Whether `error` is likely to be transient: timeouts, IO errors, and the HTTP codes 429 (Too
+/// Many Requests), 502 (Bad Gateway), 503 (Service Unavailable) and 504 (Gateway Timeout).
+#[must_use]
+#[inline]
+pub const fn transient(error: &ConnectionError) -> bool {
+    match error {
+        ConnectionError::TimedOut
+        | ConnectionError::Io(_)
+        | ConnectionError::Http {
+            code: 429 | 502 | 503 | 504,
+            ..
+        } => true,
+        ConnectionError::Http { .. } | ConnectionError::Redirect | ConnectionError::Process(_) => {
+            false
+        },
+    }
+}
+
+/// A random number in `[0, 1]`.
+fn random() -> f64 {
+    RandomState::new().hash_one(()) as f64 / u64::MAX as f64
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::missing_panics_doc,
+    clippy::unwrap_used,
+    reason = "Panics simply indicate failed tests."
+)]
+mod tests {
+    use super::*;
+    use std::io::Error as IoError;
+    #[cfg(any(feature = "rest", feature = "postgres"))]
+    use std::iter::repeat_with;
+
+    #[cfg(any(feature = "rest", feature = "postgres"))]
+    /// Run `policy`, failing with `errors` in order before succeeding, and return the result and
+    /// the number of attempts made.
+    async fn run(
+        policy: Policy,
+        errors: Vec<(ConnectionError, Option<Duration>)>,
+    ) -> (Result<(), ConnectionError>, usize) {
+        let mut errors = errors.into_iter();
+        let mut attempts = 0;
+        let result = policy
+            .run(|| {
+                attempts += 1;
+                let next = errors.next();
+                async move {
+                    match next {
+                        Some((error, retry_after)) => Err(Failure {
+                            retryable: policy.retries(&error, true),
+                            error,
+                            retry_after,
+                        }),
+                        None => Ok(()),
+                    }
+                }
+            })
+            .await;
+        (result, attempts)
+    }
+
+    fn http(code: u16) -> ConnectionError {
+        ConnectionError::Http {
+            code,
+            source: Box::new(IoError::other("failed")),
+        }
+    }
+
+    #[test]
+    fn backoff() {
+        let policy = Policy::new()
+            .initial_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(500))
+            .jitter(false);
+        let delays: Vec<_> = (1..=5).map(|retry| policy.backoff(retry)).collect();
+        assert_eq!(delays, [100, 200, 400, 500, 500].map(Duration::from_millis));
+
+        let jittered = policy.jitter(true);
+        for retry in 1..=5 {
+            let delay = jittered.backoff(retry);
+            assert!(policy.backoff(retry) / 2 <= delay && delay <= policy.backoff(retry));
+        }
+    }
+
+    #[test]
+    fn retryable() {
+        let policy = Policy::new();
+        assert!(policy.retries(&ConnectionError::TimedOut, true));
+        assert!(policy.retries(&http(503), true));
+        assert!(!policy.retries(&http(503), false));
+        assert!(!policy.retries(&http(404), true));
+        assert!(!policy.retries(&ConnectionError::Redirect, true));
+        assert!(policy.non_idempotent(true).retries(&http(429), false));
+    }
+
+    #[cfg(any(feature = "rest", feature = "postgres"))]
+    #[tokio::test]
+    async fn attempts() {
+        let policy = Policy::new()
+            .max_attempts(3)
+            .initial_delay(Duration::from_millis(1));
+
+        let (succeeded, attempts) = run(
+            policy,
+            vec![(http(502), None), (ConnectionError::TimedOut, None)],
+        )
+        .await;
+        succeeded.unwrap();
+        assert_eq!(attempts, 3);
+
+        let (exhausted, exhausted_attempts) =
+            run(policy, repeat_with(|| (http(502), None)).take(3).collect()).await;
+        assert!(matches!(
+            exhausted,
+            Err(ConnectionError::Http { code: 502, .. })
+        ));
+        assert_eq!(exhausted_attempts, 3);
+
+        let (rejected, rejected_attempts) = run(policy, vec![(http(400), None)]).await;
+        assert!(matches!(
+            rejected,
+            Err(ConnectionError::Http { code: 400, .. })
+        ));
+        assert_eq!(rejected_attempts, 1);
+    }
+
+    #[cfg(any(feature = "rest", feature = "postgres"))]
+    #[tokio::test]
+    async fn retry_after() {
+        let policy = Policy::new()
+            .max_attempts(2)
+            .max_delay(Duration::from_millis(50));
+
+        let (honoured, attempts) =
+            run(policy, vec![(http(429), Some(Duration::from_millis(1)))]).await;
+        honoured.unwrap();
+        assert_eq!(attempts, 2);
+
+        let (too_long, too_long_attempts) =
+            run(policy, vec![(http(429), Some(Duration::from_secs(60)))]).await;
+        assert!(matches!(
+            too_long,
+            Err(ConnectionError::Http { code: 429, .. })
+        ));
+        assert_eq!(too_long_attempts, 1);
+    }
+}